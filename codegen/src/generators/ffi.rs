@@ -1,42 +1,219 @@
 use anyhow::Result;
 use std::path::Path;
 use std::fs;
+use std::str::FromStr;
 
-pub fn generate(lang: &str, output: &Path) -> Result<()> {
+/// C prototypes of the functions exported by `core/src/lib.rs`.
+/// Generators that need raw declarations render them from this list so
+/// the signatures only have to be updated in one place.
+const FFI_PROTOTYPES: &[&str] = &[
+    "void* zenith_init(uint32_t buffer_size);",
+    "void zenith_free(void* engine_ptr);",
+    "int32_t zenith_publish(void* engine_ptr, void* array_ptr, void* schema_ptr, uint32_t source_id, uint64_t seq_no);",
+    "int32_t zenith_publish_blocking(void* engine_ptr, void* array_ptr, void* schema_ptr, uint32_t source_id, uint64_t seq_no, uint64_t timeout_ms);",
+    "int32_t zenith_set_expected_schema(void* engine_ptr, const void* schema_ptr);",
+    "int32_t zenith_set_dictionary_decoding(void* engine_ptr, uint32_t decode);",
+    "int32_t zenith_publish_ipc(void* engine_ptr, const uint8_t* ipc_bytes, size_t len, uint32_t source_id, uint64_t seq_no);",
+    "int32_t zenith_load_plugin(void* engine_ptr, const uint8_t* wasm_bytes, size_t len);",
+    "int32_t zenith_load_plugin_file(void* engine_ptr, const char* path);",
+    "int32_t zenith_load_plugin_limited(void* engine_ptr, const uint8_t* wasm_bytes, size_t len, uint64_t max_memory_bytes, uint64_t cpu_timeout_ms, uint32_t max_host_calls);",
+    "uint8_t* zenith_precompile_plugin(const uint8_t* wasm_bytes, size_t len, size_t* out_len);",
+    "void zenith_free_buffer(uint8_t* ptr, size_t len);",
+    "int32_t zenith_load_precompiled_plugin(void* engine_ptr, const uint8_t* artifact, size_t len);",
+    "int32_t zenith_set_sink(void* engine_ptr, void (*callback)(void* user_data, uint32_t source_id, uint64_t seq_no, uint64_t timestamp_ns), void* user_data);",
+    "int32_t zenith_set_filter(void* engine_ptr, const char* expr);",
+    "int32_t zenith_stats(void* engine_ptr, ZenithEngineStats* out);",
+    "int32_t zenith_peek_header(void* engine_ptr, ZenithEventHeader* out);",
+    "int32_t zenith_init_logging(uint32_t level, uint32_t format);",
+    "int32_t zenith_set_log_level(uint32_t level);",
+    "int32_t zenith_generate_load(void* engine_ptr, uint32_t source_id, uint64_t events_per_second, uint64_t total_events, uint64_t* out_published, uint64_t* out_buffer_full);",
+];
+
+/// Mirror of the `#[repr(C)]` `EngineStats` in `core/src/engine.rs`
+const ENGINE_STATS_STRUCT: &str = "typedef struct ZenithEngineStats {
+    uint64_t buffer_len;
+    uint64_t buffer_capacity;
+    uint64_t plugins_loaded;
+    uint64_t events_processed;
+    uint64_t events_dropped;
+    uint64_t gaps_detected;
+    uint64_t plugin_breaker_trips;
+    uint64_t latency_p50_ns;
+    uint64_t latency_p95_ns;
+    uint64_t latency_p99_ns;
+} ZenithEngineStats;
+";
+
+/// Mirror of the `#[repr(C)]` `ZenithEventHeader` in `core/src/event.rs`
+const EVENT_HEADER_STRUCT: &str = "typedef struct ZenithEventHeader {
+    uint32_t source_id;
+    uint64_t seq_no;
+    uint64_t ingest_ns;
+    uint64_t num_rows;
+} ZenithEventHeader;
+";
+
+/// Mirror of `ffi_error` in `core/src/lib.rs`: (name, code, description).
+const FFI_ERROR_CODES: &[(&str, i32, &str)] = &[
+    ("SUCCESS", 0, "success"),
+    ("NULL_POINTER", -1, "null pointer"),
+    ("BUFFER_FULL", -2, "buffer full"),
+    ("PANIC", -3, "panic in engine"),
+    ("FFI_ERROR", -4, "FFI/Arrow error"),
+    ("INIT_FAILED", -5, "initialization failed"),
+    ("PLUGIN_LOAD_FAILED", -7, "plugin could not be loaded"),
+    ("PLUGIN_VALIDATION_FAILED", -8, "plugin rejected by validation"),
+    ("INVALID_FILTER", -9, "invalid filter expression"),
+    ("INVALID_LIMITS", -10, "invalid sandbox limits"),
+    ("INCOMPATIBLE_ARTIFACT", -11, "incompatible precompiled module"),
+    ("INVALID_LOG_LEVEL", -12, "invalid log level or format"),
+    ("SCHEMA_MISMATCH", -13, "batch schema does not match the expected schema"),
+    ("DICTIONARY_REJECTED", -14, "dictionary-encoded columns rejected"),
+    ("INVALID_PATH", -15, "invalid or unsafe file path"),
+    ("FILE_READ_FAILED", -16, "file could not be read"),
+];
+
+fn c_prototypes() -> String {
+    FFI_PROTOTYPES.join("\n")
+}
+
+/// The prototype in `FFI_PROTOTYPES` declaring `name`
+fn c_prototype(name: &str) -> &'static str {
+    FFI_PROTOTYPES
+        .iter()
+        .find(|prototype| {
+            let head = prototype.split('(').next().unwrap_or_default();
+            head.rsplit([' ', '*']).next() == Some(name)
+        })
+        .unwrap_or_else(|| panic!("no FFI prototype for {}", name))
+}
+
+/// Platform the generated bindings will load `zenith_core` on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetOs {
+    Linux,
+    MacOs,
+    Windows,
+}
+
+impl TargetOs {
+    /// The platform codegen itself is running on
+    pub fn host() -> Self {
+        match std::env::consts::OS {
+            "macos" => Self::MacOs,
+            "windows" => Self::Windows,
+            _ => Self::Linux,
+        }
+    }
+
+    /// File name of the core shared library on this platform
+    pub fn library_file_name(self) -> &'static str {
+        match self {
+            Self::Linux => "libzenith_core.so",
+            Self::MacOs => "libzenith_core.dylib",
+            Self::Windows => "zenith_core.dll",
+        }
+    }
+}
+
+impl FromStr for TargetOs {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "linux" => Ok(Self::Linux),
+            "macos" | "darwin" => Ok(Self::MacOs),
+            "windows" => Ok(Self::Windows),
+            _ => Err(anyhow::anyhow!("Unsupported target OS: {} (expected linux, macos or windows)", s)),
+        }
+    }
+}
+
+/// Where the generated bindings look for the core library.
+#[derive(Debug, Clone)]
+pub struct FfiOptions {
+    /// Directory containing the library; `None` keeps each language's
+    /// default relative to the repo layout
+    pub lib_dir: Option<String>,
+    /// Decides the library file name
+    pub target_os: TargetOs,
+}
+
+impl Default for FfiOptions {
+    fn default() -> Self {
+        Self {
+            lib_dir: None,
+            target_os: TargetOs::host(),
+        }
+    }
+}
+
+impl FfiOptions {
+    fn lib_dir<'a>(&'a self, default: &'a str) -> &'a str {
+        self.lib_dir.as_deref().unwrap_or(default).trim_end_matches(['/', '\\'])
+    }
+
+    /// Full default library path for runtime loaders (Python, Node, Deno)
+    fn lib_path(&self, default_dir: &str) -> String {
+        format!("{}/{}", self.lib_dir(default_dir), self.target_os.library_file_name())
+    }
+}
+
+/// Default library location for loaders run from the repo root
+const DEFAULT_LIB_DIR: &str = "./core/target/release";
+
+pub fn generate(lang: &str, output: &Path, options: &FfiOptions) -> Result<()> {
     match lang {
-        "go" => generate_go_bindings(output),
-        "python" => generate_python_bindings(output),
-        "node" => generate_node_bindings(output),
+        "c" => generate_c_header(output),
+        "go" => generate_go_bindings(output, options),
+        "python" => generate_python_bindings(output, options),
+        "python-cffi" => generate_python_cffi_bindings(output, options),
+        "node" => generate_node_bindings(output, options),
+        "typescript" => generate_typescript_bindings(output, options),
         _ => Err(anyhow::anyhow!("Unsupported language: {}", lang)),
     }
 }
 
-fn generate_go_bindings(output: &Path) -> Result<()> {
+fn generate_go_bindings(output: &Path, options: &FfiOptions) -> Result<()> {
     let go_code = r#"package zenith
 
 /*
-#cgo LDFLAGS: -L../../core/target/release -lzenith_core
+#cgo LDFLAGS: {{LDFLAGS}}
 #include <stdint.h>
 #include <stdlib.h>
 
+{{STRUCTS}}
 // Forward declarations
-void* zenith_init(uint32_t buffer_size);
-void zenith_free(void* engine_ptr);
-int32_t zenith_publish(void* engine_ptr, void* array_ptr, void* schema_ptr, uint32_t source_id, uint64_t seq_no);
-int32_t zenith_load_plugin(void* engine_ptr, const uint8_t* wasm_bytes, size_t len);
+{{PROTOTYPES}}
 */
 import "C"
 import (
 	"errors"
+	"fmt"
 	"unsafe"
 )
 
+// FFI error codes (see ffi_error in core/src/lib.rs)
+var errorReasons = map[int32]string{
+{{ERROR_CODES}}
+}
+
+func errorReason(code int32) string {
+	if reason, ok := errorReasons[code]; ok {
+		return reason
+	}
+	return "unknown error"
+}
+
 // Client wraps the Zenith core engine
 type Client struct {
 	enginePtr unsafe.Pointer
 }
 
-// NewClient creates a new Zenith client
+// NewClient creates a new Zenith client.
+//
+// cgo resolves the library at build time from the LDFLAGS above; set
+// CGO_LDFLAGS to link against a different location.
 func NewClient(bufferSize uint32) *Client {
 	ptr := C.zenith_init(C.uint32_t(bufferSize))
 	if ptr == nil {
@@ -64,7 +241,7 @@ func (c *Client) LoadPlugin(wasmBytes []byte) error {
 	
 	ret := C.zenith_load_plugin(c.enginePtr, cBytes, cLen)
 	if ret != 0 {
-		return errors.New("failed to load plugin")
+		return fmt.Errorf("failed to load plugin: %s (code %d)", errorReason(int32(ret)), int32(ret))
 	}
 	return nil
 }
@@ -76,11 +253,47 @@ func (c *Client) Publish(sourceID uint32, seqNo uint64) error {
 }
 "#;
 
+    let error_codes: Vec<String> = FFI_ERROR_CODES
+        .iter()
+        .filter(|(_, code, _)| *code != 0)
+        .map(|(_, code, description)| format!("\t{}: \"{}\",", code, description))
+        .collect();
+    // Go packages live two levels below the repo root
+    let lib_dir = options.lib_dir("../../core/target/release");
+    let ldflags = match options.target_os {
+        TargetOs::Windows => format!("-L{} -lzenith_core", lib_dir),
+        _ => format!("-L{dir} -lzenith_core -Wl,-rpath,{dir}", dir = lib_dir),
+    };
+    let go_code = go_code
+        .replace("{{LDFLAGS}}", &ldflags)
+        .replace("{{STRUCTS}}", &[ENGINE_STATS_STRUCT, EVENT_HEADER_STRUCT].join("\n"))
+        .replace("{{PROTOTYPES}}", &c_prototypes())
+        .replace("{{ERROR_CODES}}", &error_codes.join("\n"));
     fs::write(output.join("zenith.go"), go_code)?;
     Ok(())
 }
 
-fn generate_python_bindings(output: &Path) -> Result<()> {
+/// Body of the `ZENITH_ERRORS` dict in the Python clients
+fn python_error_codes() -> String {
+    let error_codes: Vec<String> = FFI_ERROR_CODES
+        .iter()
+        .filter(|(_, code, _)| *code != 0)
+        .map(|(_, code, description)| format!("    {}: \"{}\",", code, description))
+        .collect();
+    error_codes.join("\n")
+}
+
+/// Body of the `ZENITH_ERRORS` object in the JavaScript clients
+fn js_error_codes() -> String {
+    let error_codes: Vec<String> = FFI_ERROR_CODES
+        .iter()
+        .filter(|(_, code, _)| *code != 0)
+        .map(|(_, code, description)| format!("  [{}]: \"{}\",", code, description))
+        .collect();
+    error_codes.join("\n")
+}
+
+fn generate_python_bindings(output: &Path, options: &FfiOptions) -> Result<()> {
     let py_code = r#"""
 Zenith Python SDK
 Auto-generated FFI bindings
@@ -88,8 +301,45 @@ Auto-generated FFI bindings
 import ctypes
 from typing import Optional
 
+# FFI error codes (see ffi_error in core/src/lib.rs)
+ZENITH_ERRORS = {
+{{ERROR_CODES}}
+}
+
+
+class ArrowSchema(ctypes.Structure):
+    """Arrow C Data Interface ArrowSchema struct"""
+    _fields_ = [
+        ("format", ctypes.c_char_p),
+        ("name", ctypes.c_char_p),
+        ("metadata", ctypes.c_char_p),
+        ("flags", ctypes.c_int64),
+        ("n_children", ctypes.c_int64),
+        ("children", ctypes.c_void_p),
+        ("dictionary", ctypes.c_void_p),
+        ("release", ctypes.c_void_p),
+        ("private_data", ctypes.c_void_p),
+    ]
+
+
+class ArrowArray(ctypes.Structure):
+    """Arrow C Data Interface ArrowArray struct"""
+    _fields_ = [
+        ("length", ctypes.c_int64),
+        ("null_count", ctypes.c_int64),
+        ("offset", ctypes.c_int64),
+        ("n_buffers", ctypes.c_int64),
+        ("n_children", ctypes.c_int64),
+        ("buffers", ctypes.c_void_p),
+        ("children", ctypes.c_void_p),
+        ("dictionary", ctypes.c_void_p),
+        ("release", ctypes.c_void_p),
+        ("private_data", ctypes.c_void_p),
+    ]
+
+
 class ZenithClient:
-    def __init__(self, lib_path: str = "./core/target/release/libzenith_core.so"):
+    def __init__(self, lib_path: str = "{{LIB_PATH}}"):
         self.lib = ctypes.CDLL(lib_path)
         
         # void* zenith_init(uint32_t buffer_size)
@@ -108,6 +358,16 @@ class ZenithClient:
         ]
         self.lib.zenith_load_plugin.restype = ctypes.c_int32
         
+        # int32_t zenith_publish(void* engine_ptr, void* array_ptr, void* schema_ptr, uint32_t source_id, uint64_t seq_no)
+        self.lib.zenith_publish.argtypes = [
+            ctypes.c_void_p,
+            ctypes.c_void_p,
+            ctypes.c_void_p,
+            ctypes.c_uint32,
+            ctypes.c_uint64
+        ]
+        self.lib.zenith_publish.restype = ctypes.c_int32
+        
         self.engine_ptr: Optional[int] = None
     
     def init(self, buffer_size: int = 1024):
@@ -126,7 +386,31 @@ class ZenithClient:
             len(wasm_bytes)
         )
         if ret != 0:
-            raise RuntimeError(f"Failed to load plugin: {wasm_path}")
+            reason = ZENITH_ERRORS.get(ret, "unknown error")
+            raise RuntimeError(f"Failed to load plugin {wasm_path}: {reason} (code {ret})")
+    
+    def publish(self, record_batch, source_id: int, seq_no: int):
+        """Publish a pyarrow.RecordBatch via the Arrow C Data Interface.
+
+        Ownership of the exported structs is transferred to the engine.
+        """
+        c_array = ArrowArray()
+        c_schema = ArrowSchema()
+        array_addr = ctypes.addressof(c_array)
+        schema_addr = ctypes.addressof(c_schema)
+        
+        record_batch._export_to_c(array_addr, schema_addr)
+        
+        ret = self.lib.zenith_publish(
+            self.engine_ptr,
+            array_addr,
+            schema_addr,
+            source_id,
+            seq_no
+        )
+        if ret != 0:
+            reason = ZENITH_ERRORS.get(ret, "unknown error")
+            raise RuntimeError(f"Failed to publish event: {reason} (code {ret})")
     
     def close(self):
         if self.engine_ptr:
@@ -140,11 +424,170 @@ class ZenithClient:
         self.close()
 "#;
 
+    let py_code = py_code
+        .replace("{{ERROR_CODES}}", &python_error_codes())
+        .replace("{{LIB_PATH}}", &options.lib_path(DEFAULT_LIB_DIR));
     fs::write(output.join("zenith_ffi.py"), py_code)?;
     Ok(())
 }
 
-fn generate_node_bindings(output: &Path) -> Result<()> {
+/// Functions the cffi client calls; the cdef declares just these
+const PYTHON_CFFI_FUNCTIONS: &[&str] = &["zenith_init", "zenith_free", "zenith_load_plugin", "zenith_publish"];
+
+/// Python bindings through cffi in out-of-line ABI mode.
+///
+/// `zenith_ffi_build.py` compiles the declarations into `_zenith_cffi`,
+/// which `zenith_cffi.py` loads. Unlike ctypes, cffi releases the GIL for
+/// the length of each call, so `publish` can run on an executor thread
+/// without stalling the event loop.
+fn generate_python_cffi_bindings(output: &Path, options: &FfiOptions) -> Result<()> {
+    let build_code = r#""""
+Zenith Python SDK
+Auto-generated cffi build script; run once to produce _zenith_cffi.py
+"""
+from cffi import FFI
+
+CDEF = """
+struct ArrowSchema {
+    const char* format;
+    const char* name;
+    const char* metadata;
+    int64_t flags;
+    int64_t n_children;
+    struct ArrowSchema** children;
+    struct ArrowSchema* dictionary;
+    void (*release)(struct ArrowSchema*);
+    void* private_data;
+};
+
+struct ArrowArray {
+    int64_t length;
+    int64_t null_count;
+    int64_t offset;
+    int64_t n_buffers;
+    int64_t n_children;
+    const void** buffers;
+    struct ArrowArray** children;
+    struct ArrowArray* dictionary;
+    void (*release)(struct ArrowArray*);
+    void* private_data;
+};
+
+{{PROTOTYPES}}
+"""
+
+ffibuilder = FFI()
+ffibuilder.cdef(CDEF)
+# ABI mode: no C compiler needed, the library is opened at runtime
+ffibuilder.set_source("_zenith_cffi", None)
+
+if __name__ == "__main__":
+    ffibuilder.compile(verbose=True)
+"#;
+
+    let py_code = r#""""
+Zenith Python SDK
+Auto-generated cffi bindings (build _zenith_cffi with zenith_ffi_build.py)
+"""
+import asyncio
+from concurrent.futures import Executor
+from typing import Optional
+
+from _zenith_cffi import ffi
+
+# FFI error codes (see ffi_error in core/src/lib.rs)
+ZENITH_ERRORS = {
+{{ERROR_CODES}}
+}
+
+
+class ZenithClient:
+    def __init__(self, lib_path: str = "{{LIB_PATH}}", executor: Optional[Executor] = None):
+        self.lib = ffi.dlopen(lib_path)
+        # None uses the event loop's default thread pool
+        self.executor = executor
+        self.engine_ptr = ffi.NULL
+    
+    def init(self, buffer_size: int = 1024):
+        self.engine_ptr = self.lib.zenith_init(buffer_size)
+        if self.engine_ptr == ffi.NULL:
+            raise RuntimeError("Failed to initialize Zenith Engine")
+        return self
+    
+    def load_plugin(self, wasm_path: str):
+        with open(wasm_path, 'rb') as f:
+            wasm_bytes = f.read()
+        
+        ret = self.lib.zenith_load_plugin(
+            self.engine_ptr,
+            ffi.from_buffer("uint8_t[]", wasm_bytes),
+            len(wasm_bytes)
+        )
+        if ret != 0:
+            reason = ZENITH_ERRORS.get(ret, "unknown error")
+            raise RuntimeError(f"Failed to load plugin {wasm_path}: {reason} (code {ret})")
+    
+    def publish_sync(self, record_batch, source_id: int, seq_no: int):
+        """Publish a pyarrow.RecordBatch via the Arrow C Data Interface.
+
+        Ownership of the exported structs is transferred to the engine.
+        The GIL is released while the engine takes the batch.
+        """
+        c_array = ffi.new("struct ArrowArray*")
+        c_schema = ffi.new("struct ArrowSchema*")
+        array_addr = int(ffi.cast("uintptr_t", c_array))
+        schema_addr = int(ffi.cast("uintptr_t", c_schema))
+        
+        record_batch._export_to_c(array_addr, schema_addr)
+        
+        ret = self.lib.zenith_publish(
+            self.engine_ptr,
+            c_array,
+            c_schema,
+            source_id,
+            seq_no
+        )
+        if ret != 0:
+            reason = ZENITH_ERRORS.get(ret, "unknown error")
+            raise RuntimeError(f"Failed to publish event: {reason} (code {ret})")
+    
+    async def publish(self, record_batch, source_id: int, seq_no: int):
+        """Publish from a coroutine; the call runs on the executor"""
+        loop = asyncio.get_running_loop()
+        await loop.run_in_executor(
+            self.executor,
+            self.publish_sync,
+            record_batch,
+            source_id,
+            seq_no
+        )
+    
+    def close(self):
+        if self.engine_ptr != ffi.NULL:
+            self.lib.zenith_free(self.engine_ptr)
+            self.engine_ptr = ffi.NULL
+    
+    def __enter__(self):
+        return self
+    
+    def __exit__(self, exc_type, exc_val, exc_tb):
+        self.close()
+"#;
+
+    let prototypes: Vec<&str> = PYTHON_CFFI_FUNCTIONS.iter().map(|name| c_prototype(name)).collect();
+    fs::write(
+        output.join("zenith_ffi_build.py"),
+        build_code.replace("{{PROTOTYPES}}", &prototypes.join("\n")),
+    )?;
+
+    let py_code = py_code
+        .replace("{{ERROR_CODES}}", &python_error_codes())
+        .replace("{{LIB_PATH}}", &options.lib_path(DEFAULT_LIB_DIR));
+    fs::write(output.join("zenith_cffi.py"), py_code)?;
+    Ok(())
+}
+
+fn generate_node_bindings(output: &Path, options: &FfiOptions) -> Result<()> {
     let js_code = r#"/**
  * Zenith Node.js SDK
  * Auto-generated FFI bindings
@@ -154,8 +597,17 @@ const ref = require('ref-napi');
 
 const voidPtr = ref.refType(ref.types.void);
 
+// FFI error codes (see ffi_error in core/src/lib.rs)
+const ZENITH_ERRORS = {
+{{ERROR_CODES}}
+};
+
+function errorReason(code) {
+  return ZENITH_ERRORS[code] || 'unknown error';
+}
+
 class ZenithClient {
-  constructor(libPath = './core/target/release/libzenith_core.so') {
+  constructor(libPath = '{{LIB_PATH}}') {
     this.lib = ffi.Library(libPath, {
       'zenith_init': [voidPtr, ['uint32']],
       'zenith_free': ['void', [voidPtr]],
@@ -184,7 +636,7 @@ class ZenithClient {
     );
     
     if (ret !== 0) {
-      throw new Error(`Failed to load plugin: ${wasmPath}`);
+      throw new Error(`Failed to load plugin ${wasmPath}: ${errorReason(ret)} (code ${ret})`);
     }
   }
 
@@ -199,6 +651,9 @@ class ZenithClient {
 module.exports = ZenithClient;
 "#;
 
+    let js_code = js_code
+        .replace("{{ERROR_CODES}}", &js_error_codes())
+        .replace("{{LIB_PATH}}", &options.lib_path(DEFAULT_LIB_DIR));
     fs::write(output.join("zenith.js"), js_code)?;
     
     // Also create package.json
@@ -217,3 +672,356 @@ module.exports = ZenithClient;
     
     Ok(())
 }
+
+fn generate_c_header(output: &Path) -> Result<()> {
+    let mut header = String::from(
+        "/*
+ * Zenith C API
+ * Auto-generated by zenith-codegen - do not edit by hand
+ */
+#ifndef ZENITH_H
+#define ZENITH_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern \"C\" {
+#endif
+
+/* FFI error codes */
+",
+    );
+
+    for (name, code, description) in FFI_ERROR_CODES {
+        header.push_str(&format!("#define ZENITH_{} ({}) /* {} */\n", name, code, description));
+    }
+
+    header.push('\n');
+    header.push_str(ENGINE_STATS_STRUCT);
+    header.push('\n');
+    header.push_str(EVENT_HEADER_STRUCT);
+    header.push_str("\n/* array_ptr/schema_ptr are Arrow C Data Interface ArrowArray/ArrowSchema structs */\n");
+    header.push_str(&c_prototypes());
+    header.push_str(
+        "
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* ZENITH_H */
+",
+    );
+
+    fs::write(output.join("zenith.h"), header)?;
+    Ok(())
+}
+
+fn generate_typescript_bindings(output: &Path, options: &FfiOptions) -> Result<()> {
+    let ts_code = r#"/**
+ * Zenith Deno/TypeScript SDK
+ * Auto-generated FFI bindings (Deno.dlopen)
+ */
+
+const symbols = {
+  // void* zenith_init(uint32_t buffer_size)
+  zenith_init: { parameters: ["u32"], result: "pointer" },
+  // void zenith_free(void* engine_ptr)
+  zenith_free: { parameters: ["pointer"], result: "void" },
+  // int32_t zenith_publish(void* engine_ptr, void* array_ptr, void* schema_ptr, uint32_t source_id, uint64_t seq_no)
+  zenith_publish: {
+    parameters: ["pointer", "pointer", "pointer", "u32", "u64"],
+    result: "i32",
+  },
+  // int32_t zenith_load_plugin(void* engine_ptr, const uint8_t* wasm_bytes, size_t len)
+  zenith_load_plugin: { parameters: ["pointer", "buffer", "usize"], result: "i32" },
+} as const;
+
+// FFI error codes (see ffi_error in core/src/lib.rs)
+const ZENITH_ERRORS: Record<number, string> = {
+{{ERROR_CODES}}
+};
+
+function errorReason(code: number): string {
+  return ZENITH_ERRORS[code] ?? "unknown error";
+}
+
+export class ZenithClient {
+  private lib: Deno.DynamicLibrary<typeof symbols>;
+  private enginePtr: Deno.PointerValue = null;
+
+  constructor(libPath: string = "{{LIB_PATH}}") {
+    this.lib = Deno.dlopen(libPath, symbols);
+  }
+
+  init(bufferSize: number = 1024): this {
+    this.enginePtr = this.lib.symbols.zenith_init(bufferSize);
+    if (this.enginePtr === null) {
+      throw new Error("Failed to initialize Zenith Engine");
+    }
+    return this;
+  }
+
+  loadPlugin(wasmPath: string): void {
+    const wasmBytes = Deno.readFileSync(wasmPath);
+
+    const ret = this.lib.symbols.zenith_load_plugin(
+      this.enginePtr,
+      wasmBytes,
+      BigInt(wasmBytes.length),
+    );
+
+    if (ret !== 0) {
+      throw new Error(`Failed to load plugin ${wasmPath}: ${errorReason(ret)} (code ${ret})`);
+    }
+  }
+
+  /**
+   * Publish a RecordBatch exported through the Arrow C Data Interface.
+   * Ownership of the ArrowArray/ArrowSchema structs passes to the engine.
+   */
+  publish(
+    arrayPtr: Deno.PointerValue,
+    schemaPtr: Deno.PointerValue,
+    sourceId: number,
+    seqNo: bigint,
+  ): void {
+    const ret = this.lib.symbols.zenith_publish(
+      this.enginePtr,
+      arrayPtr,
+      schemaPtr,
+      sourceId,
+      seqNo,
+    );
+
+    if (ret !== 0) {
+      throw new Error(`Failed to publish event: ${errorReason(ret)} (code ${ret})`);
+    }
+  }
+
+  close(): void {
+    if (this.enginePtr !== null) {
+      this.lib.symbols.zenith_free(this.enginePtr);
+      this.enginePtr = null;
+    }
+    this.lib.close();
+  }
+}
+
+export default ZenithClient;
+"#;
+
+    let ts_code = ts_code
+        .replace("{{ERROR_CODES}}", &js_error_codes())
+        .replace("{{LIB_PATH}}", &options.lib_path(DEFAULT_LIB_DIR));
+    fs::write(output.join("zenith.ts"), ts_code)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_output(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("zenith-codegen-ffi-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_unsupported_language() {
+        let dir = temp_output("unsupported");
+        assert!(generate("cobol", &dir, &FfiOptions::default()).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_c_header() {
+        let dir = temp_output("c");
+        generate("c", &dir, &FfiOptions::default()).unwrap();
+
+        let header = fs::read_to_string(dir.join("zenith.h")).unwrap();
+        assert!(header.contains("#ifndef ZENITH_H"));
+        assert!(header.contains("#define ZENITH_H"));
+        assert!(header.contains("#endif /* ZENITH_H */"));
+        assert!(header.contains("#define ZENITH_BUFFER_FULL (-2)"));
+        assert!(header.contains("#define ZENITH_PLUGIN_LOAD_FAILED (-7)"));
+        assert!(header.contains("#define ZENITH_PLUGIN_VALIDATION_FAILED (-8)"));
+        for prototype in FFI_PROTOTYPES {
+            assert!(header.contains(prototype), "missing prototype: {}", prototype);
+        }
+        assert!(header.contains("int32_t zenith_publish("));
+        assert!(header.find("} ZenithEngineStats;").unwrap() < header.find("zenith_stats(").unwrap(),
+            "struct must be declared before its use");
+        assert!(header.find("} ZenithEventHeader;").unwrap() < header.find("zenith_peek_header(").unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_go_bindings_share_prototypes() {
+        let dir = temp_output("go");
+        generate("go", &dir, &FfiOptions::default()).unwrap();
+
+        let code = fs::read_to_string(dir.join("zenith.go")).unwrap();
+        assert!(!code.contains("{{PROTOTYPES}}"));
+        assert!(!code.contains("{{STRUCTS}}"));
+        assert!(!code.contains("{{ERROR_CODES}}"));
+        assert!(code.contains("\t-8: \"plugin rejected by validation\","));
+        for prototype in FFI_PROTOTYPES {
+            assert!(code.contains(prototype), "missing prototype: {}", prototype);
+        }
+        // cgo needs the struct typedef before the prototype that uses it
+        let typedef = code.find("} ZenithEngineStats;").expect("missing ZenithEngineStats typedef");
+        assert!(typedef < code.find("zenith_stats(").unwrap());
+        let typedef = code.find("} ZenithEventHeader;").expect("missing ZenithEventHeader typedef");
+        assert!(typedef < code.find("zenith_peek_header(").unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_python_bindings_publish() {
+        let dir = temp_output("python");
+        generate("python", &dir, &FfiOptions::default()).unwrap();
+
+        let code = fs::read_to_string(dir.join("zenith_ffi.py")).unwrap();
+        assert!(code.contains("def publish(self, record_batch, source_id: int, seq_no: int):"));
+        assert!(code.contains("record_batch._export_to_c(array_addr, schema_addr)"));
+        assert!(code.contains(
+            "self.lib.zenith_publish.argtypes = [
+            ctypes.c_void_p,
+            ctypes.c_void_p,
+            ctypes.c_void_p,
+            ctypes.c_uint32,
+            ctypes.c_uint64
+        ]"
+        ));
+        assert!(code.contains("self.lib.zenith_publish.restype = ctypes.c_int32"));
+        assert!(code.contains("    -2: \"buffer full\","));
+        assert!(!code.contains("{{ERROR_CODES}}"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_python_cffi_bindings() {
+        let dir = temp_output("python-cffi");
+        generate("python-cffi", &dir, &FfiOptions::default()).unwrap();
+
+        let build = fs::read_to_string(dir.join("zenith_ffi_build.py")).unwrap();
+        let cdef = build.split("CDEF = \"\"\"").nth(1).and_then(|rest| rest.split("\"\"\"").next()).unwrap();
+        for prototype in [
+            "void* zenith_init(uint32_t buffer_size);",
+            "void zenith_free(void* engine_ptr);",
+            "int32_t zenith_load_plugin(void* engine_ptr, const uint8_t* wasm_bytes, size_t len);",
+            "int32_t zenith_publish(void* engine_ptr, void* array_ptr, void* schema_ptr, uint32_t source_id, uint64_t seq_no);",
+        ] {
+            assert!(cdef.contains(prototype), "missing prototype: {}", prototype);
+        }
+        assert!(!cdef.contains("zenith_publish_blocking"));
+        assert!(!cdef.contains("zenith_free_buffer"));
+        assert!(build.contains("ffibuilder.set_source(\"_zenith_cffi\", None)"));
+
+        let code = fs::read_to_string(dir.join("zenith_cffi.py")).unwrap();
+        assert!(code.contains("async def publish(self, record_batch, source_id: int, seq_no: int):"));
+        assert!(code.contains("loop.run_in_executor("));
+        assert!(code.contains("    -2: \"buffer full\","));
+        assert!(!code.contains("{{"));
+
+        // The ctypes client is still generated on its own
+        assert!(!dir.join("zenith_ffi.py").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_node_bindings_error_reasons() {
+        let dir = temp_output("node");
+        generate("node", &dir, &FfiOptions::default()).unwrap();
+
+        let code = fs::read_to_string(dir.join("zenith.js")).unwrap();
+        assert!(!code.contains("{{ERROR_CODES}}"));
+        assert!(code.contains("  [-8]: \"plugin rejected by validation\","));
+        assert!(code.contains("${errorReason(ret)} (code ${ret})"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_typescript_bindings() {
+        let dir = temp_output("typescript");
+        generate("typescript", &dir, &FfiOptions::default()).unwrap();
+
+        let code = fs::read_to_string(dir.join("zenith.ts")).unwrap();
+        assert!(code.contains("Deno.dlopen(libPath, symbols)"));
+        assert!(code.contains(r#"zenith_init: { parameters: ["u32"], result: "pointer" }"#));
+        assert!(code.contains(r#"zenith_free: { parameters: ["pointer"], result: "void" }"#));
+        assert!(code.contains(r#"parameters: ["pointer", "pointer", "pointer", "u32", "u64"]"#));
+        assert!(code.contains(r#"zenith_load_plugin: { parameters: ["pointer", "buffer", "usize"], result: "i32" }"#));
+        assert!(code.contains("export class ZenithClient"));
+        assert!(!code.contains("{{ERROR_CODES}}"));
+        assert!(code.contains("  [-16]: \"file could not be read\","));
+        assert!(code.contains("Failed to publish event: ${errorReason(ret)} (code ${ret})"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_default_library_paths_per_platform() {
+        let cases = [
+            (TargetOs::Linux, "libzenith_core.so"),
+            (TargetOs::MacOs, "libzenith_core.dylib"),
+            (TargetOs::Windows, "zenith_core.dll"),
+        ];
+
+        for (target_os, file_name) in cases {
+            let dir = temp_output(&format!("platform-{:?}", target_os));
+            let options = FfiOptions { lib_dir: None, target_os };
+            for lang in ["python", "node", "typescript", "go"] {
+                generate(lang, &dir, &options).unwrap();
+            }
+
+            let default_path = format!("./core/target/release/{}", file_name);
+            let python = fs::read_to_string(dir.join("zenith_ffi.py")).unwrap();
+            assert!(python.contains(&format!("def __init__(self, lib_path: str = \"{}\"):", default_path)));
+            let node = fs::read_to_string(dir.join("zenith.js")).unwrap();
+            assert!(node.contains(&format!("constructor(libPath = '{}')", default_path)));
+            let ts = fs::read_to_string(dir.join("zenith.ts")).unwrap();
+            assert!(ts.contains(&format!("constructor(libPath: string = \"{}\")", default_path)));
+            let go = fs::read_to_string(dir.join("zenith.go")).unwrap();
+            assert!(go.contains("#cgo LDFLAGS: -L../../core/target/release -lzenith_core"));
+            assert_eq!(go.contains("-Wl,-rpath,"), target_os != TargetOs::Windows);
+
+            for code in [&python, &node, &ts, &go] {
+                assert!(!code.contains("{{"), "unreplaced placeholder for {:?}", target_os);
+            }
+            fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_configured_library_dir() {
+        let dir = temp_output("libdir");
+        let options = FfiOptions {
+            lib_dir: Some("/opt/zenith/lib/".to_string()),
+            target_os: TargetOs::MacOs,
+        };
+        for lang in ["python", "node", "typescript", "go"] {
+            generate(lang, &dir, &options).unwrap();
+        }
+
+        for file in ["zenith_ffi.py", "zenith.js", "zenith.ts"] {
+            let code = fs::read_to_string(dir.join(file)).unwrap();
+            assert!(code.contains("/opt/zenith/lib/libzenith_core.dylib"), "{} ignores lib_dir", file);
+            assert!(!code.contains("core/target/release"));
+        }
+        let go = fs::read_to_string(dir.join("zenith.go")).unwrap();
+        assert!(go.contains("#cgo LDFLAGS: -L/opt/zenith/lib -lzenith_core -Wl,-rpath,/opt/zenith/lib"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_target_os_from_str() {
+        assert_eq!("linux".parse::<TargetOs>().unwrap(), TargetOs::Linux);
+        assert_eq!("darwin".parse::<TargetOs>().unwrap(), TargetOs::MacOs);
+        assert_eq!("windows".parse::<TargetOs>().unwrap(), TargetOs::Windows);
+        assert!("plan9".parse::<TargetOs>().is_err());
+    }
+}
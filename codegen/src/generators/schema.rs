@@ -3,12 +3,18 @@ use std::path::Path;
 use std::fs;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 struct SchemaField {
     name: String,
     #[serde(rename = "type")]
     field_type: String,
     nullable: Option<bool>,
+    /// Child fields, required when `type` is `struct`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    fields: Option<Vec<SchemaField>>,
+    /// Element field, required when `type` is `list` (or use `list<T>`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    item: Option<Box<SchemaField>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -18,12 +24,11 @@ struct SchemaDefinition {
 }
 
 pub fn generate(input: &Path, lang: &str, output: &Path) -> Result<()> {
-    let content = fs::read_to_string(input)?;
-    let schema: SchemaDefinition = serde_json::from_str(&content)?;
+    let schema = load(input)?;
     
     let generated_code = match lang {
-        "rust" => generate_rust_schema(&schema),
-        "python" => generate_python_schema(&schema),
+        "rust" => format!("{}\n{}", generate_rust_schema(&schema)?, generate_rust_struct(&schema)?),
+        "python" => generate_python_schema(&schema)?,
         _ => return Err(anyhow::anyhow!("Unsupported language: {}", lang)),
     };
     
@@ -31,68 +36,968 @@ pub fn generate(input: &Path, lang: &str, output: &Path) -> Result<()> {
     Ok(())
 }
 
-fn generate_rust_schema(schema: &SchemaDefinition) -> String {
-    let mut code = format!("// Auto-generated schema for {}\n", schema.name);
-    code.push_str("use arrow::datatypes::{{DataType, Field, Schema}};\n\n");
-    code.push_str(&format!("pub fn {}_schema() -> Schema {{\n", schema.name.to_lowercase()));
-    code.push_str("    Schema::new(vec![\n");
-    
+/// Check a schema file without generating anything, reporting every
+/// problem found rather than stopping at the first.
+pub fn validate(input: &Path) -> Result<()> {
+    load(input).map(|_| ())
+}
+
+fn load(input: &Path) -> Result<SchemaDefinition> {
+    let content = fs::read_to_string(input)?;
+    let schema: SchemaDefinition = serde_json::from_str(&content)?;
+    validate_schema(&schema)?;
+    Ok(schema)
+}
+
+fn validate_schema(schema: &SchemaDefinition) -> Result<()> {
+    let mut problems = Vec::new();
+    if schema.name.trim().is_empty() {
+        problems.push("schema name is empty".to_string());
+    }
+    validate_fields(&schema.fields, "", &mut problems);
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+
+    let mut message = format!("Schema '{}' has {} problem(s):", schema.name, problems.len());
+    for problem in &problems {
+        message.push_str("\n  - ");
+        message.push_str(problem);
+    }
+    Err(anyhow::anyhow!(message))
+}
+
+/// Check one level of fields; `scope` is the dotted path of the parent
+/// struct (empty at the top level).
+fn validate_fields(fields: &[SchemaField], scope: &str, problems: &mut Vec<String>) {
+    let mut seen = std::collections::HashSet::new();
+
+    for (index, field) in fields.iter().enumerate() {
+        let path = if scope.is_empty() {
+            field.name.clone()
+        } else {
+            format!("{}.{}", scope, field.name)
+        };
+
+        if field.name.trim().is_empty() {
+            let location = if scope.is_empty() { "top level".to_string() } else { format!("'{}'", scope) };
+            problems.push(format!("field #{} in {} has an empty name", index, location));
+        } else if !seen.insert(field.name.as_str()) {
+            problems.push(format!("duplicate field name '{}'", path));
+        }
+
+        validate_field_type(field, &path, problems);
+    }
+}
+
+fn validate_field_type(field: &SchemaField, path: &str, problems: &mut Vec<String>) {
+    match field_kind(field) {
+        Ok(FieldKind::Scalar(t)) | Ok(FieldKind::List(ListItem::Shorthand(t))) => {
+            if map_type_to_arrow(t).is_err() {
+                problems.push(format!("field '{}' has unsupported type '{}'", path, t));
+            }
+        }
+        Ok(FieldKind::List(ListItem::Field(item))) => {
+            validate_field_type(item, &format!("{}[]", path), problems);
+        }
+        Ok(FieldKind::Struct(children)) => validate_fields(children, path, problems),
+        Err(e) => problems.push(e.to_string()),
+    }
+}
+
+/// A field as `diff` sees it, addressed by its dotted path (`a.b`, with
+/// `[]` for a list's element)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSummary {
+    pub path: String,
+    pub field_type: String,
+    pub nullable: bool,
+}
+
+impl FieldSummary {
+    fn new(path: &str, field: &SchemaField) -> Self {
+        Self {
+            path: path.to_string(),
+            field_type: field.field_type.trim().to_string(),
+            nullable: field.nullable.unwrap_or(false),
+        }
+    }
+}
+
+/// A field present in both schemas whose type or nullability changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    pub old: FieldSummary,
+    pub new: FieldSummary,
+}
+
+impl FieldChange {
+    /// Readers of the new schema can still read data written with the
+    /// old one: the type is unchanged or promoted, and nullability is
+    /// not tightened.
+    pub fn is_compatible(&self) -> bool {
+        let type_ok = self.old.field_type == self.new.field_type
+            || is_type_promotion(&self.old.field_type, &self.new.field_type)
+            // Nested changes are reported on the children
+            || matches!(
+                (nested_kind(&self.old.field_type), nested_kind(&self.new.field_type)),
+                (Some(old), Some(new)) if old == new
+            );
+        type_ok && (self.new.nullable || !self.old.nullable)
+    }
+}
+
+/// Field-level differences between two schema versions
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaDiff {
+    pub added: Vec<FieldSummary>,
+    pub removed: Vec<FieldSummary>,
+    pub changed: Vec<FieldChange>,
+}
+
+impl SchemaDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Whether data written with the old schema can be read with the new
+    /// one. Added fields must be nullable (old data has no value for
+    /// them); removed fields break readers that expect them; changed
+    /// fields must pass [`FieldChange::is_compatible`].
+    pub fn is_backward_compatible(&self) -> bool {
+        self.incompatibilities().is_empty()
+    }
+
+    /// One line per change that breaks backward compatibility
+    pub fn incompatibilities(&self) -> Vec<String> {
+        let added = self.added.iter()
+            .filter(|field| !field.nullable)
+            .map(|field| format!("added non-nullable field '{}'", field.path));
+        let removed = self.removed.iter()
+            .map(|field| format!("removed field '{}'", field.path));
+        let changed = self.changed.iter()
+            .filter(|change| !change.is_compatible())
+            .map(|change| format!(
+                "field '{}' changed from {}{} to {}{}",
+                change.old.path,
+                change.old.field_type,
+                if change.old.nullable { " (nullable)" } else { "" },
+                change.new.field_type,
+                if change.new.nullable { " (nullable)" } else { "" },
+            ));
+        added.chain(removed).chain(changed).collect()
+    }
+}
+
+/// Compare two schema files field by field, matching fields by name
+pub fn diff(old: &Path, new: &Path) -> Result<SchemaDiff> {
+    let (old, new) = (load(old)?, load(new)?);
+    let mut diff = SchemaDiff::default();
+    diff_fields(&old.fields, &new.fields, "", &mut diff)?;
+    Ok(diff)
+}
+
+fn diff_fields(old: &[SchemaField], new: &[SchemaField], scope: &str, diff: &mut SchemaDiff) -> Result<()> {
+    let path = |field: &SchemaField| {
+        if scope.is_empty() { field.name.clone() } else { format!("{}.{}", scope, field.name) }
+    };
+
+    for old_field in old {
+        match new.iter().find(|field| field.name == old_field.name) {
+            Some(new_field) => diff_field(&path(old_field), old_field, new_field, diff)?,
+            None => diff.removed.push(FieldSummary::new(&path(old_field), old_field)),
+        }
+    }
+    for new_field in new.iter().filter(|field| !old.iter().any(|old_field| old_field.name == field.name)) {
+        diff.added.push(FieldSummary::new(&path(new_field), new_field));
+    }
+    Ok(())
+}
+
+fn diff_field(path: &str, old: &SchemaField, new: &SchemaField, diff: &mut SchemaDiff) -> Result<()> {
+    let change = FieldChange { old: FieldSummary::new(path, old), new: FieldSummary::new(path, new) };
+    let nullable_changed = change.old.nullable != change.new.nullable;
+
+    match (field_kind(old)?, field_kind(new)?) {
+        (FieldKind::Struct(old_children), FieldKind::Struct(new_children)) => {
+            if nullable_changed {
+                diff.changed.push(change);
+            }
+            diff_fields(old_children, new_children, path, diff)
+        }
+        (FieldKind::List(old_item), FieldKind::List(new_item)) => {
+            if nullable_changed {
+                diff.changed.push(change);
+            }
+            let item_path = format!("{}[]", path);
+            diff_field(&item_path, &list_item_field(old_item), &list_item_field(new_item), diff)
+        }
+        _ => {
+            if nullable_changed || change.old.field_type != change.new.field_type {
+                diff.changed.push(change);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// The element of a list as a field, expanding `list<T>` shorthand
+fn list_item_field(item: ListItem<'_>) -> SchemaField {
+    match item {
+        ListItem::Field(field) => field.clone(),
+        ListItem::Shorthand(item_type) => SchemaField {
+            name: "item".to_string(),
+            field_type: item_type.to_string(),
+            nullable: Some(true),
+            fields: None,
+            item: None,
+        },
+    }
+}
+
+/// `struct` or `list` for nested types, including `list<T>` shorthand
+fn nested_kind(type_str: &str) -> Option<&'static str> {
+    match type_str {
+        "struct" => Some("struct"),
+        "list" => Some("list"),
+        t if t.starts_with("list<") => Some("list"),
+        _ => None,
+    }
+}
+
+/// Lossless widenings a reader may apply to old values, as Avro allows
+/// `int` to `long` and `float` to `double`
+fn is_type_promotion(old: &str, new: &str) -> bool {
+    matches!(
+        (old, new),
+        ("int32", "int64")
+            | ("uint32", "uint64" | "int64")
+            | ("float16", "float32" | "float64")
+            | ("float32", "float64")
+    )
+}
+
+fn generate_rust_schema(schema: &SchemaDefinition) -> Result<String> {
+    let mut fields_code = String::new();
     for field in &schema.fields {
-        let arrow_type = map_type_to_arrow(&field.field_type);
-        let nullable = field.nullable.unwrap_or(false);
-        code.push_str(&format!(
-            "        Field::new(\"{}\", {}, {}),\n",
-            field.name, arrow_type, nullable
-        ));
+        fields_code.push_str(&format!("        {},\n", rust_field(field)?));
     }
-    
+
+    let mut code = format!("// Auto-generated schema for {}\n", schema.name);
+    // Only pull in Arc when a generated type actually references it,
+    // otherwise the output trips unused-import warnings.
+    if fields_code.contains("Arc::") {
+        code.push_str("use std::sync::Arc;\n");
+    }
+    code.push_str("use arrow::datatypes::{DataType, Field, Schema};\n\n");
+    code.push_str(&format!(
+        "pub fn {}() -> Schema {{\n",
+        rust_ident(&sanitize_ident(&format!("{}_schema", schema.name.to_lowercase())))
+    ));
+    code.push_str("    Schema::new(vec![\n");
+    code.push_str(&fields_code);
     code.push_str("    ])\n");
     code.push_str("}\n");
-    code
+    Ok(code)
+}
+
+/// Emit a serde record type mirroring the schema, so producers can build
+/// rows without hand-writing the struct. Nested structs become their own
+/// types named `{Parent}{Field}`.
+fn generate_rust_struct(schema: &SchemaDefinition) -> Result<String> {
+    let mut structs = Vec::new();
+    let name = rust_ident(&sanitize_ident(&pascal_case(&sanitize_ident(&schema.name))));
+    rust_struct(&name, &schema.fields, &mut structs)?;
+
+    let mut code = format!("// Auto-generated record type for {}\n", schema.name);
+    code.push_str("use serde::{Deserialize, Serialize};\n");
+    for item in structs {
+        code.push('\n');
+        code.push_str(&item);
+    }
+    Ok(code)
 }
 
-fn generate_python_schema(schema: &SchemaDefinition) -> String {
-    let mut code = format!("# Auto-generated schema for {}\n", schema.name);
+fn generate_python_schema(schema: &SchemaDefinition) -> Result<String> {
+    let mut code = format!("# Auto-generated schema for {}\n", schema.name.replace(['\n', '\r'], " "));
     code.push_str("import pyarrow as pa\n\n");
-    code.push_str(&format!("def {}_schema():\n", schema.name.to_lowercase()));
+    code.push_str(&format!("def {}_schema():\n", sanitize_ident(&schema.name.to_lowercase())));
     code.push_str("    return pa.schema([\n");
     
     for field in &schema.fields {
-        let py_type = map_type_to_pyarrow(&field.field_type);
-        code.push_str(&format!(
-            "        pa.field('{}', {}),\n",
-            field.name, py_type
-        ));
+        code.push_str(&format!("        {},\n", python_field(field)?));
     }
     
     code.push_str("    ])\n");
-    code
+    Ok(code)
 }
 
-fn map_type_to_arrow(type_str: &str) -> String {
+/// Nested shape of a field type after resolving `list<T>` shorthand.
+enum FieldKind<'a> {
+    Scalar(&'a str),
+    List(ListItem<'a>),
+    Struct(&'a [SchemaField]),
+}
+
+enum ListItem<'a> {
+    /// `list<T>` shorthand: a nullable scalar element named `item`
+    Shorthand(&'a str),
+    Field(&'a SchemaField),
+}
+
+fn field_kind(field: &SchemaField) -> Result<FieldKind<'_>> {
+    let type_str = field.field_type.trim();
+
+    if let Some(inner) = type_str.strip_prefix("list<").and_then(|t| t.strip_suffix('>')) {
+        return Ok(FieldKind::List(ListItem::Shorthand(inner.trim())));
+    }
+
     match type_str {
-        "string" => "DataType::Utf8".to_string(),
-        "int32" => "DataType::Int32".to_string(),
-        "int64" => "DataType::Int64".to_string(),
-        "uint32" => "DataType::UInt32".to_string(),
-        "uint64" => "DataType::UInt64".to_string(),
-        "float32" => "DataType::Float32".to_string(),
-        "float64" => "DataType::Float64".to_string(),
-        "bool" => "DataType::Boolean".to_string(),
-        _ => format!("DataType::Utf8 // Unknown: {}", type_str),
+        "list" => field
+            .item
+            .as_deref()
+            .map(|item| FieldKind::List(ListItem::Field(item)))
+            .ok_or_else(|| anyhow::anyhow!("List field '{}' is missing an 'item' definition", field.name)),
+        "struct" => match field.fields.as_deref() {
+            Some(children) if !children.is_empty() => Ok(FieldKind::Struct(children)),
+            _ => Err(anyhow::anyhow!("Struct field '{}' must define at least one child in 'fields'", field.name)),
+        },
+        scalar => Ok(FieldKind::Scalar(scalar)),
     }
 }
 
-fn map_type_to_pyarrow(type_str: &str) -> String {
-    match type_str {
-        "string" => "pa.string()".to_string(),
-        "int32" => "pa.int32()".to_string(),
-        "int64" => "pa.int64()".to_string(),
-        "uint32" => "pa.uint32()".to_string(),
-        "uint64" => "pa.uint64()".to_string(),
-        "float32" => "pa.float32()".to_string(),
-        "float64" => "pa.float64()".to_string(),
-        "bool" => "pa.bool_()".to_string(),
-        _ => format!("pa.string()  # Unknown: {}", type_str),
+fn rust_field(field: &SchemaField) -> Result<String> {
+    Ok(format!(
+        "Field::new({:?}, {}, {})",
+        field.name,
+        rust_data_type(field)?,
+        field.nullable.unwrap_or(false)
+    ))
+}
+
+fn rust_data_type(field: &SchemaField) -> Result<String> {
+    match field_kind(field)? {
+        FieldKind::Scalar(t) => map_type_to_arrow(t),
+        FieldKind::List(ListItem::Shorthand(t)) => Ok(format!(
+            "DataType::List(Arc::new(Field::new(\"item\", {}, true)))",
+            map_type_to_arrow(t)?
+        )),
+        FieldKind::List(ListItem::Field(item)) => {
+            Ok(format!("DataType::List(Arc::new({}))", rust_field(item)?))
+        }
+        FieldKind::Struct(children) => {
+            let children = children
+                .iter()
+                .map(rust_field)
+                .collect::<Result<Vec<_>>>()?;
+            Ok(format!("DataType::Struct(vec![{}].into())", children.join(", ")))
+        }
+    }
+}
+
+/// Render `pub struct {name}` into `out`, after any nested structs it needs.
+fn rust_struct(name: &str, fields: &[SchemaField], out: &mut Vec<String>) -> Result<()> {
+    let mut body = String::new();
+    for field in fields {
+        let ty = rust_record_type(name, field, out)?;
+        let ident = rust_ident(&sanitize_ident(&field.name));
+        // serde already strips `r#`, only sanitized names and suffixed
+        // keywords need renaming
+        if ident.trim_start_matches("r#") != field.name {
+            body.push_str(&format!("    #[serde(rename = {:?})]\n", field.name));
+        }
+        body.push_str(&format!("    pub {}: {},\n", ident, ty));
+    }
+
+    out.push(format!(
+        "#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\npub struct {} {{\n{}}}\n",
+        name, body
+    ));
+    Ok(())
+}
+
+fn rust_record_type(parent: &str, field: &SchemaField, out: &mut Vec<String>) -> Result<String> {
+    let ty = match field_kind(field)? {
+        FieldKind::Scalar(t) => map_type_to_rust(t, &field.name)?,
+        FieldKind::List(ListItem::Shorthand(t)) => {
+            format!("Vec<Option<{}>>", map_type_to_rust(t, &field.name)?)
+        }
+        FieldKind::List(ListItem::Field(item)) => {
+            format!("Vec<{}>", rust_record_type(parent, item, out)?)
+        }
+        FieldKind::Struct(children) => {
+            let child = format!("{}{}", parent, pascal_case(&sanitize_ident(&field.name)));
+            rust_struct(&child, children, out)?;
+            child
+        }
+    };
+
+    if field.nullable.unwrap_or(false) {
+        Ok(format!("Option<{}>", ty))
+    } else {
+        Ok(ty)
+    }
+}
+
+fn python_field(field: &SchemaField) -> Result<String> {
+    Ok(format!("pa.field({}, {})", python_str(&field.name), python_data_type(field)?))
+}
+
+/// Quote `value` as a single-quoted Python string literal.
+fn python_str(value: &str) -> String {
+    let mut literal = String::from("'");
+    for c in value.chars() {
+        match c {
+            '\\' => literal.push_str("\\\\"),
+            '\'' => literal.push_str("\\'"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c if c.is_control() => literal.push_str(&format!("\\u{:04x}", c as u32)),
+            c => literal.push(c),
+        }
+    }
+    literal.push('\'');
+    literal
+}
+
+/// Turn a schema name into an identifier, valid in both Rust and Python
+/// (keywords aside), by replacing anything that can't appear in one with `_`.
+fn sanitize_ident(name: &str) -> String {
+    let ident: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' })
+        .collect();
+    if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", ident)
+    } else {
+        ident
+    }
+}
+
+fn python_data_type(field: &SchemaField) -> Result<String> {
+    match field_kind(field)? {
+        FieldKind::Scalar(t) => map_type_to_pyarrow(t),
+        FieldKind::List(ListItem::Shorthand(t)) => Ok(format!("pa.list_({})", map_type_to_pyarrow(t)?)),
+        FieldKind::List(ListItem::Field(item)) => Ok(format!("pa.list_({})", python_field(item)?)),
+        FieldKind::Struct(children) => {
+            let children = children
+                .iter()
+                .map(python_field)
+                .collect::<Result<Vec<_>>>()?;
+            Ok(format!("pa.struct([{}])", children.join(", ")))
+        }
+    }
+}
+
+/// Rust keywords that cannot be used as plain identifiers in generated code.
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else",
+    "enum", "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop",
+    "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self",
+    "static", "struct", "super", "trait", "true", "type", "unsafe", "use",
+    "where", "while", "abstract", "become", "box", "do", "final", "gen", "macro",
+    "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+/// Turn a schema name into a valid Rust identifier, using a raw identifier
+/// (`r#type`) for keywords.
+fn rust_ident(name: &str) -> String {
+    match name {
+        // These keywords cannot be raw identifiers, so suffix them instead
+        "self" | "Self" | "super" | "crate" => format!("{}_", name),
+        _ if RUST_KEYWORDS.contains(&name) => format!("r#{}", name),
+        _ => name.to_string(),
+    }
+}
+
+/// `market_tick` / `MarketTick` -> `MarketTick`
+fn pascal_case(name: &str) -> String {
+    name.split(|c: char| c == '_' || c == '-' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Half-precision types use the `half` crate (with its `serde` feature),
+/// which Arrow already depends on.
+fn map_type_to_rust(type_str: &str, field_name: &str) -> Result<String> {
+    let rust_type = match type_str {
+        "string" => "String",
+        "int32" => "i32",
+        "int64" => "i64",
+        "uint32" => "u32",
+        "uint64" => "u64",
+        "float16" => "half::f16",
+        "bfloat16" => "half::bf16",
+        "float32" => "f32",
+        "float64" => "f64",
+        "bool" => "bool",
+        _ => return Err(anyhow::anyhow!("Unknown field type: {} (field '{}')", type_str, field_name)),
+    };
+    Ok(rust_type.to_string())
+}
+
+fn map_type_to_arrow(type_str: &str) -> Result<String> {
+    let arrow_type = match type_str {
+        "string" => "DataType::Utf8",
+        "int32" => "DataType::Int32",
+        "int64" => "DataType::Int64",
+        "uint32" => "DataType::UInt32",
+        "uint64" => "DataType::UInt64",
+        "float16" => "DataType::Float16",
+        // Arrow has no bfloat16 type; store the raw little-endian bits
+        // (`half::bf16::to_le_bytes`) in a 2-byte fixed-size binary
+        "bfloat16" => "DataType::FixedSizeBinary(2)",
+        "float32" => "DataType::Float32",
+        "float64" => "DataType::Float64",
+        "bool" => "DataType::Boolean",
+        _ => return Err(anyhow::anyhow!("Unknown field type: {}", type_str)),
+    };
+    Ok(arrow_type.to_string())
+}
+
+fn map_type_to_pyarrow(type_str: &str) -> Result<String> {
+    let py_type = match type_str {
+        "string" => "pa.string()",
+        "int32" => "pa.int32()",
+        "int64" => "pa.int64()",
+        "uint32" => "pa.uint32()",
+        "uint64" => "pa.uint64()",
+        "float16" => "pa.float16()",
+        // Same raw-bits fallback as the Rust schema
+        "bfloat16" => "pa.binary(2)",
+        "float32" => "pa.float32()",
+        "float64" => "pa.float64()",
+        "bool" => "pa.bool_()",
+        _ => return Err(anyhow::anyhow!("Unknown field type: {}", type_str)),
+    };
+    Ok(py_type.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, field_type: &str) -> SchemaField {
+        SchemaField {
+            name: name.to_string(),
+            field_type: field_type.to_string(),
+            nullable: Some(false),
+            fields: None,
+            item: None,
+        }
+    }
+
+    fn schema_from_json(json: &str) -> SchemaDefinition {
+        serde_json::from_str(json).expect("test schema should deserialize")
+    }
+
+    fn market_tick() -> SchemaDefinition {
+        SchemaDefinition {
+            name: "MarketTick".to_string(),
+            fields: vec![
+                field("symbol", "string"),
+                field("price", "float64"),
+                field("size", "uint32"),
+                field("type", "string"),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_rust_schema_is_valid_rust() {
+        let code = generate_rust_schema(&market_tick()).unwrap();
+
+        assert!(code.contains("use arrow::datatypes::{DataType, Field, Schema};"));
+        assert!(!code.contains("{{"), "generated code must not contain escaped braces");
+        syn::parse_file(&code).expect("generated schema should parse as Rust");
+    }
+
+    #[test]
+    fn test_rust_schema_skips_unused_arc_import() {
+        let code = generate_rust_schema(&market_tick()).unwrap();
+        assert!(!code.contains("use std::sync::Arc;"));
+    }
+
+    #[test]
+    fn test_rust_ident_escapes_keywords() {
+        assert_eq!(rust_ident("symbol"), "symbol");
+        assert_eq!(rust_ident("type"), "r#type");
+        assert_eq!(rust_ident("match"), "r#match");
+        assert_eq!(rust_ident("self"), "self_");
+    }
+
+    #[test]
+    fn test_rust_struct_sanitizes_field_names() {
+        let schema = schema_from_json(r#"{
+            "name": "Cat",
+            "fields": [
+                {"name": "9-lives", "type": "int32"},
+                {"name": "last seen", "type": "int64"},
+                {"name": "owner.info", "type": "struct", "fields": [{"name": "id", "type": "int32"}]}
+            ]
+        }"#);
+
+        let code = generate_rust_struct(&schema).unwrap();
+        assert!(code.contains("#[serde(rename = \"9-lives\")]\n    pub _9_lives: i32,"));
+        assert!(code.contains("#[serde(rename = \"last seen\")]\n    pub last_seen: i64,"));
+        let fields = struct_fields(&code, "Cat");
+        assert_eq!(fields[0].0, "_9_lives");
+        assert_eq!(fields[1].0, "last_seen");
+        assert_eq!(fields[2], ("owner_info".to_string(), ty("CatOwnerInfo")));
+    }
+
+    #[test]
+    fn test_rust_sanitizes_schema_name() {
+        let schema = schema_from_json(r#"{"name": "9-Lives", "fields": [{"name": "id", "type": "int32"}]}"#);
+
+        let code = generate_rust_schema(&schema).unwrap();
+        let file = syn::parse_file(&code).expect("generated schema should parse as Rust");
+        assert!(matches!(&file.items[..], [.., syn::Item::Fn(f)] if f.sig.ident == "_9_lives_schema"));
+
+        let code = generate_rust_struct(&schema).unwrap();
+        assert_eq!(struct_fields(&code, "_9Lives").len(), 1);
+    }
+
+    #[test]
+    fn test_generate_writes_rust_file() {
+        let dir = std::env::temp_dir().join(format!("zenith-codegen-schema-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("schema.json");
+        let output = dir.join("schema.rs");
+        fs::write(&input, serde_json::to_string(&market_tick()).unwrap()).unwrap();
+
+        generate(&input, "rust", &output).unwrap();
+
+        let code = fs::read_to_string(&output).unwrap();
+        syn::parse_file(&code).expect("written schema should parse as Rust");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Field name and type of each field of `pub struct {name}` in `code`
+    fn struct_fields(code: &str, name: &str) -> Vec<(String, syn::Type)> {
+        let file = syn::parse_file(code).expect("generated struct should parse as Rust");
+        let item = file.items.into_iter().find_map(|item| match item {
+            syn::Item::Struct(s) if s.ident == name => Some(s),
+            _ => None,
+        });
+        item.unwrap_or_else(|| panic!("struct {} not generated", name))
+            .fields
+            .into_iter()
+            .map(|f| (f.ident.unwrap().to_string(), f.ty))
+            .collect()
+    }
+
+    fn ty(s: &str) -> syn::Type {
+        syn::parse_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_rust_struct_field_types() {
+        let schema = schema_from_json(r#"{
+            "name": "market_tick",
+            "fields": [
+                {"name": "symbol", "type": "string"},
+                {"name": "price", "type": "float64", "nullable": true},
+                {"name": "size", "type": "uint32"},
+                {"name": "type", "type": "string"},
+                {"name": "self", "type": "bool"},
+                {"name": "tags", "type": "list<string>"},
+                {"name": "venue", "type": "struct", "nullable": true, "fields": [
+                    {"name": "id", "type": "int32"}
+                ]}
+            ]
+        }"#);
+
+        let code = generate_rust_struct(&schema).unwrap();
+        assert!(code.contains("use serde::{Deserialize, Serialize};"));
+        assert!(code.contains("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]"));
+        assert!(code.contains("#[serde(rename = \"self\")]"));
+
+        let fields = struct_fields(&code, "MarketTick");
+        let expected = [
+            ("symbol", "String"),
+            ("price", "Option<f64>"),
+            ("size", "u32"),
+            ("r#type", "String"),
+            ("self_", "bool"),
+            ("tags", "Vec<Option<String>>"),
+            ("venue", "Option<MarketTickVenue>"),
+        ];
+        assert_eq!(fields.len(), expected.len());
+        for ((name, actual), (want_name, want_ty)) in fields.iter().zip(expected) {
+            assert_eq!(name, want_name);
+            assert_eq!(*actual, ty(want_ty), "field {}", name);
+        }
+
+        assert_eq!(struct_fields(&code, "MarketTickVenue"), vec![("id".to_string(), ty("i32"))]);
+    }
+
+    #[test]
+    fn test_rust_struct_unknown_type_is_error() {
+        let schema = schema_from_json(r#"{"name": "Bad", "fields": [{"name": "x", "type": "list<decimal>"}]}"#);
+        let err = generate_rust_struct(&schema).unwrap_err().to_string();
+
+        assert_eq!(err, "Unknown field type: decimal (field 'x')");
+    }
+
+    #[test]
+    fn test_generate_rust_includes_struct() {
+        let dir = std::env::temp_dir().join(format!("zenith-codegen-struct-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("schema.json");
+        let output = dir.join("schema.rs");
+        fs::write(&input, serde_json::to_string(&market_tick()).unwrap()).unwrap();
+
+        generate(&input, "rust", &output).unwrap();
+
+        let code = fs::read_to_string(&output).unwrap();
+        assert!(code.contains("pub fn markettick_schema() -> Schema"));
+        assert_eq!(struct_fields(&code, "MarketTick").len(), 4);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_list_of_int_schema() {
+        let schema = schema_from_json(r#"{
+            "name": "Readings",
+            "fields": [
+                {"name": "values", "type": "list<int32>", "nullable": false},
+                {"name": "ids", "type": "list", "item": {"name": "id", "type": "int64", "nullable": false}}
+            ]
+        }"#);
+
+        let rust = generate_rust_schema(&schema).unwrap();
+        assert!(rust.contains("use std::sync::Arc;"));
+        assert!(rust.contains(
+            "Field::new(\"values\", DataType::List(Arc::new(Field::new(\"item\", DataType::Int32, true))), false)"
+        ));
+        assert!(rust.contains("DataType::List(Arc::new(Field::new(\"id\", DataType::Int64, false)))"));
+        syn::parse_file(&rust).expect("list schema should parse as Rust");
+
+        let python = generate_python_schema(&schema).unwrap();
+        assert!(python.contains("pa.field('values', pa.list_(pa.int32()))"));
+        assert!(python.contains("pa.field('ids', pa.list_(pa.field('id', pa.int64())))"));
+    }
+
+    #[test]
+    fn test_python_schema_quotes_names() {
+        let schema = schema_from_json(r#"{
+            "name": "9-Lives",
+            "fields": [
+                {"name": "class", "type": "string"},
+                {"name": "it's", "type": "int32"},
+                {"name": "C:\\tmp\nx", "type": "bool"}
+            ]
+        }"#);
+
+        let python = generate_python_schema(&schema).unwrap();
+        assert!(python.contains("def _9_lives_schema():"));
+        // Names are column names in a string literal, so keywords stay as-is
+        assert!(python.contains("pa.field('class', pa.string())"));
+        assert!(python.contains(r"pa.field('it\'s', pa.int32())"));
+        assert!(python.contains(r"pa.field('C:\\tmp\nx', pa.bool_())"));
+    }
+
+    #[test]
+    fn test_nested_struct_schema() {
+        let schema = schema_from_json(r#"{
+            "name": "Order",
+            "fields": [
+                {"name": "order_id", "type": "uint64"},
+                {"name": "customer", "type": "struct", "nullable": true, "fields": [
+                    {"name": "name", "type": "string"},
+                    {"name": "address", "type": "struct", "fields": [
+                        {"name": "zip", "type": "int32"}
+                    ]}
+                ]}
+            ]
+        }"#);
+
+        let rust = generate_rust_schema(&schema).unwrap();
+        assert!(rust.contains("DataType::Struct(vec![Field::new(\"zip\", DataType::Int32, false)].into())"));
+        assert!(!rust.contains("use std::sync::Arc;"));
+        syn::parse_file(&rust).expect("struct schema should parse as Rust");
+
+        let python = generate_python_schema(&schema).unwrap();
+        assert!(python.contains(
+            "pa.field('customer', pa.struct([pa.field('name', pa.string()), pa.field('address', pa.struct([pa.field('zip', pa.int32())]))]))"
+        ));
+    }
+
+    #[test]
+    fn test_half_precision_schema() {
+        let schema = schema_from_json(r#"{
+            "name": "Activations",
+            "fields": [
+                {"name": "fp16", "type": "float16"},
+                {"name": "bf16", "type": "bfloat16", "nullable": true},
+                {"name": "grads", "type": "list<bfloat16>"}
+            ]
+        }"#);
+        assert!(validate_schema(&schema).is_ok());
+
+        let rust = generate_rust_schema(&schema).unwrap();
+        assert!(rust.contains("Field::new(\"fp16\", DataType::Float16, false)"));
+        assert!(rust.contains("Field::new(\"bf16\", DataType::FixedSizeBinary(2), true)"));
+        assert!(rust.contains("Field::new(\"item\", DataType::FixedSizeBinary(2), true)"));
+        syn::parse_file(&rust).expect("half-precision schema should parse as Rust");
+
+        let fields = struct_fields(&generate_rust_struct(&schema).unwrap(), "Activations");
+        assert_eq!(fields[0].1, ty("half::f16"));
+        assert_eq!(fields[1].1, ty("Option<half::bf16>"));
+        assert_eq!(fields[2].1, ty("Vec<Option<half::bf16>>"));
+
+        let python = generate_python_schema(&schema).unwrap();
+        assert!(python.contains("pa.field('fp16', pa.float16())"));
+        assert!(python.contains("pa.field('bf16', pa.binary(2))"));
+        assert!(python.contains("pa.field('grads', pa.list_(pa.binary(2)))"));
+    }
+
+    #[test]
+    fn test_validate_reports_all_problems() {
+        let schema = schema_from_json(r#"{
+            "name": "Bad",
+            "fields": [
+                {"name": "id", "type": "int64"},
+                {"name": "id", "type": "string"},
+                {"name": "amount", "type": "decimal"},
+                {"name": "", "type": "bool"},
+                {"name": "meta", "type": "struct", "fields": [
+                    {"name": "k", "type": "string"},
+                    {"name": "k", "type": "list<uuid>"}
+                ]}
+            ]
+        }"#);
+
+        let err = validate_schema(&schema).unwrap_err().to_string();
+        assert!(err.starts_with("Schema 'Bad' has 5 problem(s):"), "{}", err);
+        assert!(err.contains("duplicate field name 'id'"));
+        assert!(err.contains("field 'amount' has unsupported type 'decimal'"));
+        assert!(err.contains("field #3 in top level has an empty name"));
+        assert!(err.contains("duplicate field name 'meta.k'"));
+        assert!(err.contains("field 'meta.k' has unsupported type 'uuid'"));
+    }
+
+    #[test]
+    fn test_validate_runs_before_generate() {
+        let dir = std::env::temp_dir().join(format!("zenith-codegen-validate-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("schema.json");
+        let output = dir.join("schema.py");
+
+        fs::write(&input, serde_json::to_string(&market_tick()).unwrap()).unwrap();
+        assert!(validate(&input).is_ok());
+
+        fs::write(&input, r#"{"name": "Dup", "fields": [
+            {"name": "a", "type": "int32"}, {"name": "a", "type": "int32"}
+        ]}"#).unwrap();
+        assert!(validate(&input).is_err());
+        assert!(generate(&input, "python", &output).is_err());
+        assert!(!output.exists(), "nothing should be written for an invalid schema");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Write `old` and `new` to temp files and diff them
+    fn diff_json(name: &str, old: &str, new: &str) -> SchemaDiff {
+        let dir = std::env::temp_dir().join(format!("zenith-codegen-diff-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (old_path, new_path) = (dir.join("old.json"), dir.join("new.json"));
+        fs::write(&old_path, old).unwrap();
+        fs::write(&new_path, new).unwrap();
+        let result = diff(&old_path, &new_path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        result
+    }
+
+    const TICK_V1: &str = r#"{"name": "Tick", "fields": [
+        {"name": "symbol", "type": "string"},
+        {"name": "price", "type": "float32"},
+        {"name": "venue", "type": "struct", "fields": [{"name": "code", "type": "string"}]},
+        {"name": "sizes", "type": "list<uint32>"}
+    ]}"#;
+
+    #[test]
+    fn test_diff_nullable_addition_is_compatible() {
+        let diff = diff_json("added", TICK_V1, r#"{"name": "Tick", "fields": [
+            {"name": "symbol", "type": "string", "nullable": true},
+            {"name": "price", "type": "float64"},
+            {"name": "venue", "type": "struct", "fields": [
+                {"name": "code", "type": "string"},
+                {"name": "mic", "type": "string", "nullable": true}
+            ]},
+            {"name": "sizes", "type": "list", "item": {"name": "item", "type": "uint64", "nullable": true}},
+            {"name": "note", "type": "string", "nullable": true}
+        ]}"#);
+
+        let added: Vec<_> = diff.added.iter().map(|field| field.path.as_str()).collect();
+        assert_eq!(added, ["venue.mic", "note"]);
+        assert!(diff.removed.is_empty());
+        let changed: Vec<_> = diff.changed.iter().map(|change| change.new.path.as_str()).collect();
+        assert_eq!(changed, ["symbol", "price", "sizes[]"]);
+        assert!(diff.is_backward_compatible(), "{:?}", diff.incompatibilities());
+
+        let same = diff_json("same", TICK_V1, TICK_V1);
+        assert!(same.is_empty());
+        assert!(same.is_backward_compatible());
+    }
+
+    #[test]
+    fn test_diff_type_change_is_incompatible() {
+        let diff = diff_json("changed", TICK_V1, r#"{"name": "Tick", "fields": [
+            {"name": "symbol", "type": "string"},
+            {"name": "price", "type": "int64"},
+            {"name": "venue", "type": "struct", "fields": [{"name": "code", "type": "string"}]},
+            {"name": "sizes", "type": "list<uint32>"},
+            {"name": "seq", "type": "uint64"}
+        ]}"#);
+
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].old.field_type, "float32");
+        assert_eq!(diff.changed[0].new.field_type, "int64");
+        assert!(!diff.is_backward_compatible());
+        assert_eq!(diff.incompatibilities(), [
+            "added non-nullable field 'seq'",
+            "field 'price' changed from float32 to int64",
+        ]);
+    }
+
+    #[test]
+    fn test_diff_removed_field_is_incompatible() {
+        let diff = diff_json("removed", TICK_V1, r#"{"name": "Tick", "fields": [
+            {"name": "symbol", "type": "string"},
+            {"name": "price", "type": "float32"},
+            {"name": "venue", "type": "struct", "fields": [{"name": "name", "type": "string", "nullable": true}]}
+        ]}"#);
+
+        let removed: Vec<_> = diff.removed.iter().map(|field| field.path.as_str()).collect();
+        assert_eq!(removed, ["venue.code", "sizes"]);
+        assert!(!diff.is_backward_compatible());
+        assert!(diff.incompatibilities().contains(&"removed field 'sizes'".to_string()));
+    }
+
+    #[test]
+    fn test_unknown_and_incomplete_types_are_rejected() {
+        let unknown = schema_from_json(r#"{"name": "Bad", "fields": [{"name": "x", "type": "decimal"}]}"#);
+        assert!(generate_rust_schema(&unknown).is_err());
+        assert!(generate_python_schema(&unknown).is_err());
+
+        let bad_item = schema_from_json(r#"{"name": "Bad", "fields": [{"name": "x", "type": "list<decimal>"}]}"#);
+        assert!(generate_rust_schema(&bad_item).is_err());
+
+        let missing_item = schema_from_json(r#"{"name": "Bad", "fields": [{"name": "x", "type": "list"}]}"#);
+        assert!(generate_rust_schema(&missing_item).is_err());
+
+        let empty_struct = schema_from_json(r#"{"name": "Bad", "fields": [{"name": "x", "type": "struct"}]}"#);
+        assert!(generate_python_schema(&empty_struct).is_err());
     }
 }
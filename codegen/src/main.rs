@@ -34,16 +34,24 @@ enum Commands {
     
     /// Generate FFI bindings for a new language
     Ffi {
-        /// Target language (go, python, node)
+        /// Target language (c, go, python, python-cffi, node, typescript)
         #[arg(short, long)]
         lang: String,
         
         /// Output directory
         #[arg(short, long, default_value = ".")]
         output: PathBuf,
+        
+        /// Directory the bindings load libzenith_core from (default: the repo's target/release)
+        #[arg(long)]
+        lib_dir: Option<String>,
+        
+        /// Platform the bindings target, picks the library extension (linux, macos, windows; default: host)
+        #[arg(long)]
+        target_os: Option<String>,
     },
     
-    /// Generate Arrow schema from JSON spec
+    /// Generate Arrow schema (and a serde record type for Rust) from JSON spec
     Schema {
         /// Schema definition file (JSON)
         #[arg(short, long)]
@@ -57,6 +65,24 @@ enum Commands {
         #[arg(short, long)]
         output: PathBuf,
     },
+    
+    /// Check a JSON schema spec for problems without generating code
+    ValidateSchema {
+        /// Schema definition file (JSON)
+        #[arg(short, long)]
+        input: PathBuf,
+    },
+    
+    /// Compare two versions of a JSON schema spec; fails if the new one is not backward compatible
+    DiffSchema {
+        /// Previous schema definition file (JSON)
+        #[arg(long)]
+        old: PathBuf,
+        
+        /// New schema definition file (JSON)
+        #[arg(long)]
+        new: PathBuf,
+    },
 }
 
 fn main() -> Result<()> {
@@ -67,14 +93,48 @@ fn main() -> Result<()> {
             plugin::generate(&name, &output, &ptype)?;
             println!("[OK] Plugin '{}' generated at {:?}", name, output);
         }
-        Commands::Ffi { lang, output } => {
-            ffi::generate(&lang, &output)?;
+        Commands::Ffi { lang, output, lib_dir, target_os } => {
+            let options = ffi::FfiOptions {
+                lib_dir,
+                target_os: match target_os {
+                    Some(os) => os.parse()?,
+                    None => ffi::TargetOs::host(),
+                },
+            };
+            ffi::generate(&lang, &output, &options)?;
             println!("[OK] FFI bindings for '{}' generated at {:?}", lang, output);
         }
         Commands::Schema { input, lang, output } => {
             schema::generate(&input, &lang, &output)?;
             println!("[OK] Schema code generated at {:?}", output);
         }
+        Commands::ValidateSchema { input } => {
+            schema::validate(&input)?;
+            println!("[OK] Schema {:?} is valid", input);
+        }
+        Commands::DiffSchema { old, new } => {
+            let diff = schema::diff(&old, &new)?;
+            if diff.is_empty() {
+                println!("[OK] No field changes between {:?} and {:?}", old, new);
+                return Ok(());
+            }
+            for field in &diff.added {
+                println!("+ {}: {}{}", field.path, field.field_type, if field.nullable { " (nullable)" } else { "" });
+            }
+            for field in &diff.removed {
+                println!("- {}: {}", field.path, field.field_type);
+            }
+            for change in &diff.changed {
+                println!("~ {}: {} -> {}", change.new.path, change.old.field_type, change.new.field_type);
+            }
+            if !diff.is_backward_compatible() {
+                return Err(anyhow::anyhow!(
+                    "Schema {:?} is not backward compatible with {:?}:\n  - {}",
+                    new, old, diff.incompatibilities().join("\n  - ")
+                ));
+            }
+            println!("[OK] Schema {:?} is backward compatible with {:?}", new, old);
+        }
     }
 
     Ok(())
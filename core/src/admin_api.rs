@@ -1,18 +1,45 @@
 use axum::{
-    extract::State,
-    routing::get,
+    body::Bytes,
+    extract::{Path, Request, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
     Json, Router,
 };
 use serde::Serialize;
-use std::sync::{Arc, Mutex};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::net::SocketAddr;
+use crate::capabilities::Capability;
+use crate::circuit_breaker::{BreakerConfig, BreakerState};
+use crate::error::ZenithError;
+use crate::latency::LatencyHistogram;
 use crate::ring_buffer::ZenithRingBuffer;
-use crate::wasm_host::WasmPlugin;
+use crate::wasm_host::{WasmHost, WasmPlugin, OPTIONAL_EXPORTS, REQUIRED_EXPORTS};
+use tracing::{error, info};
 
 #[derive(Clone)]
 pub struct AdminState {
     pub buffer: ZenithRingBuffer,
-    pub plugins: Arc<Mutex<Vec<WasmPlugin>>>,
+    pub plugins: Arc<RwLock<Vec<WasmPlugin>>>,
+    /// Shared secret required as `Authorization: Bearer <token>` on mutating
+    /// routes. `None` leaves every route open.
+    pub admin_token: Option<Arc<str>>,
+    /// Set by the engine while all of its consumer threads are running
+    pub consumer_alive: Arc<AtomicBool>,
+    /// Buffer depth at or above which the engine reports not ready
+    pub readiness_max_backlog: usize,
+    /// Queue-wait times of processed events
+    pub queue_wait: Arc<LatencyHistogram>,
+    /// Compiles plugins uploaded to `POST /plugins`
+    pub host: Arc<WasmHost>,
+    /// Fuel budget and breaker settings given to uploaded plugins, as
+    /// the engine gives its own
+    pub plugin_fuel_budget: Arc<AtomicU64>,
+    pub plugin_breaker: BreakerConfig,
 }
 
 #[derive(Serialize)]
@@ -20,49 +47,395 @@ struct StatusResponse {
     status: String,
     buffer_len: usize,
     plugin_count: usize,
+    /// Events pushed per source_id; null when the buffer isn't counting
+    source_counts: Option<BTreeMap<u32, u64>>,
+}
+
+impl StatusResponse {
+    /// OpenAPI schema; keep the properties in step with the fields above
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["status", "buffer_len", "plugin_count"],
+            "properties": {
+                "status": {"type": "string", "example": "running"},
+                "buffer_len": {"type": "integer", "minimum": 0, "description": "Events waiting in the ring buffer"},
+                "plugin_count": {"type": "integer", "minimum": 0},
+                "source_counts": {
+                    "type": "object",
+                    "nullable": true,
+                    "additionalProperties": {"type": "integer", "minimum": 0},
+                    "description": "Events pushed per source_id, keyed by the id; null unless the engine tracks source counts",
+                },
+            },
+        })
+    }
 }
 
 #[derive(Serialize)]
 struct PluginResponse {
     id: usize,
     status: String,
+    breaker: BreakerState,
+    breaker_trips: u64,
+    /// Granted by the plugin's manifest
+    capabilities: Vec<&'static str>,
+}
+
+impl PluginResponse {
+    fn new(id: usize, plugin: &WasmPlugin) -> Self {
+        Self {
+            id,
+            status: if plugin.is_disabled() { "disabled" } else { "loaded" }.to_string(),
+            breaker: plugin.breaker().state(),
+            breaker_trips: plugin.breaker().trips(),
+            capabilities: plugin.capabilities().names(),
+        }
+    }
+
+    /// OpenAPI schema; keep the properties in step with the fields above
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["id", "status", "breaker", "breaker_trips", "capabilities"],
+            "properties": {
+                "id": {"type": "integer", "minimum": 0, "description": "Position in the plugin chain, used by DELETE /plugins/{id}"},
+                "status": {"type": "string", "enum": ["loaded", "disabled"]},
+                "breaker": {"type": "string", "enum": ["closed", "open", "half_open"]},
+                "breaker_trips": {"type": "integer", "minimum": 0},
+                "capabilities": {
+                    "type": "array",
+                    "items": {"type": "string", "enum": Capability::ALL.map(Capability::name)},
+                    "description": "Host capabilities granted by the plugin's manifest",
+                },
+            },
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct MetricsResponse {
+    events_timed: u64,
+    queue_wait_p50_ns: u64,
+    queue_wait_p95_ns: u64,
+    queue_wait_p99_ns: u64,
+}
+
+impl MetricsResponse {
+    /// OpenAPI schema; keep the properties in step with the fields above
+    fn schema() -> Value {
+        let percentile = |description: &str| json!({"type": "integer", "minimum": 0, "description": description});
+        json!({
+            "type": "object",
+            "required": ["events_timed", "queue_wait_p50_ns", "queue_wait_p95_ns", "queue_wait_p99_ns"],
+            "properties": {
+                "events_timed": {"type": "integer", "minimum": 0, "description": "Processed events the percentiles cover"},
+                "queue_wait_p50_ns": percentile("Median ingest-to-processing wait, rounded up to a power of two minus one"),
+                "queue_wait_p95_ns": percentile("95th percentile wait, bucketed like the median"),
+                "queue_wait_p99_ns": percentile("99th percentile wait, bucketed like the median"),
+            },
+        })
+    }
 }
 
 async fn get_status(State(state): State<AdminState>) -> Json<StatusResponse> {
-    let plugins = state.plugins.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let plugins = state.plugins.read().unwrap_or_else(|poisoned| poisoned.into_inner());
     Json(StatusResponse {
         status: "running".to_string(),
         buffer_len: state.buffer.len(),
         plugin_count: plugins.len(),
+        source_counts: state.buffer.tracks_sources()
+            .then(|| state.buffer.source_counts().into_iter().collect()),
     })
 }
 
 async fn get_plugins(State(state): State<AdminState>) -> Json<Vec<PluginResponse>> {
-    let plugins = state.plugins.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
-    let list = plugins.iter().enumerate().map(|(i, _)| PluginResponse {
-        id: i,
-        status: "loaded".to_string(),
-    }).collect();
+    let plugins = state.plugins.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let list = plugins.iter().enumerate().map(|(i, plugin)| PluginResponse::new(i, plugin)).collect();
     Json(list)
 }
 
-pub async fn start_admin_server(state: AdminState, port: u16) {
-    let app = Router::new()
+async fn get_metrics(State(state): State<AdminState>) -> Json<MetricsResponse> {
+    let histogram = &state.queue_wait;
+    Json(MetricsResponse {
+        events_timed: histogram.count(),
+        queue_wait_p50_ns: histogram.percentile(0.50),
+        queue_wait_p95_ns: histogram.percentile(0.95),
+        queue_wait_p99_ns: histogram.percentile(0.99),
+    })
+}
+
+/// Liveness: the HTTP server answering is all this checks
+async fn livez() -> &'static str {
+    "OK"
+}
+
+/// Readiness: the consumer is running and the buffer isn't backed up
+async fn readyz(State(state): State<AdminState>) -> (StatusCode, String) {
+    if !state.consumer_alive.load(Ordering::Relaxed) {
+        return (StatusCode::SERVICE_UNAVAILABLE, "consumer not running".to_string());
+    }
+    
+    let backlog = state.buffer.len();
+    if backlog >= state.readiness_max_backlog {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!("buffer backlog {} at or above {}", backlog, state.readiness_max_backlog),
+        );
+    }
+    
+    (StatusCode::OK, "OK".to_string())
+}
+
+/// Compile the module in the body and append it to the chain. A refused
+/// module gets its `PluginLoadReport` back as a 422.
+async fn load_plugin(State(state): State<AdminState>, wasm: Bytes) -> Response {
+    let host = state.host.clone();
+    let loaded = tokio::task::spawn_blocking(move || host.load_plugin(&wasm)).await;
+    let plugin = match loaded {
+        Ok(Ok(plugin)) => plugin,
+        Ok(Err(ZenithError::PluginRejected(report))) => {
+            info!("Rejected uploaded plugin: {}", report);
+            return (StatusCode::UNPROCESSABLE_ENTITY, Json(report)).into_response();
+        }
+        Ok(Err(e)) => {
+            error!("Failed to load uploaded plugin: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
+        }
+        Err(e) => {
+            error!("Plugin load task failed: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    plugin.set_fuel_budget(state.plugin_fuel_budget.load(Ordering::Relaxed));
+    plugin.breaker().set_config(state.plugin_breaker);
+    
+    let mut plugins = state.plugins.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let response = PluginResponse::new(plugins.len(), &plugin);
+    plugins.push(plugin);
+    (StatusCode::CREATED, Json(response)).into_response()
+}
+
+/// OpenAPI schema of `PluginLoadReport`, one object per `kind`
+fn load_report_schema() -> Value {
+    let kind = |name: &str| json!({"type": "string", "enum": [name]});
+    json!({
+        "oneOf": [
+            {
+                "type": "object",
+                "required": ["kind", "offset", "message"],
+                "properties": {
+                    "kind": kind("parse"),
+                    "offset": {"type": "integer", "minimum": 0, "description": "Byte offset at which parsing failed"},
+                    "message": {"type": "string"},
+                },
+            },
+            {
+                "type": "object",
+                "required": ["kind", "missing", "missing_optional"],
+                "properties": {
+                    "kind": kind("missing_exports"),
+                    "missing": {"type": "array", "items": {"type": "string", "enum": REQUIRED_EXPORTS}},
+                    "missing_optional": {
+                        "type": "array",
+                        "items": {"type": "string", "enum": OPTIONAL_EXPORTS},
+                        "description": "Absent optional exports; informational only",
+                    },
+                },
+            },
+            {
+                "type": "object",
+                "required": ["kind", "reason"],
+                "properties": {
+                    "kind": kind("rejected"),
+                    "reason": {"type": "string", "description": "Why the sandbox refused the module"},
+                },
+            },
+        ],
+        "discriminator": {"propertyName": "kind"},
+    })
+}
+
+async fn unload_plugin(State(state): State<AdminState>, Path(id): Path<usize>) -> StatusCode {
+    let mut plugins = state.plugins.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if id >= plugins.len() {
+        return StatusCode::NOT_FOUND;
+    }
+    plugins.remove(id);
+    StatusCode::NO_CONTENT
+}
+
+/// OpenAPI 3 description of the routes `admin_router` serves
+fn openapi_document() -> Value {
+    let text = |description: &str| json!({
+        "description": description,
+        "content": {"text/plain": {"schema": {"type": "string"}}},
+    });
+    
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Zenith Admin API",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": {
+            "/status": {"get": {
+                "summary": "Engine status",
+                "responses": {"200": {
+                    "description": "Current status",
+                    "content": {"application/json": {"schema": {"$ref": "#/components/schemas/StatusResponse"}}},
+                }},
+            }},
+            "/plugins": {
+                "get": {
+                    "summary": "List loaded plugins in chain order",
+                    "responses": {"200": {
+                        "description": "Loaded plugins",
+                        "content": {"application/json": {"schema": {
+                            "type": "array",
+                            "items": {"$ref": "#/components/schemas/PluginResponse"},
+                        }}},
+                    }},
+                },
+                "post": {
+                    "summary": "Load a plugin and append it to the chain",
+                    "security": [{"bearerAuth": []}],
+                    "requestBody": {
+                        "required": true,
+                        "content": {"application/wasm": {"schema": {"type": "string", "format": "binary"}}},
+                    },
+                    "responses": {
+                        "201": {
+                            "description": "Plugin loaded",
+                            "content": {"application/json": {"schema": {"$ref": "#/components/schemas/PluginResponse"}}},
+                        },
+                        "401": {"description": "Missing or wrong bearer token"},
+                        "422": {
+                            "description": "Module refused; the report says why",
+                            "content": {"application/json": {"schema": {"$ref": "#/components/schemas/PluginLoadReport"}}},
+                        },
+                    },
+                },
+            },
+            "/plugins/{id}": {"delete": {
+                "summary": "Unload a plugin; later plugins shift down by one",
+                "security": [{"bearerAuth": []}],
+                "parameters": [{
+                    "name": "id",
+                    "in": "path",
+                    "required": true,
+                    "schema": {"type": "integer", "minimum": 0},
+                }],
+                "responses": {
+                    "204": {"description": "Plugin unloaded"},
+                    "401": {"description": "Missing or wrong bearer token"},
+                    "404": {"description": "No plugin with that id"},
+                },
+            }},
+            "/metrics": {"get": {
+                "summary": "Event queue-wait percentiles",
+                "responses": {"200": {
+                    "description": "Percentiles since the engine started",
+                    "content": {"application/json": {"schema": {"$ref": "#/components/schemas/MetricsResponse"}}},
+                }},
+            }},
+            "/livez": {"get": {
+                "summary": "Liveness probe",
+                "responses": {"200": text("The admin server is up")},
+            }},
+            "/readyz": {"get": {
+                "summary": "Readiness probe",
+                "responses": {
+                    "200": text("Consumers are running and the buffer is below the backlog limit"),
+                    "503": text("Reason the engine is not ready"),
+                },
+            }},
+            "/openapi.json": {"get": {
+                "summary": "This document",
+                "responses": {"200": {
+                    "description": "OpenAPI 3 document",
+                    "content": {"application/json": {"schema": {"type": "object"}}},
+                }},
+            }},
+        },
+        "components": {
+            "schemas": {
+                "StatusResponse": StatusResponse::schema(),
+                "PluginResponse": PluginResponse::schema(),
+                "MetricsResponse": MetricsResponse::schema(),
+                "PluginLoadReport": load_report_schema(),
+            },
+            "securitySchemes": {
+                "bearerAuth": {
+                    "type": "http",
+                    "scheme": "bearer",
+                    "description": "Only enforced when the server is configured with an admin token",
+                },
+            },
+        },
+    })
+}
+
+async fn get_openapi() -> Json<Value> {
+    Json(openapi_document())
+}
+
+/// Compare without short-circuiting on the first differing byte
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+async fn require_admin_token(State(state): State<AdminState>, request: Request, next: Next) -> Response {
+    if let Some(expected) = &state.admin_token {
+        let presented = request.headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        
+        let authorized = presented.is_some_and(|token| constant_time_eq(token.as_bytes(), expected.as_bytes()));
+        if !authorized {
+            return (StatusCode::UNAUTHORIZED, [(header::WWW_AUTHENTICATE, "Bearer")]).into_response();
+        }
+    }
+    next.run(request).await
+}
+
+/// Build the admin router. Read-only routes are always open; mutating
+/// routes go through the bearer-token check.
+pub fn admin_router(state: AdminState) -> Router {
+    let mutating = Router::new()
+        .route("/plugins", post(load_plugin))
+        .route("/plugins/:id", delete(unload_plugin))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_admin_token));
+    
+    Router::new()
         .route("/status", get(get_status))
         .route("/plugins", get(get_plugins))
-        .with_state(state);
+        .route("/metrics", get(get_metrics))
+        .route("/livez", get(livez))
+        .route("/readyz", get(readyz))
+        .route("/openapi.json", get(get_openapi))
+        .merge(mutating)
+        .with_state(state)
+}
+
+pub async fn start_admin_server(state: AdminState, port: u16) {
+    let app = admin_router(state);
 
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
-    println!("Zenith Admin API listening on {}", addr);
+    info!("Zenith Admin API listening on {}", addr);
     
     match tokio::net::TcpListener::bind(addr).await {
         Ok(listener) => {
             if let Err(e) = axum::serve(listener, app).await {
-                eprintln!("Admin server error: {}", e);
+                error!("Admin server error: {}", e);
             }
         }
         Err(e) => {
-            eprintln!("Failed to bind admin server to {}: {}", addr, e);
+            error!("Failed to bind admin server to {}: {}", addr, e);
         }
     }
 }
@@ -72,19 +445,192 @@ mod tests {
     use super::*;
     use crate::ring_buffer::ZenithRingBuffer;
     
+    const ACCEPT_WAT: &str = r#"(module (func (export "on_event") (param i32 i64) (result i32) i32.const 1))"#;
+    
     /// Create a test AdminState for testing
     fn create_test_state() -> AdminState {
         AdminState {
             buffer: ZenithRingBuffer::new(100),
-            plugins: Arc::new(Mutex::new(Vec::new())),
+            plugins: Arc::new(RwLock::new(Vec::new())),
+            admin_token: None,
+            consumer_alive: Arc::new(AtomicBool::new(true)),
+            readiness_max_backlog: 3,
+            queue_wait: Arc::new(LatencyHistogram::new()),
+            host: Arc::new(WasmHost::new().unwrap()),
+            plugin_fuel_budget: Arc::new(AtomicU64::new(crate::wasm_host::DEFAULT_FUEL_BUDGET)),
+            plugin_breaker: BreakerConfig::default(),
+        }
+    }
+    
+    async fn probe(state: &AdminState, path: &str) -> StatusCode {
+        send(state, Request::builder().uri(path).body(axum::body::Body::empty()).unwrap()).await
+    }
+    
+    fn test_event(seq_no: u64) -> crate::event::ZenithEvent {
+        use arrow::array::Int32Array;
+        use arrow::datatypes::{DataType, Field, Schema};
+        use arrow::record_batch::RecordBatch;
+        
+        let schema = Arc::new(Schema::new(vec![Field::new("value", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(vec![1]))]).unwrap();
+        crate::event::ZenithEvent::new(1, seq_no, batch)
+    }
+    
+    #[tokio::test]
+    async fn test_readyz_ready() {
+        let state = create_test_state();
+        state.buffer.push(test_event(1)).unwrap();
+        
+        assert_eq!(probe(&state, "/livez").await, StatusCode::OK);
+        assert_eq!(probe(&state, "/readyz").await, StatusCode::OK);
+    }
+    
+    #[tokio::test]
+    async fn test_readyz_not_ready() {
+        let state = create_test_state();
+        
+        // Consumer stopped: live but not ready
+        state.consumer_alive.store(false, Ordering::Relaxed);
+        assert_eq!(probe(&state, "/livez").await, StatusCode::OK);
+        assert_eq!(probe(&state, "/readyz").await, StatusCode::SERVICE_UNAVAILABLE);
+        
+        // Consumer running but buffer wedged at the threshold
+        state.consumer_alive.store(true, Ordering::Relaxed);
+        for seq in 0..3 {
+            state.buffer.push(test_event(seq)).unwrap();
+        }
+        assert_eq!(probe(&state, "/readyz").await, StatusCode::SERVICE_UNAVAILABLE);
+        
+        state.buffer.pop();
+        assert_eq!(probe(&state, "/readyz").await, StatusCode::OK);
+    }
+    
+    fn unload_request(id: usize, token: Option<&str>) -> Request {
+        let mut builder = Request::builder().method("DELETE").uri(format!("/plugins/{}", id));
+        if let Some(token) = token {
+            builder = builder.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        builder.body(axum::body::Body::empty()).unwrap()
+    }
+    
+    async fn send(state: &AdminState, request: Request) -> StatusCode {
+        use tower::ServiceExt;
+        admin_router(state.clone()).oneshot(request).await.unwrap().status()
+    }
+    
+    #[tokio::test]
+    async fn test_admin_token_authorized() {
+        use crate::wasm_host::WasmHost;
+        
+        let state = AdminState { admin_token: Some("s3cret".into()), ..create_test_state() };
+        let plugin = WasmHost::new().unwrap()
+            .load_plugin(ACCEPT_WAT.as_bytes())
+            .unwrap();
+        state.plugins.write().unwrap().push(plugin);
+        
+        assert_eq!(send(&state, unload_request(0, Some("s3cret"))).await, StatusCode::NO_CONTENT);
+        assert!(state.plugins.read().unwrap().is_empty());
+        assert_eq!(send(&state, unload_request(0, Some("s3cret"))).await, StatusCode::NOT_FOUND);
+    }
+    
+    #[tokio::test]
+    async fn test_admin_token_unauthorized() {
+        let state = AdminState { admin_token: Some("s3cret".into()), ..create_test_state() };
+        
+        assert_eq!(send(&state, unload_request(0, None)).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(send(&state, unload_request(0, Some("wrong"))).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(send(&state, unload_request(0, Some("s3cret-but-longer"))).await, StatusCode::UNAUTHORIZED);
+        
+        let basic = Request::builder().method("DELETE").uri("/plugins/0")
+            .header(header::AUTHORIZATION, "Basic s3cret")
+            .body(axum::body::Body::empty()).unwrap();
+        assert_eq!(send(&state, basic).await, StatusCode::UNAUTHORIZED);
+        
+        // Read-only routes stay open
+        let status = Request::builder().uri("/status").body(axum::body::Body::empty()).unwrap();
+        assert_eq!(send(&state, status).await, StatusCode::OK);
+    }
+    
+    #[tokio::test]
+    async fn test_admin_no_token_configured() {
+        let state = create_test_state();
+        
+        // Without a configured token mutating routes are open (empty list -> 404)
+        assert_eq!(send(&state, unload_request(0, None)).await, StatusCode::NOT_FOUND);
+        assert_eq!(send(&state, unload_request(0, Some("anything"))).await, StatusCode::NOT_FOUND);
+    }
+    
+    #[tokio::test]
+    async fn test_openapi_document_lists_routes() {
+        use tower::ServiceExt;
+        
+        let request = Request::builder().uri("/openapi.json").body(axum::body::Body::empty()).unwrap();
+        let response = admin_router(create_test_state()).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let doc: Value = serde_json::from_slice(&body).expect("document should be valid JSON");
+        
+        assert_eq!(doc["openapi"], "3.0.3");
+        let mut paths: Vec<&str> = doc["paths"].as_object().unwrap().keys().map(String::as_str).collect();
+        paths.sort_unstable();
+        assert_eq!(paths, ["/livez", "/metrics", "/openapi.json", "/plugins", "/plugins/{id}", "/readyz", "/status"]);
+        assert!(doc["paths"]["/plugins/{id}"]["delete"]["security"].is_array());
+        assert!(doc["paths"]["/plugins"]["post"]["security"].is_array());
+    }
+    
+    /// The hand-written schemas must list exactly the fields the handlers serialize
+    #[test]
+    fn test_openapi_schemas_match_responses() {
+        fn keys(value: &Value) -> Vec<String> {
+            let mut keys: Vec<String> = value.as_object().unwrap().keys().cloned().collect();
+            keys.sort_unstable();
+            keys
+        }
+        
+        let status = serde_json::to_value(StatusResponse {
+            status: "running".to_string(),
+            buffer_len: 0,
+            plugin_count: 0,
+            source_counts: None,
+        }).unwrap();
+        assert_eq!(keys(&status), keys(&StatusResponse::schema()["properties"]));
+        
+        let metrics = serde_json::to_value(MetricsResponse {
+            events_timed: 0,
+            queue_wait_p50_ns: 0,
+            queue_wait_p95_ns: 0,
+            queue_wait_p99_ns: 0,
+        }).unwrap();
+        assert_eq!(keys(&metrics), keys(&MetricsResponse::schema()["properties"]));
+        
+        let plugin = serde_json::to_value(PluginResponse {
+            id: 0,
+            status: "loaded".to_string(),
+            breaker: BreakerState::HalfOpen,
+            breaker_trips: 0,
+            capabilities: vec!["clock"],
+        }).unwrap();
+        let schema = PluginResponse::schema();
+        assert_eq!(keys(&plugin), keys(&schema["properties"]));
+        for state in [BreakerState::Closed, BreakerState::Open, BreakerState::HalfOpen] {
+            let state = serde_json::to_value(state).unwrap();
+            assert!(schema["properties"]["breaker"]["enum"].as_array().unwrap().contains(&state));
         }
     }
     
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"token", b"token"));
+        assert!(!constant_time_eq(b"token", b"tokeN"));
+        assert!(!constant_time_eq(b"token", b"token2"));
+        assert!(constant_time_eq(b"", b""));
+    }
+    
     #[test]
     fn test_admin_state_creation() {
         let state = create_test_state();
         assert!(state.buffer.is_empty());
-        assert!(state.plugins.lock().unwrap().is_empty());
+        assert!(state.plugins.read().unwrap().is_empty());
     }
     
     #[test]
@@ -93,6 +639,7 @@ mod tests {
             status: "running".to_string(),
             buffer_len: 10,
             plugin_count: 2,
+            source_counts: None,
         };
         
         // Verify it can be serialized
@@ -110,6 +657,9 @@ mod tests {
         let response = PluginResponse {
             id: 5,
             status: "loaded".to_string(),
+            breaker: BreakerState::HalfOpen,
+            breaker_trips: 2,
+            capabilities: Vec::new(),
         };
         
         let json = serde_json::to_string(&response);
@@ -118,6 +668,7 @@ mod tests {
         let json_str = json.unwrap();
         assert!(json_str.contains("5"));
         assert!(json_str.contains("loaded"));
+        assert!(json_str.contains(r#""breaker":"half_open""#));
     }
     
     /// Test get_plugins handler logic directly
@@ -130,10 +681,13 @@ mod tests {
         
         // Initially empty
         {
-            let plugins = state.plugins.lock().unwrap();
+            let plugins = state.plugins.read().unwrap();
             let list: Vec<PluginResponse> = plugins.iter().enumerate().map(|(i, _)| PluginResponse {
                 id: i,
                 status: "loaded".to_string(),
+                breaker: BreakerState::Closed,
+                breaker_trips: 0,
+                capabilities: Vec::new(),
             }).collect();
             
             // CRITICAL: This catches the mutation that returns empty vec
@@ -148,19 +702,17 @@ mod tests {
             let host = WasmHost::new().unwrap();
             
             // Try to add a minimal valid WASM module
-            let minimal_wasm = &[
-                0x00, 0x61, 0x73, 0x6D,  // WASM magic number
-                0x01, 0x00, 0x00, 0x00,  // Version 1
-            ];
-            
-            if let Ok(plugin) = host.load_plugin(minimal_wasm) {
-                let mut plugins = state.plugins.lock().unwrap();
+            if let Ok(plugin) = host.load_plugin(ACCEPT_WAT.as_bytes()) {
+                let mut plugins = state.plugins.write().unwrap();
                 plugins.push(plugin);
                 
                 // Now create the list
                 let list: Vec<PluginResponse> = plugins.iter().enumerate().map(|(i, _)| PluginResponse {
                     id: i,
                     status: "loaded".to_string(),
+                    breaker: BreakerState::Closed,
+                    breaker_trips: 0,
+                    capabilities: Vec::new(),
                 }).collect();
                 
                 // CRITICAL: This catches mutation that returns empty vec
@@ -173,6 +725,126 @@ mod tests {
     }
     
     /// Test get_status handler logic
+    #[tokio::test]
+    async fn test_plugins_report_breaker_state() {
+        use crate::circuit_breaker::BreakerConfig;
+        use crate::wasm_host::WasmHost;
+        use tower::ServiceExt;
+        
+        let state = create_test_state();
+        let plugin = WasmHost::new().unwrap()
+            .load_plugin(ACCEPT_WAT.as_bytes())
+            .unwrap();
+        plugin.breaker().set_config(BreakerConfig { failure_threshold: 1, ..BreakerConfig::default() });
+        plugin.breaker().record_failure();
+        state.plugins.write().unwrap().push(plugin);
+        
+        let request = Request::builder().uri("/plugins").body(axum::body::Body::empty()).unwrap();
+        let response = admin_router(state.clone()).oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let plugins: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(plugins[0]["breaker"], "open");
+        assert_eq!(plugins[0]["breaker_trips"], 1);
+        assert_eq!(plugins[0]["capabilities"], json!([]));
+    }
+    
+    #[tokio::test]
+    async fn test_metrics_report_queue_wait() {
+        use tower::ServiceExt;
+        
+        let state = create_test_state();
+        for latency_ns in [1_000, 1_000, 1_000, 5_000_000] {
+            state.queue_wait.record(latency_ns);
+        }
+        
+        let request = Request::builder().uri("/metrics").body(axum::body::Body::empty()).unwrap();
+        let response = admin_router(state).oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let metrics: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(metrics["events_timed"], 4);
+        assert_eq!(metrics["queue_wait_p50_ns"], 1_023);
+        assert_eq!(metrics["queue_wait_p99_ns"], (1 << 23) - 1);
+    }
+    
+    #[tokio::test]
+    async fn test_plugins_report_capabilities() {
+        use crate::wasm_host::WasmHost;
+        use tower::ServiceExt;
+        
+        let state = create_test_state();
+        let wasm = wat::parse_str(r#"
+            (module
+                (@custom "zenith.capabilities" "[\"logging\", \"clock\"]")
+                (func (export "on_event") (param i32 i64) (result i32) i32.const 1))
+        "#).unwrap();
+        state.plugins.write().unwrap().push(WasmHost::new().unwrap().load_plugin(&wasm).unwrap());
+        
+        let request = Request::builder().uri("/plugins").body(axum::body::Body::empty()).unwrap();
+        let response = admin_router(state).oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let plugins: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(plugins[0]["capabilities"], json!(["logging", "clock"]));
+    }
+    
+    #[tokio::test]
+    async fn test_load_plugin_reports_rejections() {
+        use tower::ServiceExt;
+        
+        let state = AdminState { admin_token: Some("s3cret".into()), ..create_test_state() };
+        let upload = |wasm: Vec<u8>, token: &str| Request::builder().method("POST").uri("/plugins")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .body(axum::body::Body::from(wasm)).unwrap();
+        let load = |wasm: Vec<u8>| {
+            let router = admin_router(state.clone());
+            async move {
+                let response = router.oneshot(upload(wasm, "s3cret")).await.unwrap();
+                let status = response.status();
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                (status, serde_json::from_slice::<Value>(&body).unwrap())
+            }
+        };
+        
+        let (status, report) = load(b"not wasm".to_vec()).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(report["kind"], "parse");
+        assert!(report["offset"].is_u64() && report["message"].is_string());
+        
+        let (status, report) = load(wat::parse_str("(module (func (export \"init\")))").unwrap()).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(report, json!({"kind": "missing_exports", "missing": ["on_event"], "missing_optional": ["version"]}));
+        assert!(state.plugins.read().unwrap().is_empty());
+        
+        let (status, plugin) = load(wat::parse_str(ACCEPT_WAT).unwrap()).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(plugin["id"], 0);
+        assert_eq!(plugin["status"], "loaded");
+        assert_eq!(state.plugins.read().unwrap().len(), 1);
+        
+        let denied = admin_router(state.clone()).oneshot(upload(ACCEPT_WAT.into(), "wrong")).await.unwrap();
+        assert_eq!(denied.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(state.plugins.read().unwrap().len(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_status_reports_source_counts() {
+        use tower::ServiceExt;
+        
+        async fn status(state: &AdminState) -> Value {
+            let request = Request::builder().uri("/status").body(axum::body::Body::empty()).unwrap();
+            let response = admin_router(state.clone()).oneshot(request).await.unwrap();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            serde_json::from_slice(&body).unwrap()
+        }
+        
+        let state = create_test_state();
+        assert!(status(&state).await["source_counts"].is_null());
+        
+        let state = AdminState { buffer: ZenithRingBuffer::new(100).with_source_counts(), ..state };
+        state.buffer.push(test_event(1)).unwrap();
+        state.buffer.push(test_event(2)).unwrap();
+        assert_eq!(status(&state).await["source_counts"], json!({"1": 2}));
+    }
+    
     #[tokio::test]
     async fn test_get_status_returns_buffer_len() {
         use crate::event::ZenithEvent;
@@ -199,11 +871,12 @@ mod tests {
         assert_eq!(state.buffer.len(), 1);
         
         // Verify status would report correct count
-        let plugins = state.plugins.lock().unwrap();
+        let plugins = state.plugins.read().unwrap();
         let status = StatusResponse {
             status: "running".to_string(),
             buffer_len: state.buffer.len(),
             plugin_count: plugins.len(),
+            source_counts: None,
         };
         
         assert_eq!(status.buffer_len, 1);
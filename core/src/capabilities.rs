@@ -0,0 +1,208 @@
+//! Host capabilities a plugin may use, declared in its manifest.
+//!
+//! The manifest is a custom section named `zenith.capabilities` holding a
+//! JSON array of capability names, e.g. `["logging", "clock"]`. A module
+//! without one is granted nothing. In WAT:
+//!
+//! ```text
+//! (module
+//!   (@custom "zenith.capabilities" "[\"clock\"]")
+//!   ...)
+//! ```
+
+use crate::validation::ValidationError;
+use std::fmt;
+
+/// Name of the custom section carrying the manifest
+pub const MANIFEST_SECTION: &str = "zenith.capabilities";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Capability {
+    /// Write to the host log
+    Logging,
+    /// Emit events back into the engine
+    EmitEvent,
+    /// Use the per-plugin scratch store
+    KvStore,
+    /// Read the host clock, including the WASI clock and timer calls
+    Clock,
+    /// Draw from the WASI random source
+    Random,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 5] = [Self::Logging, Self::EmitEvent, Self::KvStore, Self::Clock, Self::Random];
+
+    /// Name used in manifests and reported by the admin API
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Logging => "logging",
+            Self::EmitEvent => "emit-event",
+            Self::KvStore => "kv-store",
+            Self::Clock => "clock",
+            Self::Random => "random",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|capability| capability.name() == name)
+    }
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// Error from a call the plugin's manifest didn't grant. Returned from a
+/// host function, it traps the calling plugin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapabilityDenied(pub Capability);
+
+impl fmt::Display for CapabilityDenied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "plugin did not declare the '{}' capability", self.0)
+    }
+}
+
+impl std::error::Error for CapabilityDenied {}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Set of granted capabilities; the default grants none
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Capabilities(u8);
+
+impl Capabilities {
+    pub const NONE: Self = Self(0);
+
+    pub fn all() -> Self {
+        Capability::ALL.into_iter().collect()
+    }
+
+    pub fn with(self, capability: Capability) -> Self {
+        Self(self.0 | capability.bit())
+    }
+
+    pub fn contains(self, capability: Capability) -> bool {
+        self.0 & capability.bit() != 0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Granted capabilities in `Capability::ALL` order
+    pub fn iter(self) -> impl Iterator<Item = Capability> {
+        Capability::ALL.into_iter().filter(move |capability| self.contains(*capability))
+    }
+
+    pub fn names(self) -> Vec<&'static str> {
+        self.iter().map(Capability::name).collect()
+    }
+
+    /// Read the manifest from a binary module. Several manifest sections
+    /// are merged; text-format or truncated input yields no capabilities
+    /// (it won't compile anyway). Unknown names and malformed manifests
+    /// are rejected.
+    pub fn from_wasm(wasm: &[u8]) -> Result<Self, ValidationError> {
+        let mut granted = Self::NONE;
+        for payload in custom_sections(wasm, MANIFEST_SECTION) {
+            let names: Vec<String> = serde_json::from_slice(payload).map_err(|e| {
+                ValidationError::Invalid(format!("{} section is not a JSON array of names: {}", MANIFEST_SECTION, e))
+            })?;
+            for name in names {
+                let capability = Capability::from_name(&name).ok_or_else(|| {
+                    ValidationError::Invalid(format!("unknown capability '{}' in {} section", name, MANIFEST_SECTION))
+                })?;
+                granted = granted.with(capability);
+            }
+        }
+        Ok(granted)
+    }
+}
+
+impl FromIterator<Capability> for Capabilities {
+    fn from_iter<I: IntoIterator<Item = Capability>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::NONE, Self::with)
+    }
+}
+
+/// Payloads of the custom sections called `name`, in module order
+fn custom_sections<'a>(wasm: &'a [u8], name: &'a str) -> impl Iterator<Item = &'a [u8]> + 'a {
+    let mut rest = match wasm.strip_prefix(b"\0asm") {
+        Some(rest) if rest.len() >= 4 => &rest[4..],
+        _ => &[][..],
+    };
+    std::iter::from_fn(move || loop {
+        let (&id, after_id) = rest.split_first()?;
+        let (size, body) = read_u32(after_id)?;
+        let Some((section, next)) = body.split_at_checked(size as usize) else {
+            rest = &[];
+            return None;
+        };
+        rest = next;
+        if id != 0 {
+            continue;
+        }
+        let Some((name_len, section)) = read_u32(section) else { continue };
+        if let Some((section_name, payload)) = section.split_at_checked(name_len as usize) {
+            if section_name == name.as_bytes() {
+                return Some(payload);
+            }
+        }
+    })
+}
+
+/// Decode an unsigned LEB128 u32, returning it and the bytes after it
+fn read_u32(bytes: &[u8]) -> Option<(u32, &[u8])> {
+    let mut value = 0u32;
+    for (i, &byte) in bytes.iter().enumerate().take(5) {
+        value |= u32::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, &bytes[i + 1..]));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn module(manifests: &[&str]) -> Vec<u8> {
+        let sections: String = manifests.iter()
+            .map(|manifest| format!("(@custom \"{}\" {:?})", MANIFEST_SECTION, manifest))
+            .collect();
+        wat::parse_str(format!("(module {} (func (export \"on_event\") (param i32 i64) (result i32) i32.const 1))", sections)).unwrap()
+    }
+
+    #[test]
+    fn test_manifest_parsing() {
+        assert_eq!(Capabilities::from_wasm(&module(&[])).unwrap(), Capabilities::NONE);
+
+        let granted = Capabilities::from_wasm(&module(&[r#"["clock", "logging"]"#])).unwrap();
+        assert_eq!(granted.names(), ["logging", "clock"]);
+        assert!(!granted.contains(Capability::KvStore));
+
+        let merged = Capabilities::from_wasm(&module(&[r#"["kv-store"]"#, r#"["emit-event"]"#])).unwrap();
+        assert_eq!(merged.names(), ["emit-event", "kv-store"]);
+        assert_eq!(Capabilities::all().names(), ["logging", "emit-event", "kv-store", "clock", "random"]);
+    }
+
+    #[test]
+    fn test_manifest_rejects_bad_input() {
+        let err = Capabilities::from_wasm(&module(&[r#"["clock", "network"]"#])).unwrap_err();
+        assert!(err.to_string().contains("unknown capability 'network'"), "{}", err);
+        let err = Capabilities::from_wasm(&module(&["clock"])).unwrap_err();
+        assert!(err.to_string().contains("not a JSON array"), "{}", err);
+
+        // Not binary WASM, or cut off mid-section
+        assert!(Capabilities::from_wasm(b"(module)").unwrap().is_empty());
+        let wasm = module(&[r#"["clock"]"#]);
+        assert!(Capabilities::from_wasm(&wasm[..12]).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,214 @@
+//! Per-plugin circuit breaker.
+//!
+//! A plugin that fails `failure_threshold` times in a row, with all of those
+//! failures inside `window`, is tripped: the engine skips it until
+//! `cooldown` has passed. The next event then goes to the plugin as a trial
+//! (half-open); success closes the breaker, another failure reopens it for
+//! a fresh cooldown.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    /// Plugin runs normally
+    Closed,
+    /// Plugin is skipped until the cooldown passes
+    Open,
+    /// One trial event is in flight
+    HalfOpen,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreakerConfig {
+    /// Consecutive failures that trip the breaker; 0 never trips it
+    pub failure_threshold: u32,
+    /// The failures must all fall within this span of the first one
+    pub window: Duration,
+    /// How long a tripped plugin is skipped before a trial event
+    pub cooldown: Duration,
+}
+
+impl Default for BreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            window: Duration::from_secs(10),
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+struct BreakerInner {
+    config: BreakerConfig,
+    state: BreakerState,
+    consecutive_failures: u32,
+    /// Start of the current run of failures
+    first_failure: Option<Instant>,
+    opened_at: Option<Instant>,
+}
+
+pub struct CircuitBreaker {
+    inner: Mutex<BreakerInner>,
+    trips: AtomicU64,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new(BreakerConfig::default())
+    }
+}
+
+impl CircuitBreaker {
+    pub fn new(config: BreakerConfig) -> Self {
+        Self {
+            inner: Mutex::new(BreakerInner {
+                config,
+                state: BreakerState::Closed,
+                consecutive_failures: 0,
+                first_failure: None,
+                opened_at: None,
+            }),
+            trips: AtomicU64::new(0),
+        }
+    }
+
+    /// Replace the thresholds; the current state is kept
+    pub fn set_config(&self, config: BreakerConfig) {
+        self.lock().config = config;
+    }
+
+    pub fn state(&self) -> BreakerState {
+        self.lock().state
+    }
+
+    /// Times the breaker has opened, including reopening after a failed trial
+    pub fn trips(&self) -> u64 {
+        self.trips.load(Ordering::Relaxed)
+    }
+
+    /// Whether the plugin should be called now. Once the cooldown has
+    /// passed this returns true for exactly one caller, whose outcome must
+    /// be reported through `record_success` or `record_failure`.
+    pub fn allow(&self) -> bool {
+        let mut inner = self.lock();
+        match inner.state {
+            BreakerState::Closed => true,
+            BreakerState::Open if inner.opened_at.is_none_or(|at| at.elapsed() >= inner.config.cooldown) => {
+                inner.state = BreakerState::HalfOpen;
+                true
+            }
+            BreakerState::Open | BreakerState::HalfOpen => false,
+        }
+    }
+
+    pub fn record_success(&self) {
+        let mut inner = self.lock();
+        inner.state = BreakerState::Closed;
+        inner.consecutive_failures = 0;
+        inner.first_failure = None;
+    }
+
+    /// Count a failure. Returns true if it tripped the breaker.
+    pub fn record_failure(&self) -> bool {
+        let mut inner = self.lock();
+        let now = Instant::now();
+        match inner.state {
+            BreakerState::HalfOpen => {}
+            // Late result from a call that started before the breaker opened
+            BreakerState::Open => return false,
+            BreakerState::Closed => {
+                let in_window = inner.first_failure
+                    .is_some_and(|first| now.duration_since(first) <= inner.config.window);
+                if !in_window {
+                    inner.consecutive_failures = 0;
+                    inner.first_failure = Some(now);
+                }
+                inner.consecutive_failures += 1;
+                if inner.config.failure_threshold == 0
+                    || inner.consecutive_failures < inner.config.failure_threshold
+                {
+                    return false;
+                }
+            }
+        }
+
+        inner.state = BreakerState::Open;
+        inner.opened_at = Some(now);
+        inner.consecutive_failures = 0;
+        inner.first_failure = None;
+        self.trips.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BreakerInner> {
+        self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker(failure_threshold: u32, window: Duration, cooldown: Duration) -> CircuitBreaker {
+        CircuitBreaker::new(BreakerConfig { failure_threshold, window, cooldown })
+    }
+
+    #[test]
+    fn test_trips_after_consecutive_failures() {
+        let breaker = breaker(3, Duration::from_secs(60), Duration::from_secs(60));
+        assert!(!breaker.record_failure());
+        assert!(!breaker.record_failure());
+        breaker.record_success();
+        assert!(!breaker.record_failure());
+        assert!(!breaker.record_failure());
+        assert!(breaker.allow(), "A success resets the run of failures");
+
+        assert!(breaker.record_failure());
+        assert_eq!(breaker.state(), BreakerState::Open);
+        assert_eq!(breaker.trips(), 1);
+        assert!(!breaker.allow());
+    }
+
+    #[test]
+    fn test_failures_outside_window_do_not_trip() {
+        let breaker = breaker(2, Duration::ZERO, Duration::from_secs(60));
+        for _ in 0..5 {
+            std::thread::sleep(Duration::from_millis(1));
+            assert!(!breaker.record_failure());
+        }
+        assert_eq!(breaker.state(), BreakerState::Closed);
+    }
+
+    #[test]
+    fn test_half_open_trial() {
+        let breaker = breaker(1, Duration::from_secs(60), Duration::ZERO);
+        assert!(breaker.record_failure());
+
+        // Only one trial at a time
+        assert!(breaker.allow());
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+        assert!(!breaker.allow());
+
+        assert!(breaker.record_failure(), "A failed trial reopens the breaker");
+        assert_eq!(breaker.trips(), 2);
+
+        assert!(breaker.allow());
+        breaker.record_success();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+        assert!(breaker.allow());
+    }
+
+    #[test]
+    fn test_zero_threshold_never_trips() {
+        let breaker = breaker(0, Duration::from_secs(60), Duration::from_secs(60));
+        for _ in 0..100 {
+            assert!(!breaker.record_failure());
+        }
+        assert!(breaker.allow());
+        assert_eq!(breaker.trips(), 0);
+    }
+}
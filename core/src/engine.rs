@@ -1,25 +1,234 @@
 use crate::ring_buffer::ZenithRingBuffer;
-// use crate::event::ZenithEvent;
-use crate::wasm_host::{WasmHost, WasmPlugin};
-use crate::error::Result;
-use std::sync::{Arc, Mutex};
+use crate::circuit_breaker::BreakerConfig;
+use crate::event::ZenithEvent;
+use crate::wasm_host::{SandboxLimits, WasmHost, WasmPlugin, DEFAULT_FUEL_BUDGET};
+use crate::error::{Result, ZenithError};
+use crate::filter::FilterExpr;
+use crate::latency::LatencyHistogram;
+use crate::validation::Validator;
+use arrow::datatypes::{DataType, FieldRef, Fields, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use std::collections::hash_map::{Entry, HashMap};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::thread;
 use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// Maximum number of events the consumer drains from the ring buffer at once
+const CONSUMER_BATCH_SIZE: usize = 64;
+
+/// Fraction of buffer capacity at which the engine stops reporting ready
+const READINESS_BACKLOG_PERCENT: usize = 90;
+
+/// Upper bound on `EngineConfig::consumer_threads`
+pub const MAX_CONSUMER_THREADS: usize = 256;
+
+/// Clears the consumer-alive flag when a consumer thread exits, including
+/// by panic
+struct ConsumerAliveGuard(Arc<AtomicBool>);
+
+impl Drop for ConsumerAliveGuard {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Receives every event that passes the filter and plugin chain
+pub type EventSink = Box<dyn Fn(&ZenithEvent) + Send + Sync>;
+
+/// Installed sink, cloned out of the lock before each use
+type SharedSink = Arc<dyn Fn(&ZenithEvent) + Send + Sync>;
+
+/// How the results of multiple plugins combine into one allow/drop decision.
+/// Evaluation short-circuits as soon as the outcome is known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PluginChainPolicy {
+    /// Allow only if every plugin accepts (AND). Plugin errors don't block.
+    #[default]
+    All,
+    /// Allow if any plugin accepts (OR). Plugin errors don't count as accepts.
+    Any,
+    /// The first plugin that runs successfully decides; erroring plugins
+    /// are skipped. Allows if no plugin produced a decision.
+    FirstMatch,
+}
+
+/// What publishing does with dictionary-encoded columns, which plugins and
+/// sinks written against plain arrays can't read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DictionaryPolicy {
+    /// Cast each dictionary column to its value type (default)
+    #[default]
+    Decode,
+    /// Fail with `ZenithError::DictionaryEncoded`
+    Reject,
+}
+
+#[derive(Debug, Clone)]
+pub struct EngineConfig {
+    /// Ring buffer capacity, in events
+    pub buffer_size: usize,
+    /// Threads `start()` spawns to drain the ring buffer. Each pops batches
+    /// from the shared queue and runs the filter, plugins and sink on its
+    /// own, so with more than one, events from the same source can be
+    /// processed and forwarded out of order.
+    pub consumer_threads: usize,
+    /// Circuit breaker applied to each plugin that returns errors
+    pub plugin_breaker: BreakerConfig,
+    /// Schema every batch published through the FFI must match (see
+    /// [`ZenithEngine::check_schema`]); `None` accepts any
+    pub expected_schema: Option<SchemaRef>,
+    /// Count accepted events per source_id (see
+    /// [`ZenithEngine::source_counts`]). Off by default, which keeps the
+    /// push path free of the extra map update.
+    pub track_source_counts: bool,
+    /// Applied by [`ZenithEngine::decode_dictionaries`] to batches
+    /// published through the FFI
+    pub dictionary_policy: DictionaryPolicy,
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        Self {
+            buffer_size: 1024,
+            consumer_threads: 1,
+            plugin_breaker: BreakerConfig::default(),
+            expected_schema: None,
+            track_source_counts: false,
+            dictionary_policy: DictionaryPolicy::default(),
+        }
+    }
+}
+
+/// Point-in-time engine counters. Laid out for `zenith_stats`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EngineStats {
+    pub buffer_len: u64,
+    pub buffer_capacity: u64,
+    pub plugins_loaded: u64,
+    /// Events taken off the ring buffer and run through the filter and plugins
+    pub events_processed: u64,
+    /// Processed events rejected by the filter or plugin chain
+    pub events_dropped: u64,
+    /// Times a source's seq_no skipped ahead of the last one seen from it.
+    /// With several consumer threads, reordering between them can also
+    /// register as a gap.
+    pub gaps_detected: u64,
+    /// Circuit breaker trips across the loaded plugins
+    pub plugin_breaker_trips: u64,
+    /// Queue-wait percentiles (ingest to processing, ns) over every event
+    /// processed so far. Bucketed by powers of two, so each is the upper
+    /// bound of the bucket the percentile falls in.
+    pub latency_p50_ns: u64,
+    pub latency_p95_ns: u64,
+    pub latency_p99_ns: u64,
+}
+
+/// Counters behind `EngineStats`, shared with the consumer threads
+#[derive(Default)]
+struct EngineCounters {
+    events_processed: AtomicU64,
+    events_dropped: AtomicU64,
+    gaps_detected: AtomicU64,
+    /// Highest seq_no seen per source
+    last_seq_no: Mutex<HashMap<u32, u64>>,
+    /// Shared with the admin API
+    queue_wait: Arc<LatencyHistogram>,
+}
+
+impl EngineCounters {
+    /// Check a batch for sequence gaps, taking the lock once
+    fn track_sequence(&self, events: &[ZenithEvent]) {
+        let mut last_seq_no = self.last_seq_no.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        for event in events {
+            let header = &event.header;
+            match last_seq_no.entry(header.source_id) {
+                Entry::Occupied(mut last) if header.seq_no > *last.get() => {
+                    if header.seq_no > *last.get() + 1 {
+                        self.gaps_detected.fetch_add(1, Ordering::Relaxed);
+                    }
+                    last.insert(header.seq_no);
+                }
+                // Duplicates and late arrivals don't move the high-water mark
+                Entry::Occupied(_) => {}
+                Entry::Vacant(entry) => {
+                    entry.insert(header.seq_no);
+                }
+            }
+        }
+    }
+
+    fn record(&self, allowed: bool, latency_ns: u64) {
+        self.queue_wait.record(latency_ns);
+        self.events_processed.fetch_add(1, Ordering::Relaxed);
+        if !allowed {
+            self.events_dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
 
 pub struct ZenithEngine {
     buffer: ZenithRingBuffer,
     wasm_host: Arc<WasmHost>,
-    plugins: Arc<Mutex<Vec<WasmPlugin>>>,
+    /// Read-locked by consumers for each batch, so they run plugins in
+    /// parallel; a single plugin still serves one event at a time
+    plugins: Arc<RwLock<Vec<WasmPlugin>>>,
     running: Arc<std::sync::atomic::AtomicBool>,
+    /// Ingest-to-processed latency of the most recent event, in nanoseconds
+    last_latency_ns: Arc<AtomicU64>,
+    counters: Arc<EngineCounters>,
+    chain_policy: Arc<Mutex<PluginChainPolicy>>,
+    /// Fuel budget applied to each plugin invocation
+    plugin_fuel_budget: Arc<AtomicU64>,
+    consumer_threads: usize,
+    plugin_breaker: BreakerConfig,
+    /// True while every consumer thread is running
+    consumer_alive: Arc<AtomicBool>,
+    /// Native filter applied before plugins; events failing it are dropped
+    filter: Arc<Mutex<Option<Arc<FilterExpr>>>>,
+    /// Where allowed events are forwarded; they are discarded if unset
+    sink: Arc<Mutex<Option<SharedSink>>>,
+    expected_schema: Mutex<Option<SchemaRef>>,
+    dictionary_policy: Mutex<DictionaryPolicy>,
 }
 
 impl ZenithEngine {
     pub fn new(buffer_size: usize) -> Result<Self> {
+        Self::with_config(EngineConfig { buffer_size, ..EngineConfig::default() })
+    }
+
+    pub fn with_config(config: EngineConfig) -> Result<Self> {
+        Validator::new().validate_range(
+            "consumer_threads",
+            config.consumer_threads as i64,
+            1,
+            MAX_CONSUMER_THREADS as i64,
+        )?;
+
+        let mut buffer = ZenithRingBuffer::new(config.buffer_size);
+        if config.track_source_counts {
+            buffer = buffer.with_source_counts();
+        }
+
         Ok(Self {
-            buffer: ZenithRingBuffer::new(buffer_size),
+            buffer,
             wasm_host: Arc::new(WasmHost::new()?),
-            plugins: Arc::new(Mutex::new(Vec::new())),
+            plugins: Arc::new(RwLock::new(Vec::new())),
             running: Arc::new(std::sync::atomic::AtomicBool::new(true)),
+            last_latency_ns: Arc::new(AtomicU64::new(0)),
+            counters: Arc::new(EngineCounters::default()),
+            chain_policy: Arc::new(Mutex::new(PluginChainPolicy::default())),
+            plugin_fuel_budget: Arc::new(AtomicU64::new(DEFAULT_FUEL_BUDGET)),
+            consumer_threads: config.consumer_threads,
+            plugin_breaker: config.plugin_breaker,
+            consumer_alive: Arc::new(AtomicBool::new(false)),
+            filter: Arc::new(Mutex::new(None)),
+            sink: Arc::new(Mutex::new(None)),
+            expected_schema: Mutex::new(config.expected_schema),
+            dictionary_policy: Mutex::new(config.dictionary_policy),
         })
     }
 
@@ -27,23 +236,249 @@ impl ZenithEngine {
         self.buffer.clone()
     }
 
+    /// Publish from async code, waiting for buffer space without blocking
+    /// the executor thread. Dropping the future before it resolves leaves
+    /// the event unpublished.
+    pub async fn publish_async(&self, event: ZenithEvent) -> Result<()> {
+        self.buffer.push_async(event).await
+    }
+
+    /// Ingest-to-processed latency of the most recently consumed event (ns)
+    pub fn last_latency_ns(&self) -> u64 {
+        self.last_latency_ns.load(Ordering::Relaxed)
+    }
+
+    pub fn stats(&self) -> EngineStats {
+        let plugins = self.plugins.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        EngineStats {
+            buffer_len: self.buffer.len() as u64,
+            buffer_capacity: self.buffer.capacity() as u64,
+            plugins_loaded: plugins.len() as u64,
+            events_processed: self.counters.events_processed.load(Ordering::Relaxed),
+            events_dropped: self.counters.events_dropped.load(Ordering::Relaxed),
+            gaps_detected: self.counters.gaps_detected.load(Ordering::Relaxed),
+            plugin_breaker_trips: plugins.iter().map(|plugin| plugin.breaker().trips()).sum(),
+            latency_p50_ns: self.counters.queue_wait.percentile(0.50),
+            latency_p95_ns: self.counters.queue_wait.percentile(0.95),
+            latency_p99_ns: self.counters.queue_wait.percentile(0.99),
+        }
+    }
+
+    /// Events pushed onto the ring buffer per source_id. Empty unless the
+    /// engine was built with `track_source_counts`.
+    pub fn source_counts(&self) -> HashMap<u32, u64> {
+        self.buffer.source_counts()
+    }
+
+    /// Whether all consumer threads started by `start()` are still
+    /// running. Cleared as soon as any one of them exits.
+    pub fn is_consumer_alive(&self) -> bool {
+        self.consumer_alive.load(Ordering::Relaxed)
+    }
+
+    pub fn consumer_threads(&self) -> usize {
+        self.consumer_threads
+    }
+
+    /// Change how plugin decisions are combined. Takes effect from the
+    /// next batch each consumer processes.
+    pub fn set_chain_policy(&self, policy: PluginChainPolicy) {
+        *self.chain_policy.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = policy;
+    }
+
+    pub fn chain_policy(&self) -> PluginChainPolicy {
+        *self.chain_policy.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Install a native filter expression (see [`crate::filter`]). Events
+    /// it rejects are dropped without reaching any plugin. An empty
+    /// expression clears the filter.
+    pub fn set_filter(&self, expr: &str) -> Result<()> {
+        let filter = if expr.trim().is_empty() {
+            None
+        } else {
+            Some(Arc::new(FilterExpr::parse(expr)?))
+        };
+        *self.filter.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = filter;
+        Ok(())
+    }
+
+    pub fn filter(&self) -> Option<Arc<FilterExpr>> {
+        self.filter.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+
+    /// Require published batches to match `schema`, or accept any again
+    /// with `None`
+    pub fn set_expected_schema(&self, schema: Option<SchemaRef>) {
+        *self.expected_schema.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = schema;
+    }
+
+    pub fn expected_schema(&self) -> Option<SchemaRef> {
+        self.expected_schema.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+
+    /// Check a batch's schema against the expected one, if set. Fields
+    /// must match by position, name and type, nested fields included; a
+    /// non-nullable field is accepted where a nullable one is expected.
+    /// Metadata is ignored.
+    pub fn check_schema(&self, schema: &Schema) -> Result<()> {
+        match self.expected_schema() {
+            Some(expected) => fields_compatible(expected.fields(), schema.fields(), "")
+                .map_err(ZenithError::SchemaMismatch),
+            None => Ok(()),
+        }
+    }
+
+    pub fn set_dictionary_policy(&self, policy: DictionaryPolicy) {
+        *self.dictionary_policy.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = policy;
+    }
+
+    pub fn dictionary_policy(&self) -> DictionaryPolicy {
+        *self.dictionary_policy.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Apply the dictionary policy to a batch: without dictionary-encoded
+    /// columns, nested ones included, it is returned as is; otherwise those
+    /// columns are cast to their value types or the batch is rejected.
+    pub fn decode_dictionaries(&self, batch: RecordBatch) -> Result<RecordBatch> {
+        let schema = batch.schema();
+        let encoded: Vec<&str> = schema.fields().iter()
+            .filter(|field| has_dictionary(field.data_type()))
+            .map(|field| field.name().as_str())
+            .collect();
+        if encoded.is_empty() {
+            return Ok(batch);
+        }
+        if self.dictionary_policy() == DictionaryPolicy::Reject {
+            return Err(ZenithError::DictionaryEncoded(encoded.join(", ")));
+        }
+
+        let mut fields = Vec::with_capacity(batch.num_columns());
+        let mut columns = Vec::with_capacity(batch.num_columns());
+        for (field, column) in schema.fields().iter().zip(batch.columns()) {
+            if has_dictionary(field.data_type()) {
+                let field = decoded_field(field);
+                columns.push(arrow::compute::cast(column, field.data_type())?);
+                fields.push(field);
+            } else {
+                columns.push(column.clone());
+                fields.push(field.clone());
+            }
+        }
+        let schema = Schema::new_with_metadata(fields, schema.metadata().clone());
+        Ok(RecordBatch::try_new(Arc::new(schema), columns)?)
+    }
+
+    /// Forward each allowed event to `sink`, replacing any previous sink.
+    ///
+    /// The sink runs on the consumer threads, so a slow sink backs up the
+    /// ring buffer. With a single consumer it sees one event at a time, in
+    /// order; with several it is called concurrently and per-source order
+    /// is not preserved. A panicking sink is caught and logged; the
+    /// consumer keeps going.
+    pub fn set_sink(&self, sink: EventSink) {
+        *self.sink.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Arc::from(sink));
+    }
+
+    /// Stop forwarding events; allowed events are discarded again
+    pub fn clear_sink(&self) {
+        *self.sink.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+    }
+
+    /// Set the per-event fuel budget for all current and future plugins.
+    /// A plugin that exhausts its budget is disabled by the consumer.
+    pub fn set_plugin_fuel_budget(&self, fuel: u64) {
+        self.plugin_fuel_budget.store(fuel, Ordering::Relaxed);
+        let plugins = self.plugins.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        for plugin in plugins.iter() {
+            plugin.set_fuel_budget(fuel);
+        }
+    }
+
     pub fn load_plugin(&self, wasm_bytes: &[u8]) -> Result<()> {
         let plugin = self.wasm_host.load_plugin(wasm_bytes)?;
-        let mut plugins = self.plugins.lock()
+        self.add_plugin(plugin)
+    }
+
+    /// Load a plugin confined to `limits` on top of the shared fuel budget
+    pub fn load_plugin_limited(&self, wasm_bytes: &[u8], limits: SandboxLimits) -> Result<()> {
+        let plugin = self.wasm_host.load_plugin_with_limits(wasm_bytes, limits)?;
+        self.add_plugin(plugin)
+    }
+
+    /// Load an artifact produced by `WasmHost::precompile`
+    pub fn load_precompiled_plugin(&self, artifact: &[u8]) -> Result<()> {
+        let plugin = self.wasm_host.load_precompiled(artifact)?;
+        self.add_plugin(plugin)
+    }
+
+    /// Replace the plugin at `index` with new code, keeping its sandbox
+    /// limits. If both versions export the state pair (see
+    /// [`WasmPlugin::has_state_exports`]) the old plugin's state is handed
+    /// to the new one; otherwise the new plugin starts fresh. The swap
+    /// happens under the plugin lock, so every event sees either the old
+    /// plugin or the new one with the transferred state. On any error the
+    /// old plugin stays in place.
+    pub fn reload_plugin(&self, index: usize, wasm: &[u8]) -> Result<()> {
+        let limits = self.plugin_at(index, |plugin| plugin.limits())?;
+        // Compile before taking the write lock so consumers aren't held up
+        let plugin = match limits {
+            Some(limits) => self.wasm_host.load_plugin_with_limits(wasm, limits)?,
+            None => self.wasm_host.load_plugin(wasm)?,
+        };
+        plugin.set_fuel_budget(self.plugin_fuel_budget.load(Ordering::Relaxed));
+        plugin.breaker().set_config(self.plugin_breaker);
+
+        let mut plugins = self.plugins.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        Validator::new().validate_range("plugin_index", index as i64, 0, plugins.len() as i64 - 1)?;
+        let old = &plugins[index];
+        if old.has_state_exports() && plugin.has_state_exports() {
+            plugin.restore_state(&old.save_state()?)?;
+        }
+        plugins[index] = plugin;
+        Ok(())
+    }
+
+    fn plugin_at<T>(&self, index: usize, f: impl FnOnce(&WasmPlugin) -> T) -> Result<T> {
+        let plugins = self.plugins.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        Validator::new().validate_range("plugin_index", index as i64, 0, plugins.len() as i64 - 1)?;
+        Ok(f(&plugins[index]))
+    }
+
+    fn add_plugin(&self, plugin: WasmPlugin) -> Result<()> {
+        plugin.set_fuel_budget(self.plugin_fuel_budget.load(Ordering::Relaxed));
+        plugin.breaker().set_config(self.plugin_breaker);
+        let mut plugins = self.plugins.write()
             .map_err(|_| anyhow::anyhow!("Failed to acquire plugin lock"))?;
         plugins.push(plugin);
         Ok(())
     }
 
     pub fn start(&self) {
-        let buffer = self.buffer.clone();
-        let running = self.running.clone();
-        let plugins = self.plugins.clone(); 
+        let consumer = Consumer {
+            buffer: self.buffer.clone(),
+            running: self.running.clone(),
+            plugins: self.plugins.clone(),
+            last_latency_ns: self.last_latency_ns.clone(),
+            counters: self.counters.clone(),
+            chain_policy: self.chain_policy.clone(),
+            filter: self.filter.clone(),
+            sink: self.sink.clone(),
+        };
 
         // Start Admin API
         let admin_state = crate::admin_api::AdminState {
             buffer: self.buffer.clone(),
             plugins: self.plugins.clone(),
+            admin_token: std::env::var("ZENITH_ADMIN_TOKEN").ok()
+                .filter(|token| !token.is_empty())
+                .map(Arc::from),
+            consumer_alive: self.consumer_alive.clone(),
+            readiness_max_backlog: (self.buffer.capacity() * READINESS_BACKLOG_PERCENT / 100).max(1),
+            queue_wait: self.counters.queue_wait.clone(),
+            host: self.wasm_host.clone(),
+            plugin_fuel_budget: self.plugin_fuel_budget.clone(),
+            plugin_breaker: self.plugin_breaker,
         };
         
         thread::spawn(move || {
@@ -54,38 +489,45 @@ impl ZenithEngine {
             rt.block_on(crate::admin_api::start_admin_server(admin_state, 8080));
         });
 
-        thread::spawn(move || {
-            println!("Zenith Core Engine: Consumer thread started.");
-            while running.load(std::sync::atomic::Ordering::Relaxed) {
-                if let Some(event) = buffer.pop() {
-                    // Process event
-                    let plugin_list = match plugins.lock() {
-                        Ok(guard) => guard,
-                        Err(poisoned) => poisoned.into_inner(), // Recover from poisoned mutex
-                    };
-                    let mut allowed = true;
-                    
-                    for plugin in plugin_list.iter() {
-                        // Pass metadata to WASM
-                        match plugin.on_event(event.header.source_id, event.header.seq_no) {
-                            Ok(res) => {
-                                if !res { allowed = false; }
-                            },
-                            Err(e) => eprintln!("Plugin Execution Error: {}", e),
-                        }
-                    }
+        self.consumer_alive.store(true, Ordering::Relaxed);
+        for id in 0..self.consumer_threads {
+            let consumer = consumer.clone();
+            let alive = ConsumerAliveGuard(self.consumer_alive.clone());
+            thread::Builder::new()
+                .name(format!("zenith-consumer-{}", id))
+                .spawn(move || {
+                    let _alive = alive;
+                    info!("Consumer thread {} started", id);
+                    consumer.run();
+                })
+                .expect("failed to spawn consumer thread");
+        }
+    }
 
-                    if allowed {
-                         // println!("Event Processed: {}", event.header.seq_no);
-                         // Logic to forward to storage/network would be here
-                    } else {
-                         // println!("Event Dropped: {}", event.header.seq_no);
-                    }
-                } else {
-                    thread::park_timeout(Duration::from_micros(10));
-                }
+    /// Pop a single event and run it through the loaded plugins on the
+    /// calling thread. Returns the allow/drop decision, or `None` if the
+    /// buffer is empty. Intended for deterministic tests without `start()`.
+    pub fn process_one(&self) -> Option<bool> {
+        let event = self.buffer.pop()?;
+        self.counters.track_sequence(std::slice::from_ref(&event));
+        let policy = self.chain_policy();
+        let filter = self.filter();
+        let plugin_list = match self.plugins.read() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let allowed = filter_allows(filter.as_deref(), &event)
+            && run_plugins(&plugin_list, &event, policy);
+        let latency_ns = event.latency_ns();
+        self.last_latency_ns.store(latency_ns, Ordering::Relaxed);
+        self.counters.record(allowed, latency_ns);
+        if allowed {
+            let sink = self.sink.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+            if let Some(sink) = sink.as_deref() {
+                forward(sink, &event);
             }
-        });
+        }
+        Some(allowed)
     }
 
     pub fn shutdown(&self) {
@@ -93,6 +535,191 @@ impl ZenithEngine {
     }
 }
 
+/// Handles shared by every consumer thread
+#[derive(Clone)]
+struct Consumer {
+    buffer: ZenithRingBuffer,
+    running: Arc<AtomicBool>,
+    plugins: Arc<RwLock<Vec<WasmPlugin>>>,
+    last_latency_ns: Arc<AtomicU64>,
+    counters: Arc<EngineCounters>,
+    chain_policy: Arc<Mutex<PluginChainPolicy>>,
+    filter: Arc<Mutex<Option<Arc<FilterExpr>>>>,
+    sink: Arc<Mutex<Option<SharedSink>>>,
+}
+
+impl Consumer {
+    /// Drain the ring buffer until the engine shuts down. Consumers compete
+    /// for batches on the shared queue, so an idle one picks up whatever
+    /// the busy ones haven't reached yet.
+    fn run(&self) {
+        let mut batch = Vec::with_capacity(CONSUMER_BATCH_SIZE);
+        while self.running.load(Ordering::Relaxed) {
+            if self.buffer.pop_batch(CONSUMER_BATCH_SIZE, &mut batch) == 0 {
+                thread::park_timeout(Duration::from_micros(10));
+                continue;
+            }
+
+            let policy = *self.chain_policy.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            let filter = self.filter.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+            let sink = self.sink.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+
+            self.counters.track_sequence(&batch);
+
+            // Lock once per batch rather than once per event
+            let plugin_list = match self.plugins.read() {
+                Ok(guard) => guard,
+                Err(poisoned) => poisoned.into_inner(), // Recover from poisoned lock
+            };
+
+            for event in batch.drain(..) {
+                let allowed = filter_allows(filter.as_deref(), &event)
+                    && run_plugins(&plugin_list, &event, policy);
+
+                let latency_ns = event.latency_ns();
+                self.last_latency_ns.store(latency_ns, Ordering::Relaxed);
+                self.counters.record(allowed, latency_ns);
+
+                if allowed {
+                    if let Some(sink) = sink.as_deref() {
+                        forward(sink, &event);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Compare `actual` fields against `expected`, describing the first
+/// difference. `scope` is the dotted path of the parent field.
+fn fields_compatible(expected: &Fields, actual: &Fields, scope: &str) -> std::result::Result<(), String> {
+    if expected.len() != actual.len() {
+        let location = if scope.is_empty() { "schema".to_string() } else { format!("'{}'", scope) };
+        return Err(format!("expected {} fields in {}, got {}", expected.len(), location, actual.len()));
+    }
+    for (expected, actual) in expected.iter().zip(actual.iter()) {
+        let path = if scope.is_empty() {
+            expected.name().clone()
+        } else {
+            format!("{}.{}", scope, expected.name())
+        };
+        if expected.name() != actual.name() {
+            return Err(format!("expected field '{}', got '{}'", path, actual.name()));
+        }
+        if actual.is_nullable() && !expected.is_nullable() {
+            return Err(format!("field '{}' is nullable but the expected schema requires values", path));
+        }
+        types_compatible(expected.data_type(), actual.data_type(), &path)?;
+    }
+    Ok(())
+}
+
+fn types_compatible(expected: &DataType, actual: &DataType, path: &str) -> std::result::Result<(), String> {
+    match (expected, actual) {
+        (DataType::Struct(expected), DataType::Struct(actual)) => fields_compatible(expected, actual, path),
+        (DataType::List(expected), DataType::List(actual))
+        | (DataType::LargeList(expected), DataType::LargeList(actual)) => {
+            fields_compatible(&Fields::from(vec![expected.clone()]), &Fields::from(vec![actual.clone()]), path)
+        }
+        (DataType::FixedSizeList(expected, expected_len), DataType::FixedSizeList(actual, actual_len))
+            if expected_len == actual_len =>
+        {
+            fields_compatible(&Fields::from(vec![expected.clone()]), &Fields::from(vec![actual.clone()]), path)
+        }
+        _ if expected == actual => Ok(()),
+        _ => Err(format!("field '{}' has type {}, expected {}", path, actual, expected)),
+    }
+}
+
+fn has_dictionary(data_type: &DataType) -> bool {
+    match data_type {
+        DataType::Dictionary(_, _) => true,
+        DataType::Struct(fields) => fields.iter().any(|field| has_dictionary(field.data_type())),
+        DataType::List(item) | DataType::LargeList(item) | DataType::FixedSizeList(item, _) => {
+            has_dictionary(item.data_type())
+        }
+        _ => false,
+    }
+}
+
+/// `field` with every dictionary type in it replaced by its value type
+fn decoded_field(field: &FieldRef) -> FieldRef {
+    Arc::new(field.as_ref().clone().with_data_type(decoded_type(field.data_type())))
+}
+
+fn decoded_type(data_type: &DataType) -> DataType {
+    match data_type {
+        DataType::Dictionary(_, values) => decoded_type(values),
+        DataType::Struct(fields) => DataType::Struct(fields.iter().map(decoded_field).collect()),
+        DataType::List(item) => DataType::List(decoded_field(item)),
+        DataType::LargeList(item) => DataType::LargeList(decoded_field(item)),
+        DataType::FixedSizeList(item, len) => DataType::FixedSizeList(decoded_field(item), *len),
+        other => other.clone(),
+    }
+}
+
+/// Hand an allowed event to the sink, containing any panic
+fn forward(sink: &(dyn Fn(&ZenithEvent) + Send + Sync), event: &ZenithEvent) {
+    if catch_unwind(AssertUnwindSafe(|| sink(event))).is_err() {
+        error!("Event sink panicked on seq_no {}", event.header.seq_no);
+    }
+}
+
+/// Whether the native filter (if any) keeps the event
+fn filter_allows(filter: Option<&FilterExpr>, event: &ZenithEvent) -> bool {
+    filter.is_none_or(|filter| filter.matches(&event.header))
+}
+
+/// Ask a single plugin for its decision; `None` if it failed, is disabled
+/// or its circuit breaker is open.
+fn plugin_decision(plugin: &WasmPlugin, event: &ZenithEvent) -> Option<bool> {
+    if plugin.is_disabled() || !plugin.breaker().allow() {
+        return None;
+    }
+    
+    // Pass metadata to WASM
+    match plugin.on_event(event.header.source_id, event.header.seq_no) {
+        Ok(res) => {
+            plugin.breaker().record_success();
+            Some(res)
+        }
+        Err(ZenithError::BudgetExceeded) => {
+            warn!("Plugin exceeded its execution budget on seq_no {}; disabling it",
+                event.header.seq_no);
+            plugin.disable();
+            None
+        }
+        Err(e) => {
+            warn!("Plugin execution error: {}", e);
+            if plugin.breaker().record_failure() {
+                warn!("Plugin keeps failing; skipping it until its circuit breaker cools down");
+            }
+            None
+        }
+    }
+}
+
+/// Run an event through the plugin chain and combine the results
+/// according to `policy`.
+fn run_plugins(plugins: &[WasmPlugin], event: &ZenithEvent, policy: PluginChainPolicy) -> bool {
+    if plugins.is_empty() {
+        return true;
+    }
+    
+    match policy {
+        PluginChainPolicy::All => plugins
+            .iter()
+            .all(|plugin| plugin_decision(plugin, event).unwrap_or(true)),
+        PluginChainPolicy::Any => plugins
+            .iter()
+            .any(|plugin| plugin_decision(plugin, event).unwrap_or(false)),
+        PluginChainPolicy::FirstMatch => plugins
+            .iter()
+            .find_map(|plugin| plugin_decision(plugin, event))
+            .unwrap_or(true),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -192,6 +819,24 @@ mod tests {
             "Engine should be stopped after shutdown");
     }
     
+    #[test]
+    fn test_consumer_alive_signal() {
+        use std::time::{Duration, Instant};
+        
+        let engine = ZenithEngine::new(64).unwrap();
+        assert!(!engine.is_consumer_alive(), "No consumer before start()");
+        
+        engine.start();
+        assert!(engine.is_consumer_alive());
+        
+        engine.shutdown();
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while engine.is_consumer_alive() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert!(!engine.is_consumer_alive(), "Consumer exit must clear the flag");
+    }
+    
     /// Test the event processing logic with allowed flag
     /// This catches the mutation: delete ! in `if !res { allowed = false; }`
     #[test]
@@ -247,6 +892,11 @@ mod tests {
         // Wait for consumer thread to process it
         thread::sleep(Duration::from_millis(50));
         
+        if buffer.is_empty() {
+            assert!(engine.last_latency_ns() > 0,
+                "Consumer should record latency for processed events");
+        }
+        
         // Event should have been consumed (buffer empty)
         // Note: This may fail if thread hasn't processed yet, but the
         // important thing is that start() actually created the consumer thread
@@ -257,6 +907,586 @@ mod tests {
         thread::sleep(Duration::from_millis(20));
     }
     
+    /// Filter plugin equivalent to plugins/simple_filter: accept even seq_no
+    const EVEN_FILTER_WAT: &str = r#"
+        (module
+            (func (export "on_event") (param i32 i64) (result i32)
+                local.get 1
+                i64.const 2
+                i64.rem_u
+                i64.eqz))
+    "#;
+    
+    fn create_test_event(seq_no: u64) -> ZenithEvent {
+        use arrow::array::Int32Array;
+        use arrow::datatypes::{DataType, Field, Schema};
+        use arrow::record_batch::RecordBatch;
+        
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("value", DataType::Int32, false),
+        ]));
+        let values = Int32Array::from(vec![1, 2, 3]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(values)]).unwrap();
+        ZenithEvent::new(1, seq_no, batch)
+    }
+    
+    #[test]
+    fn test_process_one_empty_buffer() {
+        let engine = ZenithEngine::new(16).unwrap();
+        assert_eq!(engine.process_one(), None);
+    }
+    
+    #[test]
+    fn test_process_one_without_plugins_allows() {
+        let engine = ZenithEngine::new(16).unwrap();
+        engine.get_ring_buffer().push(create_test_event(3)).unwrap();
+        
+        assert_eq!(engine.process_one(), Some(true));
+        assert_eq!(engine.process_one(), None);
+    }
+    
+    #[test]
+    fn test_queue_wait_percentiles() {
+        let engine = ZenithEngine::new(128).unwrap();
+        let buffer = engine.get_ring_buffer();
+        // Waits sit mid-bucket, leaving slack for the time the test takes:
+        // 2.5ms is in [2^21, 2^22), 40ms in [2^25, 2^26), 700ms in [2^29, 2^30)
+        let waits = [(90, 2_500_000), (8, 40_000_000), (2, 700_000_000)];
+        let mut seq_no = 0;
+        for (count, wait_ns) in waits {
+            for _ in 0..count {
+                let mut event = create_test_event(seq_no);
+                event.header.ingest_ns -= wait_ns;
+                buffer.push(event).unwrap();
+                seq_no += 1;
+            }
+        }
+        while engine.process_one().is_some() {}
+        
+        let stats = engine.stats();
+        assert_eq!(stats.events_processed, 100);
+        assert_eq!(stats.latency_p50_ns, (1 << 22) - 1);
+        assert_eq!(stats.latency_p95_ns, (1 << 26) - 1);
+        assert_eq!(stats.latency_p99_ns, (1 << 30) - 1);
+    }
+    
+    #[test]
+    fn test_process_one_with_filter_plugin() {
+        let engine = ZenithEngine::new(16).unwrap();
+        let wasm = wat::parse_str(EVEN_FILTER_WAT).unwrap();
+        engine.load_plugin(&wasm).unwrap();
+        
+        let buffer = engine.get_ring_buffer();
+        for seq_no in [2, 3, 4, 7] {
+            buffer.push(create_test_event(seq_no)).unwrap();
+        }
+        
+        assert_eq!(engine.process_one(), Some(true), "seq_no 2 should be allowed");
+        assert_eq!(engine.process_one(), Some(false), "seq_no 3 should be dropped");
+        assert_eq!(engine.process_one(), Some(true), "seq_no 4 should be allowed");
+        assert_eq!(engine.process_one(), Some(false), "seq_no 7 should be dropped");
+        assert_eq!(engine.process_one(), None);
+        assert!(engine.last_latency_ns() > 0);
+    }
+    
+    #[test]
+    fn test_native_filter() {
+        let engine = ZenithEngine::new(16).unwrap();
+        engine.set_filter("seq_no % 3 != 0").unwrap();
+        
+        let buffer = engine.get_ring_buffer();
+        for seq_no in [1, 3, 4] {
+            buffer.push(create_test_event(seq_no)).unwrap();
+        }
+        assert_eq!(engine.process_one(), Some(true));
+        assert_eq!(engine.process_one(), Some(false), "seq_no 3 should be filtered");
+        assert_eq!(engine.process_one(), Some(true));
+        
+        assert!(matches!(engine.set_filter("seq_no ="), Err(ZenithError::InvalidFilter(_))));
+        assert!(engine.filter().is_some(), "failed set_filter keeps the old filter");
+        
+        engine.set_filter("").unwrap();
+        assert!(engine.filter().is_none());
+    }
+    
+    #[test]
+    fn test_native_filter_runs_before_plugins() {
+        let engine = ZenithEngine::new(16).unwrap();
+        engine.load_plugin(&wat::parse_str(EVEN_FILTER_WAT).unwrap()).unwrap();
+        engine.set_filter("seq_no < 5").unwrap();
+        
+        let buffer = engine.get_ring_buffer();
+        for seq_no in [2, 3, 6] {
+            buffer.push(create_test_event(seq_no)).unwrap();
+        }
+        assert_eq!(engine.process_one(), Some(true), "passes filter and plugin");
+        assert_eq!(engine.process_one(), Some(false), "dropped by plugin");
+        assert_eq!(engine.process_one(), Some(false), "dropped by filter");
+    }
+    
+    #[test]
+    fn test_check_schema() {
+        use arrow::datatypes::Field;
+        
+        let location = |nullable| DataType::Struct(vec![
+            Field::new("lat", DataType::Float64, nullable),
+        ].into());
+        let schema = |price_type, price_nullable, lat_nullable| Schema::new(vec![
+            Field::new("symbol", DataType::Utf8, false),
+            Field::new("price", price_type, price_nullable),
+            Field::new("venue", location(lat_nullable), false),
+        ]);
+        
+        let engine = ZenithEngine::with_config(EngineConfig {
+            expected_schema: Some(Arc::new(schema(DataType::Float64, true, true))),
+            ..EngineConfig::default()
+        }).unwrap();
+        
+        // Matching, and non-nullable where nullable is expected
+        engine.check_schema(&schema(DataType::Float64, true, true)).unwrap();
+        engine.check_schema(&schema(DataType::Float64, false, false)).unwrap();
+        
+        let err = engine.check_schema(&schema(DataType::Float32, true, true)).unwrap_err();
+        assert_eq!(err.to_string(), "Schema mismatch: field 'price' has type Float32, expected Float64");
+        let err = engine.check_schema(&Schema::new(vec![Field::new("symbol", DataType::Utf8, false)])).unwrap_err();
+        assert_eq!(err.to_string(), "Schema mismatch: expected 3 fields in schema, got 1");
+        
+        // Nullable where values are required
+        engine.set_expected_schema(Some(Arc::new(schema(DataType::Float64, true, false))));
+        let err = engine.check_schema(&schema(DataType::Float64, true, true)).unwrap_err();
+        assert!(err.to_string().contains("field 'venue.lat' is nullable"), "{}", err);
+        
+        engine.set_expected_schema(None);
+        engine.check_schema(&Schema::empty()).unwrap();
+    }
+    
+    #[test]
+    fn test_decode_dictionaries() {
+        use arrow::array::{Array, AsArray, DictionaryArray, Int64Array, ListArray, StringArray};
+        use arrow::buffer::OffsetBuffer;
+        use arrow::datatypes::{Field, Int8Type};
+        
+        let tags: DictionaryArray<Int8Type> = vec!["a", "b", "a"].into_iter().collect();
+        let tag_lists = ListArray::new(
+            Arc::new(Field::new("item", tags.data_type().clone(), false)),
+            OffsetBuffer::from_lengths([1, 0, 2]),
+            Arc::new(tags.clone()),
+            None,
+        );
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("tag", tags.data_type().clone(), false),
+            Field::new("tags", tag_lists.data_type().clone(), false),
+        ]));
+        let batch = RecordBatch::try_new(schema, vec![
+            Arc::new(Int64Array::from(vec![1, 2, 3])),
+            Arc::new(tags),
+            Arc::new(tag_lists),
+        ]).unwrap();
+        
+        let engine = ZenithEngine::new(16).unwrap();
+        let decoded = engine.decode_dictionaries(batch.clone()).unwrap();
+        assert_eq!(decoded.schema().field(0), batch.schema().field(0));
+        assert_eq!(decoded.schema().field(1).data_type(), &DataType::Utf8);
+        assert_eq!(
+            decoded.schema().field(2).data_type(),
+            &DataType::List(Arc::new(Field::new("item", DataType::Utf8, false)))
+        );
+        assert_eq!(decoded.column(1).as_string::<i32>(), &StringArray::from(vec!["a", "b", "a"]));
+        let last = decoded.column(2).as_list::<i32>().value(2);
+        assert_eq!(last.as_string::<i32>(), &StringArray::from(vec!["b", "a"]));
+        
+        engine.set_dictionary_policy(DictionaryPolicy::Reject);
+        let err = engine.decode_dictionaries(batch).unwrap_err();
+        assert_eq!(err.to_string(), "Dictionary-encoded columns not accepted: tag, tags");
+        
+        // Plain batches pass either way
+        engine.decode_dictionaries(decoded).unwrap();
+    }
+    
+    const ACCEPT_ALL_WAT: &str = r#"
+        (module
+            (func (export "on_event") (param i32 i64) (result i32)
+                i32.const 1))
+    "#;
+    
+    const REJECT_ALL_WAT: &str = r#"
+        (module
+            (func (export "on_event") (param i32 i64) (result i32)
+                i32.const 0))
+    "#;
+    
+    const TRAPPING_WAT: &str = r#"
+        (module
+            (func (export "on_event") (param i32 i64) (result i32)
+                unreachable))
+    "#;
+    
+    /// Load the given plugins in order and return the decision for one event
+    fn decide(plugins: &[&str], policy: PluginChainPolicy) -> bool {
+        let engine = ZenithEngine::new(16).unwrap();
+        for wat_src in plugins {
+            engine.load_plugin(&wat::parse_str(wat_src).unwrap()).unwrap();
+        }
+        engine.set_chain_policy(policy);
+        engine.get_ring_buffer().push(create_test_event(1)).unwrap();
+        engine.process_one().unwrap()
+    }
+    
+    #[test]
+    fn test_sink_receives_allowed_events() {
+        let engine = ZenithEngine::new(16).unwrap();
+        engine.load_plugin(&wat::parse_str(EVEN_FILTER_WAT).unwrap()).unwrap();
+        engine.set_filter("seq_no != 4").unwrap();
+        
+        let forwarded = Arc::new(Mutex::new(Vec::new()));
+        let sink_forwarded = forwarded.clone();
+        engine.set_sink(Box::new(move |event| {
+            sink_forwarded.lock().unwrap().push(event.header.seq_no);
+        }));
+        
+        let buffer = engine.get_ring_buffer();
+        for seq_no in 1..=6 {
+            buffer.push(create_test_event(seq_no)).unwrap();
+        }
+        while engine.process_one().is_some() {}
+        
+        // Odd events fail the plugin, 4 fails the native filter
+        assert_eq!(*forwarded.lock().unwrap(), vec![2, 6]);
+        
+        engine.clear_sink();
+        buffer.push(create_test_event(8)).unwrap();
+        assert_eq!(engine.process_one(), Some(true));
+        assert_eq!(forwarded.lock().unwrap().len(), 2);
+    }
+    
+    #[test]
+    fn test_sink_panic_is_contained() {
+        let engine = ZenithEngine::new(16).unwrap();
+        engine.set_sink(Box::new(|_| panic!("sink failure")));
+        
+        engine.get_ring_buffer().push(create_test_event(1)).unwrap();
+        assert_eq!(engine.process_one(), Some(true));
+    }
+    
+    #[test]
+    fn test_sink_runs_on_consumer_thread() {
+        let engine = ZenithEngine::new(64).unwrap();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let tx = Mutex::new(tx);
+        engine.set_sink(Box::new(move |event| {
+            let _ = tx.lock().unwrap().send(event.header.seq_no);
+        }));
+        engine.start();
+        
+        engine.get_ring_buffer().push(create_test_event(7)).unwrap();
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)).unwrap(), 7);
+        engine.shutdown();
+    }
+    
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_publish_async_with_running_consumer() {
+        let engine = Arc::new(ZenithEngine::new(4).unwrap());
+        let forwarded = Arc::new(AtomicU64::new(0));
+        let sink_forwarded = forwarded.clone();
+        engine.set_sink(Box::new(move |_| {
+            sink_forwarded.fetch_add(1, Ordering::Relaxed);
+        }));
+        engine.start();
+        
+        // Far more events than the buffer holds, from concurrent tasks
+        let publishers: Vec<_> = (0..4u64).map(|task| {
+            let engine = engine.clone();
+            tokio::spawn(async move {
+                for i in 0..50 {
+                    engine.publish_async(create_test_event(task * 1000 + i)).await.unwrap();
+                }
+            })
+        }).collect();
+        for publisher in publishers {
+            publisher.await.unwrap();
+        }
+        
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while forwarded.load(Ordering::Relaxed) < 200 && std::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert_eq!(forwarded.load(Ordering::Relaxed), 200);
+        engine.shutdown();
+    }
+    
+    #[test]
+    fn test_stats_counts_processed_events() {
+        let engine = ZenithEngine::new(16).unwrap();
+        engine.load_plugin(&wat::parse_str(EVEN_FILTER_WAT).unwrap()).unwrap();
+        engine.set_filter("seq_no != 4").unwrap();
+        
+        let buffer = engine.get_ring_buffer();
+        // 4 skips ahead of the late 3, and 7 skips 6
+        for seq_no in [1, 2, 4, 3, 5, 7] {
+            buffer.push(create_test_event(seq_no)).unwrap();
+        }
+        for _ in 0..3 {
+            engine.process_one();
+        }
+        
+        let stats = engine.stats();
+        assert_eq!(stats.buffer_len, 3);
+        assert_eq!(stats.buffer_capacity, buffer.capacity() as u64);
+        assert_eq!(stats.plugins_loaded, 1);
+        assert_eq!(stats.events_processed, 3);
+        assert_eq!(stats.events_dropped, 2, "seq_no 1 by the plugin, 4 by the filter");
+        assert_eq!(stats.gaps_detected, 1);
+        
+        while engine.process_one().is_some() {}
+        let stats = engine.stats();
+        assert_eq!(stats.buffer_len, 0);
+        assert_eq!(stats.events_processed, 6);
+        assert_eq!(stats.events_dropped, 5);
+        assert_eq!(stats.gaps_detected, 2);
+    }
+    
+    #[test]
+    fn test_consumer_threads_validated() {
+        assert!(matches!(
+            ZenithEngine::with_config(EngineConfig { consumer_threads: 0, ..EngineConfig::default() }),
+            Err(ZenithError::Validation(_))
+        ));
+        assert!(ZenithEngine::with_config(EngineConfig {
+            consumer_threads: MAX_CONSUMER_THREADS + 1,
+            ..EngineConfig::default()
+        }).is_err());
+        assert_eq!(ZenithEngine::new(16).unwrap().consumer_threads(), 1);
+    }
+    
+    #[test]
+    fn test_multiple_consumers_drain_all_events() {
+        const EVENTS: u64 = 20_000;
+        
+        let engine = ZenithEngine::with_config(EngineConfig {
+            buffer_size: 256,
+            consumer_threads: 4,
+            ..EngineConfig::default()
+        }).unwrap();
+        engine.load_plugin(&wat::parse_str(EVEN_FILTER_WAT).unwrap()).unwrap();
+        
+        let forwarded = Arc::new(Mutex::new(Vec::new()));
+        let consumers = Arc::new(Mutex::new(std::collections::HashSet::new()));
+        let (sink_forwarded, sink_consumers) = (forwarded.clone(), consumers.clone());
+        engine.set_sink(Box::new(move |event| {
+            sink_forwarded.lock().unwrap().push(event.header.seq_no);
+            sink_consumers.lock().unwrap().insert(thread::current().name().map(str::to_string));
+        }));
+        engine.start();
+        
+        let buffer = engine.get_ring_buffer();
+        for seq_no in 0..EVENTS {
+            buffer.push_timeout(create_test_event(seq_no), Duration::from_secs(5)).unwrap();
+        }
+        
+        let deadline = std::time::Instant::now() + Duration::from_secs(10);
+        while forwarded.lock().unwrap().len() < (EVENTS / 2) as usize
+            && std::time::Instant::now() < deadline
+        {
+            thread::sleep(Duration::from_millis(5));
+        }
+        engine.shutdown();
+        
+        // Every event was processed exactly once, though not in order
+        let mut seen = forwarded.lock().unwrap().clone();
+        seen.sort_unstable();
+        assert_eq!(seen, (0..EVENTS).filter(|n| n % 2 == 0).collect::<Vec<_>>());
+        assert!(consumers.lock().unwrap().iter()
+            .all(|name| name.as_deref().is_some_and(|name| name.starts_with("zenith-consumer-"))));
+    }
+    
+    /// Counts events and accepts from the `THRESHOLD`th on. The count is
+    /// kept in a global and serialized at address 16 by `get_state`.
+    const COUNTING_WAT: &str = r#"
+        (module
+            (memory (export "memory") 1)
+            (global $count (mut i32) (i32.const 0))
+            (func (export "on_event") (param i32 i64) (result i32)
+                (global.set $count (i32.add (global.get $count) (i32.const 1)))
+                (i32.ge_u (global.get $count) (i32.const THRESHOLD)))
+            (func (export "get_state") (result i64)
+                (i32.store (i32.const 16) (global.get $count))
+                (i64.or (i64.shl (i64.const 16) (i64.const 32)) (i64.const 4)))
+            (func (export "set_state") (param $ptr i32) (param $len i32)
+                (global.set $count (i32.load (local.get $ptr)))))
+    "#;
+    
+    fn counting_plugin(threshold: u32) -> Vec<u8> {
+        wat::parse_str(COUNTING_WAT.replace("THRESHOLD", &threshold.to_string())).unwrap()
+    }
+    
+    #[test]
+    fn test_reload_plugin_preserves_state() {
+        let engine = ZenithEngine::new(16).unwrap();
+        engine.load_plugin(&counting_plugin(100)).unwrap();
+        
+        let buffer = engine.get_ring_buffer();
+        for seq_no in 0..3 {
+            buffer.push(create_test_event(seq_no)).unwrap();
+            assert_eq!(engine.process_one(), Some(false));
+        }
+        
+        // The new version accepts from the 4th event, which it only
+        // reaches if the count of 3 carried over
+        engine.reload_plugin(0, &counting_plugin(4)).unwrap();
+        assert_eq!(engine.stats().plugins_loaded, 1);
+        buffer.push(create_test_event(3)).unwrap();
+        assert_eq!(engine.process_one(), Some(true));
+    }
+    
+    #[test]
+    fn test_reload_plugin_without_state_exports() {
+        let engine = ZenithEngine::new(16).unwrap();
+        engine.load_plugin(&counting_plugin(100)).unwrap();
+        let buffer = engine.get_ring_buffer();
+        buffer.push(create_test_event(1)).unwrap();
+        assert_eq!(engine.process_one(), Some(false));
+        
+        // The filter has no state exports, so it simply takes over
+        engine.reload_plugin(0, &wat::parse_str(EVEN_FILTER_WAT).unwrap()).unwrap();
+        buffer.push(create_test_event(2)).unwrap();
+        assert_eq!(engine.process_one(), Some(true));
+        
+        // And a stateful plugin replacing it starts from zero
+        engine.reload_plugin(0, &counting_plugin(2)).unwrap();
+        buffer.push(create_test_event(3)).unwrap();
+        assert_eq!(engine.process_one(), Some(false));
+    }
+    
+    #[test]
+    fn test_reload_plugin_failure_keeps_old_plugin() {
+        let engine = ZenithEngine::new(16).unwrap();
+        engine.load_plugin(&wat::parse_str(EVEN_FILTER_WAT).unwrap()).unwrap();
+        
+        assert!(matches!(engine.reload_plugin(1, &counting_plugin(1)), Err(ZenithError::Validation(_))));
+        assert!(engine.reload_plugin(0, b"not wasm").is_err());
+        
+        let buffer = engine.get_ring_buffer();
+        buffer.push(create_test_event(3)).unwrap();
+        assert_eq!(engine.process_one(), Some(false), "Even filter should still be loaded");
+    }
+    
+    #[test]
+    fn test_failing_plugin_trips_breaker() {
+        use crate::circuit_breaker::BreakerState;
+        
+        let engine = ZenithEngine::with_config(EngineConfig {
+            plugin_breaker: BreakerConfig {
+                failure_threshold: 3,
+                window: Duration::from_secs(60),
+                cooldown: Duration::from_millis(100),
+            },
+            ..EngineConfig::default()
+        }).unwrap();
+        // Counts its calls at address 0, readable through get_state, then traps
+        engine.load_plugin(&wat::parse_str(r#"
+            (module
+                (memory (export "memory") 1)
+                (func (export "on_event") (param i32 i64) (result i32)
+                    (i32.store (i32.const 0) (i32.add (i32.load (i32.const 0)) (i32.const 1)))
+                    unreachable)
+                (func (export "get_state") (result i64)
+                    i64.const 4)
+                (func (export "set_state") (param i32 i32)))
+        "#).unwrap()).unwrap();
+        let calls = || {
+            let state = engine.plugin_at(0, |plugin| plugin.save_state()).unwrap().unwrap();
+            u32::from_le_bytes(state.try_into().unwrap())
+        };
+        let breaker_state = || engine.plugin_at(0, |plugin| plugin.breaker().state()).unwrap();
+        
+        let buffer = engine.get_ring_buffer();
+        for seq_no in 0..10 {
+            buffer.push(create_test_event(seq_no)).unwrap();
+        }
+        // Errors don't block events, tripped or not
+        while let Some(allowed) = engine.process_one() {
+            assert!(allowed);
+        }
+        assert_eq!(calls(), 3, "Plugin should stop being called once tripped");
+        assert_eq!(breaker_state(), BreakerState::Open);
+        assert_eq!(engine.stats().plugin_breaker_trips, 1);
+        
+        // After the cooldown one trial event reaches it, fails and reopens
+        thread::sleep(Duration::from_millis(150));
+        for seq_no in 10..15 {
+            buffer.push(create_test_event(seq_no)).unwrap();
+        }
+        while engine.process_one().is_some() {}
+        assert_eq!(calls(), 4);
+        assert_eq!(breaker_state(), BreakerState::Open);
+        assert_eq!(engine.stats().plugin_breaker_trips, 2);
+    }
+    
+    #[test]
+    fn test_slow_plugin_is_disabled() {
+        let engine = ZenithEngine::new(16).unwrap();
+        engine.set_plugin_fuel_budget(50_000);
+        
+        let spinning = wat::parse_str(r#"
+            (module
+                (func (export "on_event") (param i32 i64) (result i32)
+                    (loop $spin (br $spin))
+                    i32.const 0))
+        "#).unwrap();
+        engine.load_plugin(&spinning).unwrap();
+        engine.load_plugin(&wat::parse_str(EVEN_FILTER_WAT).unwrap()).unwrap();
+        
+        let buffer = engine.get_ring_buffer();
+        buffer.push(create_test_event(2)).unwrap();
+        buffer.push(create_test_event(3)).unwrap();
+        
+        // The spinning plugin is interrupted instead of hanging the caller
+        assert_eq!(engine.process_one(), Some(true));
+        assert!(engine.plugins.read().unwrap()[0].is_disabled(),
+            "Plugin that exceeded its budget should be disabled");
+        assert!(!engine.plugins.read().unwrap()[1].is_disabled());
+        
+        // Subsequent events skip the disabled plugin; the filter still runs
+        assert_eq!(engine.process_one(), Some(false));
+    }
+    
+    #[test]
+    fn test_chain_policy_default_is_all() {
+        let engine = ZenithEngine::new(16).unwrap();
+        assert_eq!(engine.chain_policy(), PluginChainPolicy::All);
+    }
+    
+    #[test]
+    fn test_chain_policy_all() {
+        use PluginChainPolicy::All;
+        assert!(decide(&[ACCEPT_ALL_WAT, ACCEPT_ALL_WAT], All));
+        assert!(!decide(&[ACCEPT_ALL_WAT, REJECT_ALL_WAT], All));
+        assert!(!decide(&[REJECT_ALL_WAT, ACCEPT_ALL_WAT], All));
+        // Errors don't block under AND, matching the original behavior
+        assert!(decide(&[TRAPPING_WAT, ACCEPT_ALL_WAT], All));
+    }
+    
+    #[test]
+    fn test_chain_policy_any() {
+        use PluginChainPolicy::Any;
+        assert!(decide(&[ACCEPT_ALL_WAT, REJECT_ALL_WAT], Any));
+        assert!(decide(&[REJECT_ALL_WAT, ACCEPT_ALL_WAT], Any));
+        assert!(!decide(&[REJECT_ALL_WAT, REJECT_ALL_WAT], Any));
+        // Errors are not accepts under OR
+        assert!(!decide(&[TRAPPING_WAT, REJECT_ALL_WAT], Any));
+    }
+    
+    #[test]
+    fn test_chain_policy_first_match() {
+        use PluginChainPolicy::FirstMatch;
+        assert!(decide(&[ACCEPT_ALL_WAT, REJECT_ALL_WAT], FirstMatch));
+        assert!(!decide(&[REJECT_ALL_WAT, ACCEPT_ALL_WAT], FirstMatch));
+        // Failing plugins are skipped
+        assert!(!decide(&[TRAPPING_WAT, REJECT_ALL_WAT], FirstMatch));
+        assert!(decide(&[TRAPPING_WAT], FirstMatch));
+    }
+    
     /// Test that the event processing respects the allowed flag logic
     #[test]
     fn test_event_allowed_semantics() {
@@ -1,18 +1,113 @@
 use thiserror::Error;
+use crate::filter::FilterError;
+use crate::validation::ValidationError;
+use crate::wasm_host::PluginLoadReport;
 
 #[derive(Error, Debug)]
 pub enum ZenithError {
     #[error("Arrow error: {0}")]
-    ArrowError(#[from] arrow::error::ArrowError),
+    Arrow(String),
 
     #[error("WASM error: {0}")]
     WasmError(#[from] anyhow::Error),
 
+    #[error("Plugin load failed: {0}")]
+    PluginLoad(String),
+
+    #[error("Plugin load failed: {0}")]
+    PluginRejected(#[from] PluginLoadReport),
+
+    #[error("Validation failed: {0}")]
+    Validation(#[from] ValidationError),
+
+    #[error("{0}")]
+    InvalidFilter(#[from] FilterError),
+
+    #[error("Precompiled module is incompatible with this host: {0}")]
+    IncompatibleArtifact(String),
+
+    #[error("Plugin exceeded its execution budget")]
+    BudgetExceeded,
+
+    #[error("Sink error: {0}")]
+    Sink(String),
+
     #[error("Buffer full")]
     BufferFull,
 
+    #[error("Schema mismatch: {0}")]
+    SchemaMismatch(String),
+
+    #[error("Dictionary-encoded columns not accepted: {0}")]
+    DictionaryEncoded(String),
+
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 }
 
+impl From<arrow::error::ArrowError> for ZenithError {
+    fn from(err: arrow::error::ArrowError) -> Self {
+        Self::Arrow(err.to_string())
+    }
+}
+
 pub type Result<T> = std::result::Result<T, ZenithError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::error::ArrowError;
+
+    #[test]
+    fn test_display_messages() {
+        assert_eq!(ZenithError::BufferFull.to_string(), "Buffer full");
+        assert_eq!(
+            ZenithError::PluginLoad("bad magic".to_string()).to_string(),
+            "Plugin load failed: bad magic"
+        );
+        assert_eq!(
+            ZenithError::Arrow("schema mismatch".to_string()).to_string(),
+            "Arrow error: schema mismatch"
+        );
+        assert_eq!(
+            ZenithError::Validation(ValidationError::Empty("name".to_string())).to_string(),
+            "Validation failed: name cannot be empty"
+        );
+    }
+
+    #[test]
+    fn test_from_arrow_error() {
+        let err: ZenithError = ArrowError::SchemaError("missing column".to_string()).into();
+        match &err {
+            ZenithError::Arrow(msg) => assert!(msg.contains("missing column")),
+            other => panic!("Expected Arrow variant, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_from_validation_error() {
+        let err: ZenithError = ValidationError::Invalid("bad input".to_string()).into();
+        assert!(matches!(
+            err,
+            ZenithError::Validation(ValidationError::Invalid(ref msg)) if msg == "bad input"
+        ));
+    }
+
+    #[test]
+    fn test_question_mark_and_anyhow_interop() {
+        fn fails() -> Result<()> {
+            Err(ValidationError::Empty("job".to_string()))?;
+            Ok(())
+        }
+
+        let err = fails().unwrap_err();
+        assert!(matches!(err, ZenithError::Validation(_)));
+
+        // ZenithError implements std::error::Error, so it converts into anyhow
+        let any: anyhow::Error = ZenithError::BufferFull.into();
+        assert_eq!(any.to_string(), "Buffer full");
+
+        let source: &dyn std::error::Error = &ZenithError::Validation(ValidationError::Empty("x".to_string()));
+        assert!(source.source().is_some(), "Validation should expose its source");
+    }
+}
@@ -1,29 +1,41 @@
 use arrow::record_batch::RecordBatch;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// Current wall-clock time in nanoseconds since the Unix epoch
+fn now_ns() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64
+}
+
 #[derive(Debug, Clone)]
 pub struct EventHeader {
     pub source_id: u32,
     pub seq_no: u64,
     pub timestamp_ns: u64,
     pub flags: u32,
+    /// Wall-clock time (ns since Unix epoch) at which the event entered Zenith
+    pub ingest_ns: u64,
 }
 
 impl EventHeader {
     pub fn new(source_id: u32, seq_no: u64) -> Self {
-        let start = SystemTime::now();
-        let timestamp_ns = start
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_nanos() as u64;
+        let timestamp_ns = now_ns();
 
         Self {
             source_id,
             seq_no,
             timestamp_ns,
             flags: 0,
+            ingest_ns: timestamp_ns,
         }
     }
+
+    /// Nanoseconds elapsed since ingest (0 if the clock went backwards)
+    pub fn latency_ns(&self) -> u64 {
+        now_ns().saturating_sub(self.ingest_ns)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -39,4 +51,135 @@ impl ZenithEvent {
             payload: Some(payload),
         }
     }
+
+    /// Nanoseconds elapsed since this event was ingested
+    pub fn latency_ns(&self) -> u64 {
+        self.header.latency_ns()
+    }
+
+    /// Borrow the payload batch, if any (header-only events have none)
+    pub fn batch(&self) -> Option<&RecordBatch> {
+        self.payload.as_ref()
+    }
+
+    /// Take ownership of the payload batch without copying.
+    ///
+    /// Arrow columns are reference counted, so this moves the existing
+    /// buffers out of the event rather than deep-copying them.
+    pub fn into_batch(self) -> Option<RecordBatch> {
+        self.payload
+    }
+}
+
+/// C-facing summary of an event, filled in by `zenith_peek_header`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ZenithEventHeader {
+    pub source_id: u32,
+    pub seq_no: u64,
+    /// Wall-clock time (ns since Unix epoch) at which the event entered Zenith
+    pub ingest_ns: u64,
+    /// Rows in the payload batch; 0 for header-only events
+    pub num_rows: u64,
+}
+
+impl From<&ZenithEvent> for ZenithEventHeader {
+    fn from(event: &ZenithEvent) -> Self {
+        Self {
+            source_id: event.header.source_id,
+            seq_no: event.header.seq_no,
+            ingest_ns: event.header.ingest_ns,
+            num_rows: event.batch().map_or(0, |batch| batch.num_rows() as u64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    fn create_test_batch() -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("value", DataType::Int32, false),
+        ]));
+        let values = Int32Array::from(vec![1, 2, 3]);
+        RecordBatch::try_new(schema, vec![Arc::new(values)]).unwrap()
+    }
+
+    #[test]
+    fn test_event_ingest_timestamp_is_set() {
+        let before = now_ns();
+        let event = ZenithEvent::new(1, 100, create_test_batch());
+        let after = now_ns();
+
+        assert!(event.header.ingest_ns > 0, "ingest_ns should be populated");
+        assert!(event.header.ingest_ns >= before && event.header.ingest_ns <= after,
+            "ingest_ns should be taken at construction time");
+    }
+
+    #[test]
+    fn test_event_ingest_timestamp_monotonic() {
+        let first = ZenithEvent::new(1, 1, create_test_batch());
+        let second = ZenithEvent::new(1, 2, create_test_batch());
+
+        assert!(second.header.ingest_ns >= first.header.ingest_ns,
+            "Later events should not have an earlier ingest timestamp");
+    }
+
+    #[test]
+    fn test_event_latency_ns() {
+        let event = ZenithEvent::new(1, 1, create_test_batch());
+        std::thread::sleep(std::time::Duration::from_millis(2));
+
+        assert!(event.latency_ns() >= 2_000_000, "latency should cover the sleep");
+    }
+
+    #[test]
+    fn test_batch_round_trip() {
+        let batch = create_test_batch();
+        let schema = batch.schema();
+        let event = ZenithEvent::new(1, 1, batch);
+
+        let borrowed = event.batch().expect("event should carry a payload");
+        assert_eq!(borrowed.schema(), schema);
+        assert_eq!(borrowed.num_rows(), 3);
+
+        let owned = event.into_batch().expect("event should carry a payload");
+        assert_eq!(owned.schema(), schema);
+        assert_eq!(owned.num_rows(), 3);
+    }
+
+    #[test]
+    fn test_into_batch_does_not_copy_buffers() {
+        let batch = create_test_batch();
+        let original_ptr = batch.column(0).to_data().buffers()[0].as_ptr();
+        let event = ZenithEvent::new(1, 1, batch);
+
+        let borrowed_ptr = event.batch().unwrap().column(0).to_data().buffers()[0].as_ptr();
+        assert_eq!(borrowed_ptr, original_ptr, "batch() should borrow the original buffers");
+
+        let owned = event.into_batch().unwrap();
+        let owned_ptr = owned.column(0).to_data().buffers()[0].as_ptr();
+        assert_eq!(owned_ptr, original_ptr, "into_batch() should not copy the buffers");
+    }
+
+    #[test]
+    fn test_header_only_event_has_no_batch() {
+        let event = ZenithEvent {
+            header: EventHeader::new(1, 1),
+            payload: None,
+        };
+        assert!(event.batch().is_none());
+        assert!(event.into_batch().is_none());
+    }
+
+    #[test]
+    fn test_latency_saturates_for_future_ingest() {
+        let mut header = EventHeader::new(1, 1);
+        header.ingest_ns = u64::MAX;
+        assert_eq!(header.latency_ns(), 0);
+    }
 }
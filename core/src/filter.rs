@@ -0,0 +1,409 @@
+//! Native Event Filter
+//!
+//! A small expression language over event header fields, evaluated by the
+//! consumer before any plugin runs, so simple drops don't need a WASM module:
+//!
+//! ```text
+//! source_id != 0 && seq_no % 100 != 0
+//! !(source_id == 3 || source_id == 4)
+//! ```
+//!
+//! Operands are `source_id`, `seq_no`, integer literals, or `field % N`.
+//! Comparisons are `== != < <= > >=`, combined with `&&`, `||`, `!` and
+//! parentheses. `&&` binds tighter than `||`.
+
+use crate::event::EventHeader;
+use std::fmt;
+
+/// Maximum nesting of parentheses and `!` in one expression
+pub const MAX_FILTER_DEPTH: usize = 64;
+
+/// Header field a filter can read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterField {
+    SourceId,
+    SeqNo,
+}
+
+/// One side of a comparison
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Field(FilterField),
+    Literal(u64),
+    /// `field % n`, with `n` checked non-zero at parse time
+    Modulo(FilterField, u64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Parsed filter expression. An event is kept when it evaluates to true.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterExpr {
+    Compare(Operand, CmpOp, Operand),
+    And(Box<FilterExpr>, Box<FilterExpr>),
+    Or(Box<FilterExpr>, Box<FilterExpr>),
+    Not(Box<FilterExpr>),
+}
+
+/// Why an expression failed to parse; `column` is 1-based
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterError {
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid filter at column {}: {}", self.column, self.message)
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+impl FilterExpr {
+    /// Parse a filter expression
+    pub fn parse(input: &str) -> Result<Self, FilterError> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, pos: 0, end: input.len(), depth: 0 };
+
+        if parser.tokens.is_empty() {
+            return Err(parser.error_at(parser.end, "empty expression"));
+        }
+
+        let expr = parser.parse_or()?;
+        match parser.tokens.get(parser.pos) {
+            None => Ok(expr),
+            Some((token, offset)) => Err(parser.error_at(*offset, format!("unexpected {}", token))),
+        }
+    }
+
+    /// Whether an event with this header passes the filter
+    pub fn matches(&self, header: &EventHeader) -> bool {
+        match self {
+            Self::Compare(left, op, right) => {
+                let (l, r) = (left.eval(header), right.eval(header));
+                match op {
+                    CmpOp::Eq => l == r,
+                    CmpOp::Ne => l != r,
+                    CmpOp::Lt => l < r,
+                    CmpOp::Le => l <= r,
+                    CmpOp::Gt => l > r,
+                    CmpOp::Ge => l >= r,
+                }
+            }
+            Self::And(a, b) => a.matches(header) && b.matches(header),
+            Self::Or(a, b) => a.matches(header) || b.matches(header),
+            Self::Not(e) => !e.matches(header),
+        }
+    }
+}
+
+impl FilterField {
+    fn eval(self, header: &EventHeader) -> u64 {
+        match self {
+            Self::SourceId => header.source_id as u64,
+            Self::SeqNo => header.seq_no,
+        }
+    }
+}
+
+impl Operand {
+    fn eval(self, header: &EventHeader) -> u64 {
+        match self {
+            Self::Field(field) => field.eval(header),
+            Self::Literal(value) => value,
+            Self::Modulo(field, n) => field.eval(header) % n,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Int(u64),
+    Sym(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ident(name) => write!(f, "'{}'", name),
+            Self::Int(value) => write!(f, "'{}'", value),
+            Self::Sym(sym) => write!(f, "'{}'", sym),
+        }
+    }
+}
+
+const TWO_CHAR_SYMBOLS: &[&str] = &["==", "!=", "<=", ">=", "&&", "||"];
+const ONE_CHAR_SYMBOLS: &[&str] = &["<", ">", "!", "%", "(", ")"];
+
+/// Split input into tokens paired with their byte offsets
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, FilterError> {
+    let mut tokens = Vec::new();
+    let bytes = input.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        if c.is_ascii_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            let value = input[start..i].parse::<u64>().map_err(|_| FilterError {
+                column: start + 1,
+                message: format!("integer '{}' is out of range", &input[start..i]),
+            })?;
+            tokens.push((Token::Int(value), start));
+        } else if c.is_ascii_alphabetic() || c == b'_' {
+            let start = i;
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+            tokens.push((Token::Ident(input[start..i].to_string()), start));
+        } else if let Some(sym) = TWO_CHAR_SYMBOLS.iter().find(|s| bytes[i..].starts_with(s.as_bytes())) {
+            tokens.push((Token::Sym(sym), i));
+            i += 2;
+        } else if let Some(sym) = ONE_CHAR_SYMBOLS.iter().find(|s| bytes[i..].starts_with(s.as_bytes())) {
+            tokens.push((Token::Sym(sym), i));
+            i += 1;
+        } else {
+            let message = match c {
+                b'=' => "expected '==' (assignment is not supported)".to_string(),
+                b'&' => "expected '&&'".to_string(),
+                b'|' => "expected '||'".to_string(),
+                _ => format!("unexpected character '{}'", input[i..].chars().next().unwrap_or('?')),
+            };
+            return Err(FilterError { column: i + 1, message });
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    /// Input length, for errors at end of input
+    end: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn error_at(&self, offset: usize, message: impl Into<String>) -> FilterError {
+        FilterError { column: offset + 1, message: message.into() }
+    }
+
+    /// Offset of the next token, or end of input
+    fn offset(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.end, |(_, offset)| *offset)
+    }
+
+    fn eat(&mut self, sym: &str) -> bool {
+        if matches!(self.tokens.get(self.pos), Some((Token::Sym(s), _)) if *s == sym) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn enter(&mut self) -> Result<(), FilterError> {
+        self.depth += 1;
+        if self.depth > MAX_FILTER_DEPTH {
+            return Err(self.error_at(self.offset(), format!("nesting deeper than {}", MAX_FILTER_DEPTH)));
+        }
+        Ok(())
+    }
+
+    fn parse_or(&mut self) -> Result<FilterExpr, FilterError> {
+        let mut left = self.parse_and()?;
+        while self.eat("||") {
+            let right = self.parse_and()?;
+            left = FilterExpr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<FilterExpr, FilterError> {
+        let mut left = self.parse_unary()?;
+        while self.eat("&&") {
+            let right = self.parse_unary()?;
+            left = FilterExpr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<FilterExpr, FilterError> {
+        if self.eat("!") {
+            self.enter()?;
+            let inner = self.parse_unary()?;
+            self.depth -= 1;
+            return Ok(FilterExpr::Not(Box::new(inner)));
+        }
+
+        if self.eat("(") {
+            self.enter()?;
+            let inner = self.parse_or()?;
+            if !self.eat(")") {
+                return Err(self.error_at(self.offset(), "expected ')'"));
+            }
+            self.depth -= 1;
+            return Ok(inner);
+        }
+
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> Result<FilterExpr, FilterError> {
+        let left = self.parse_operand()?;
+
+        let op = match self.tokens.get(self.pos) {
+            Some((Token::Sym(sym), _)) => match *sym {
+                "==" => CmpOp::Eq,
+                "!=" => CmpOp::Ne,
+                "<" => CmpOp::Lt,
+                "<=" => CmpOp::Le,
+                ">" => CmpOp::Gt,
+                ">=" => CmpOp::Ge,
+                _ => return Err(self.error_at(self.offset(), "expected a comparison operator")),
+            },
+            _ => return Err(self.error_at(self.offset(), "expected a comparison operator")),
+        };
+        self.pos += 1;
+
+        let right = self.parse_operand()?;
+        Ok(FilterExpr::Compare(left, op, right))
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, FilterError> {
+        let offset = self.offset();
+        let operand = match self.tokens.get(self.pos).cloned() {
+            Some((Token::Ident(name), _)) => Operand::Field(match name.as_str() {
+                "source_id" => FilterField::SourceId,
+                "seq_no" => FilterField::SeqNo,
+                _ => {
+                    return Err(self.error_at(offset, format!(
+                        "unknown field '{}', expected source_id or seq_no", name
+                    )))
+                }
+            }),
+            Some((Token::Int(value), _)) => Operand::Literal(value),
+            Some((token, _)) => return Err(self.error_at(offset, format!("expected a field or integer, found {}", token))),
+            None => return Err(self.error_at(offset, "expected a field or integer")),
+        };
+        self.pos += 1;
+
+        if !self.eat("%") {
+            return Ok(operand);
+        }
+
+        let Operand::Field(field) = operand else {
+            return Err(self.error_at(offset, "'%' needs a field on the left"));
+        };
+        match self.tokens.get(self.pos) {
+            Some((Token::Int(0), divisor_offset)) => Err(self.error_at(*divisor_offset, "modulo by zero")),
+            Some((Token::Int(n), _)) => {
+                let n = *n;
+                self.pos += 1;
+                Ok(Operand::Modulo(field, n))
+            }
+            _ => Err(self.error_at(self.offset(), "'%' needs an integer on the right")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(source_id: u32, seq_no: u64) -> EventHeader {
+        EventHeader::new(source_id, seq_no)
+    }
+
+    fn eval(expr: &str, source_id: u32, seq_no: u64) -> bool {
+        FilterExpr::parse(expr).unwrap().matches(&header(source_id, seq_no))
+    }
+
+    #[test]
+    fn test_parse_structure() {
+        assert_eq!(
+            FilterExpr::parse("source_id != 0 && seq_no % 100 != 0").unwrap(),
+            FilterExpr::And(
+                Box::new(FilterExpr::Compare(
+                    Operand::Field(FilterField::SourceId),
+                    CmpOp::Ne,
+                    Operand::Literal(0),
+                )),
+                Box::new(FilterExpr::Compare(
+                    Operand::Modulo(FilterField::SeqNo, 100),
+                    CmpOp::Ne,
+                    Operand::Literal(0),
+                )),
+            )
+        );
+    }
+
+    #[test]
+    fn test_evaluate_against_events() {
+        let expr = "source_id != 0 && seq_no % 100 != 0";
+        assert!(eval(expr, 1, 5));
+        assert!(!eval(expr, 0, 5));
+        assert!(!eval(expr, 1, 200));
+
+        assert!(eval("seq_no >= 10 && seq_no < 20", 0, 10));
+        assert!(!eval("seq_no >= 10 && seq_no < 20", 0, 20));
+        assert!(eval("seq_no <= 3 || source_id > 7", 8, 100));
+        assert!(!eval("!(source_id == 3 || source_id == 4)", 4, 0));
+        assert!(eval("!!(source_id == 3)", 3, 0));
+        assert!(eval("seq_no == source_id", 9, 9));
+    }
+
+    #[test]
+    fn test_and_binds_tighter_than_or() {
+        // a || (b && c), not (a || b) && c
+        let expr = "source_id == 1 || source_id == 2 && seq_no == 0";
+        assert!(eval(expr, 1, 5));
+        assert!(!eval(expr, 2, 5));
+        assert!(eval("(source_id == 1 || source_id == 2) && seq_no == 0", 2, 0));
+    }
+
+    #[test]
+    fn test_malformed_expressions() {
+        let err = |expr: &str| FilterExpr::parse(expr).unwrap_err();
+
+        assert_eq!(err("").message, "empty expression");
+        assert_eq!(err("   ").column, 4);
+        assert_eq!(err("source_id = 1").message, "expected '==' (assignment is not supported)");
+        assert_eq!(err("source_id == 1 & seq_no == 2").message, "expected '&&'");
+        assert!(err("priority == 1").message.contains("unknown field 'priority'"));
+        assert_eq!(err("seq_no % 0 == 1"), FilterError { column: 10, message: "modulo by zero".to_string() });
+        assert_eq!(err("10 % 3 == 1").message, "'%' needs a field on the left");
+        assert_eq!(err("(source_id == 1").message, "expected ')'");
+        assert_eq!(err("source_id == 1)").message, "unexpected ')'");
+        assert_eq!(err("source_id").message, "expected a comparison operator");
+        assert!(err("seq_no == 99999999999999999999").message.contains("out of range"));
+        assert!(err("seq_no == 1 # comment").message.contains("unexpected character '#'"));
+
+        let deep = format!("{}seq_no == 1{}", "(".repeat(MAX_FILTER_DEPTH + 1), ")".repeat(MAX_FILTER_DEPTH + 1));
+        assert!(err(&deep).message.contains("nesting"));
+        let ok = format!("{}seq_no == 1{}", "(".repeat(MAX_FILTER_DEPTH), ")".repeat(MAX_FILTER_DEPTH));
+        assert!(FilterExpr::parse(&ok).is_ok());
+    }
+
+    #[test]
+    fn test_error_display() {
+        let err = FilterExpr::parse("seq_no ==").unwrap_err();
+        assert_eq!(err.to_string(), "invalid filter at column 10: expected a field or integer");
+    }
+}
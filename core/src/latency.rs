@@ -0,0 +1,105 @@
+//! Lock-free histogram of event queue-wait times.
+//!
+//! Buckets are powers of two: bucket 0 holds 0 ns and bucket `i` holds
+//! `[2^(i-1), 2^i)`. Percentiles are reported as the upper bound of the
+//! bucket they fall in, so they overstate the true value by at most 2x.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const BUCKETS: usize = 65;
+
+pub struct LatencyHistogram {
+    buckets: [AtomicU64; BUCKETS],
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self { buckets: std::array::from_fn(|_| AtomicU64::new(0)) }
+    }
+}
+
+impl LatencyHistogram {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, latency_ns: u64) {
+        let bucket = (u64::BITS - latency_ns.leading_zeros()) as usize;
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Samples recorded so far
+    pub fn count(&self) -> u64 {
+        self.buckets.iter().map(|bucket| bucket.load(Ordering::Relaxed)).sum()
+    }
+
+    /// Upper bound of the bucket holding the `quantile` (0.0..=1.0) sample,
+    /// in nanoseconds; 0 with no samples. Concurrent `record`s may or may
+    /// not be counted.
+    pub fn percentile(&self, quantile: f64) -> u64 {
+        let counts: Vec<u64> = self.buckets.iter().map(|bucket| bucket.load(Ordering::Relaxed)).collect();
+        let total: u64 = counts.iter().sum();
+        if total == 0 {
+            return 0;
+        }
+        let rank = ((quantile.clamp(0.0, 1.0) * total as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (bucket, count) in counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return bucket_upper_bound(bucket);
+            }
+        }
+        u64::MAX
+    }
+}
+
+/// Largest latency that lands in `bucket`
+fn bucket_upper_bound(bucket: usize) -> u64 {
+    match bucket {
+        0 => 0,
+        64.. => u64::MAX,
+        _ => (1u64 << bucket) - 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles() {
+        let histogram = LatencyHistogram::new();
+        assert_eq!(histogram.percentile(0.5), 0);
+
+        // 90 fast, 8 slower, 2 very slow samples
+        for _ in 0..90 {
+            histogram.record(3_000);
+        }
+        for _ in 0..8 {
+            histogram.record(50_000);
+        }
+        histogram.record(1_000_000);
+        histogram.record(1_000_000);
+
+        assert_eq!(histogram.count(), 100);
+        assert_eq!(histogram.percentile(0.5), 4_095);
+        assert_eq!(histogram.percentile(0.95), 65_535);
+        assert_eq!(histogram.percentile(0.99), 1_048_575);
+        assert_eq!(histogram.percentile(1.0), 1_048_575);
+    }
+
+    #[test]
+    fn test_bucket_edges() {
+        let histogram = LatencyHistogram::new();
+        histogram.record(0);
+        assert_eq!(histogram.percentile(1.0), 0);
+        histogram.record(1);
+        assert_eq!(histogram.percentile(1.0), 1);
+        histogram.record(1 << 20);
+        assert_eq!(histogram.percentile(1.0), (1 << 21) - 1);
+        histogram.record(u64::MAX);
+        assert_eq!(histogram.percentile(1.0), u64::MAX);
+        assert_eq!(histogram.percentile(0.0), 0);
+    }
+}
@@ -5,13 +5,30 @@ pub mod wasm_host;
 pub mod error;
 pub mod admin_api;
 pub mod validation;
+pub mod filter;
+pub mod circuit_breaker;
+pub mod capabilities;
+pub mod latency;
+pub mod load_gen;
+pub mod logging;
+#[cfg(feature = "parquet")]
+pub mod parquet_sink;
 
-use std::ffi::c_void;
+use std::ffi::{c_char, c_void, CStr};
 use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::time::Duration;
+use arrow::datatypes::Schema;
 use arrow::ffi::{FFI_ArrowArray, FFI_ArrowSchema};
+use arrow::ipc::reader::StreamReader;
 use arrow::record_batch::RecordBatch;
-use crate::engine::ZenithEngine;
-use crate::event::ZenithEvent;
+use crate::engine::{DictionaryPolicy, EngineStats, ZenithEngine};
+use crate::error::ZenithError;
+use crate::event::{ZenithEvent, ZenithEventHeader};
+use crate::load_gen::{LoadConfig, LoadGenerator};
+use crate::logging::LogFormat;
+use crate::validation::Validator;
+use crate::wasm_host::{PluginLoadReport, SandboxLimits, WasmHost};
+use tracing::{error, warn};
 
 pub use engine::ZenithEngine as Engine;
 pub use event::ZenithEvent as Event;
@@ -30,6 +47,27 @@ pub mod ffi_error {
     pub const FFI_ERROR: i32 = -4;
     /// Initialization failed
     pub const INIT_FAILED: i32 = -5;
+    /// Plugin could not be parsed/compiled (e.g. not a WASM module)
+    pub const PLUGIN_LOAD_FAILED: i32 = -7;
+    /// Plugin is well-formed but was rejected (e.g. disallowed imports,
+    /// no `on_event` export)
+    pub const PLUGIN_VALIDATION_FAILED: i32 = -8;
+    /// Filter expression is malformed or not valid UTF-8
+    pub const INVALID_FILTER: i32 = -9;
+    /// Sandbox limits are zero or above their ceilings
+    pub const INVALID_LIMITS: i32 = -10;
+    /// Precompiled artifact is corrupt or built for another wasmtime/CPU
+    pub const INCOMPATIBLE_ARTIFACT: i32 = -11;
+    /// Log level or format number out of range
+    pub const INVALID_LOG_LEVEL: i32 = -12;
+    /// Batch schema doesn't match the engine's expected schema
+    pub const SCHEMA_MISMATCH: i32 = -13;
+    /// Batch has dictionary-encoded columns and the engine rejects them
+    pub const DICTIONARY_REJECTED: i32 = -14;
+    /// Path is too long, not UTF-8 or contains traversal
+    pub const INVALID_PATH: i32 = -15;
+    /// File could not be read (missing, permissions, ...)
+    pub const FILE_READ_FAILED: i32 = -16;
 }
 
 /// Initialize the Zenith Engine
@@ -57,7 +95,7 @@ pub extern "C" fn zenith_init(buffer_size: u32) -> *mut c_void {
         Ok(ptr) => ptr,
         Err(_) => {
             // Panic occurred - log and return null
-            eprintln!("[zenith] PANIC in zenith_init - caught safely");
+            error!("PANIC in zenith_init - caught safely");
             std::ptr::null_mut()
         }
     }
@@ -81,12 +119,14 @@ pub unsafe extern "C" fn zenith_free(engine_ptr: *mut c_void) {
     }));
     
     if result.is_err() {
-        eprintln!("[zenith] PANIC in zenith_free - caught safely");
+        error!("PANIC in zenith_free - caught safely");
     }
 }
 
 /// Publish an Arrow RecordBatch via C Data Interface
 /// Takes ownership of the FFI structs (they are moved into Rust)
+/// Dictionary-encoded columns are decoded to their value types unless
+/// `zenith_set_dictionary_decoding` turned that off
 /// 
 /// # Returns
 /// - 0: Success
@@ -94,6 +134,8 @@ pub unsafe extern "C" fn zenith_free(engine_ptr: *mut c_void) {
 /// - -2: Buffer full
 /// - -3: Panic occurred
 /// - -4: FFI/Arrow error
+/// - -13: Schema doesn't match the engine's expected schema
+/// - -14: Dictionary-encoded columns and the engine rejects them
 /// 
 /// # Safety
 /// - All pointers must be valid
@@ -114,14 +156,12 @@ pub unsafe extern "C" fn zenith_publish(
     let result = catch_unwind(AssertUnwindSafe(|| {
         let engine = &*(engine_ptr as *mut ZenithEngine);
         
-        // SAFETY: Caller has prepared valid FFI structs
-        let array = std::ptr::read(array_ptr);
-        let schema = std::ptr::read(schema_ptr);
-
-        match arrow::ffi::from_ffi(array, &schema) {
-            Ok(array_data) => {
-                let struct_array = arrow::array::StructArray::from(array_data);
-                let batch = RecordBatch::from(&struct_array);
+        match import_batch(array_ptr, schema_ptr) {
+            Ok(batch) => {
+                let batch = match admit_batch(engine, batch) {
+                    Ok(batch) => batch,
+                    Err(code) => return code,
+                };
                 let event = ZenithEvent::new(source_id, seq_no, batch);
                  
                 match engine.get_ring_buffer().push(event) {
@@ -136,52 +176,805 @@ pub unsafe extern "C" fn zenith_publish(
     match result {
         Ok(code) => code,
         Err(_) => {
-            eprintln!("[zenith] PANIC in zenith_publish - caught safely");
+            error!("PANIC in zenith_publish - caught safely");
             ffi_error::PANIC
         }
     }
 }
 
+/// Move a batch out of C Data Interface structs
+///
+/// # Safety
+/// Both pointers must be valid; ownership of the structs is taken
+unsafe fn import_batch(
+    array_ptr: *mut FFI_ArrowArray,
+    schema_ptr: *mut FFI_ArrowSchema
+) -> Result<RecordBatch, arrow::error::ArrowError> {
+    // SAFETY: Caller has prepared valid FFI structs
+    let array = std::ptr::read(array_ptr);
+    let schema = std::ptr::read(schema_ptr);
+
+    let array_data = arrow::ffi::from_ffi(array, &schema)?;
+    let struct_array = arrow::array::StructArray::from(array_data);
+    Ok(RecordBatch::from(&struct_array))
+}
+
+/// Decode dictionary columns per the engine's policy, then check the
+/// expected schema, so a decoded batch is checked by its value types
+fn admit_batch(engine: &ZenithEngine, batch: RecordBatch) -> Result<RecordBatch, i32> {
+    let batch = match engine.decode_dictionaries(batch) {
+        Ok(batch) => batch,
+        Err(ZenithError::DictionaryEncoded(_)) => return Err(ffi_error::DICTIONARY_REJECTED),
+        Err(_) => return Err(ffi_error::FFI_ERROR),
+    };
+    engine.check_schema(&batch.schema()).map_err(|_| ffi_error::SCHEMA_MISMATCH)?;
+    Ok(batch)
+}
+
+/// Make `zenith_publish` and friends reject batches whose schema doesn't
+/// match `schema_ptr` (see `ZenithEngine::check_schema`). The schema is
+/// copied; the caller keeps ownership. A null `schema_ptr` removes the
+/// requirement.
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Null engine pointer
+/// - -3: Panic occurred
+/// - -4: Schema could not be imported
+///
+/// # Safety
+/// - engine_ptr must be valid pointer from zenith_init
+/// - schema_ptr must be null or point to a valid ArrowSchema
+#[no_mangle]
+pub unsafe extern "C" fn zenith_set_expected_schema(
+    engine_ptr: *mut c_void,
+    schema_ptr: *const FFI_ArrowSchema
+) -> i32 {
+    if engine_ptr.is_null() {
+        return ffi_error::NULL_POINTER;
+    }
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let engine = &*(engine_ptr as *mut ZenithEngine);
+        if schema_ptr.is_null() {
+            engine.set_expected_schema(None);
+            return ffi_error::SUCCESS;
+        }
+        match Schema::try_from(&*schema_ptr) {
+            Ok(schema) => {
+                engine.set_expected_schema(Some(std::sync::Arc::new(schema)));
+                ffi_error::SUCCESS
+            }
+            Err(_) => ffi_error::FFI_ERROR,
+        }
+    }));
+
+    match result {
+        Ok(code) => code,
+        Err(_) => {
+            error!("PANIC in zenith_set_expected_schema - caught safely");
+            ffi_error::PANIC
+        }
+    }
+}
+
+/// Choose what `zenith_publish` and friends do with dictionary-encoded
+/// columns: non-zero `decode` casts them to their value types (the
+/// default), zero rejects such batches with -14.
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Null engine pointer
+/// - -3: Panic occurred
+///
+/// # Safety
+/// - engine_ptr must be valid pointer from zenith_init
+#[no_mangle]
+pub unsafe extern "C" fn zenith_set_dictionary_decoding(engine_ptr: *mut c_void, decode: u32) -> i32 {
+    if engine_ptr.is_null() {
+        return ffi_error::NULL_POINTER;
+    }
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let engine = &*(engine_ptr as *mut ZenithEngine);
+        engine.set_dictionary_policy(if decode != 0 {
+            DictionaryPolicy::Decode
+        } else {
+            DictionaryPolicy::Reject
+        });
+    }));
+
+    match result {
+        Ok(()) => ffi_error::SUCCESS,
+        Err(_) => {
+            error!("PANIC in zenith_set_dictionary_decoding - caught safely");
+            ffi_error::PANIC
+        }
+    }
+}
+
+/// Like `zenith_publish`, but when the buffer is full waits up to
+/// `timeout_ms` for the consumer to free a slot instead of failing
+/// straight away. A timeout of 0 behaves exactly like `zenith_publish`.
+/// 
+/// Only the default reject policy waits; the drop policies never report
+/// a full buffer.
+/// 
+/// # Returns
+/// - 0: Success
+/// - -1: Null pointer
+/// - -2: Buffer still full after `timeout_ms`
+/// - -3: Panic occurred
+/// - -4: FFI/Arrow error
+/// - -13: Schema doesn't match the engine's expected schema
+/// - -14: Dictionary-encoded columns and the engine rejects them
+/// 
+/// # Safety
+/// - All pointers must be valid
+/// - array_ptr and schema_ptr ownership is transferred to Rust, even when
+///   the call times out
+#[no_mangle]
+pub unsafe extern "C" fn zenith_publish_blocking(
+    engine_ptr: *mut c_void,
+    array_ptr: *mut FFI_ArrowArray,
+    schema_ptr: *mut FFI_ArrowSchema,
+    source_id: u32,
+    seq_no: u64,
+    timeout_ms: u64
+) -> i32 {
+    if engine_ptr.is_null() || array_ptr.is_null() || schema_ptr.is_null() {
+        return ffi_error::NULL_POINTER;
+    }
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let engine = &*(engine_ptr as *mut ZenithEngine);
+
+        let batch = match import_batch(array_ptr, schema_ptr) {
+            Ok(batch) => batch,
+            Err(_) => return ffi_error::FFI_ERROR,
+        };
+        let batch = match admit_batch(engine, batch) {
+            Ok(batch) => batch,
+            Err(code) => return code,
+        };
+        let event = ZenithEvent::new(source_id, seq_no, batch);
+        match engine.get_ring_buffer().push_timeout(event, Duration::from_millis(timeout_ms)) {
+            Ok(_) => ffi_error::SUCCESS,
+            Err(_) => ffi_error::BUFFER_FULL,
+        }
+    }));
+
+    match result {
+        Ok(code) => code,
+        Err(_) => {
+            error!("PANIC in zenith_publish_blocking - caught safely");
+            ffi_error::PANIC
+        }
+    }
+}
+
+/// Publish a RecordBatch serialized in the Arrow IPC streaming format.
+/// The bytes are only read; the caller keeps ownership of the buffer.
+/// A stream holding several batches is published as one concatenated
+/// event.
+/// 
+/// # Returns
+/// - 0: Success
+/// - -1: Null pointer
+/// - -2: Buffer full
+/// - -3: Panic occurred
+/// - -4: Malformed or empty IPC stream
+/// - -13: Schema doesn't match the engine's expected schema
+/// - -14: Dictionary-encoded columns and the engine rejects them
+/// 
+/// # Safety
+/// - engine_ptr must be valid pointer from zenith_init
+/// - ipc_bytes must point to valid memory of len bytes
+#[no_mangle]
+pub unsafe extern "C" fn zenith_publish_ipc(
+    engine_ptr: *mut c_void,
+    ipc_bytes: *const u8,
+    len: usize,
+    source_id: u32,
+    seq_no: u64
+) -> i32 {
+    if engine_ptr.is_null() || ipc_bytes.is_null() {
+        return ffi_error::NULL_POINTER;
+    }
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let engine = &*(engine_ptr as *mut ZenithEngine);
+        let bytes = std::slice::from_raw_parts(ipc_bytes, len);
+
+        let batch = match read_ipc_stream(bytes) {
+            Ok(batch) => batch,
+            Err(_) => return ffi_error::FFI_ERROR,
+        };
+        let batch = match admit_batch(engine, batch) {
+            Ok(batch) => batch,
+            Err(code) => return code,
+        };
+        match engine.get_ring_buffer().push(ZenithEvent::new(source_id, seq_no, batch)) {
+            Ok(_) => ffi_error::SUCCESS,
+            Err(_) => ffi_error::BUFFER_FULL,
+        }
+    }));
+
+    match result {
+        Ok(code) => code,
+        Err(_) => {
+            error!("PANIC in zenith_publish_ipc - caught safely");
+            ffi_error::PANIC
+        }
+    }
+}
+
+/// FFI code for a failed plugin load: malformed modules are load
+/// failures, modules the host refuses are validation failures
+fn plugin_load_error(err: &ZenithError) -> i32 {
+    warn!("{}", err);
+    match err {
+        ZenithError::PluginRejected(PluginLoadReport::Parse { .. }) => ffi_error::PLUGIN_LOAD_FAILED,
+        ZenithError::PluginRejected(_) | ZenithError::Validation(_) => ffi_error::PLUGIN_VALIDATION_FAILED,
+        _ => ffi_error::PLUGIN_LOAD_FAILED,
+    }
+}
+
+fn read_ipc_stream(bytes: &[u8]) -> Result<RecordBatch, arrow::error::ArrowError> {
+    let reader = StreamReader::try_new(bytes, None)?;
+    let schema = reader.schema();
+    let batches = reader.collect::<Result<Vec<_>, _>>()?;
+    match batches.len() {
+        0 => Err(arrow::error::ArrowError::IpcError("IPC stream contains no record batches".to_string())),
+        1 => Ok(batches.into_iter().next().unwrap()),
+        _ => arrow::compute::concat_batches(&schema, &batches),
+    }
+}
+
 /// Load a WASM plugin
 /// 
 /// # Returns
 /// - 0: Success
 /// - -1: Null pointer
-/// - -2: Load failed
 /// - -3: Panic occurred
-/// 
+/// - -7: Plugin could not be compiled
+/// - -8: Plugin was rejected at instantiation/validation
+/// 
+/// # Safety
+/// - engine_ptr must be valid pointer from zenith_init
+/// - wasm_bytes must point to valid memory of len bytes
+#[no_mangle]
+pub unsafe extern "C" fn zenith_load_plugin(
+    engine_ptr: *mut c_void,
+    wasm_bytes: *const u8,
+    len: usize
+) -> i32 {
+    if engine_ptr.is_null() || wasm_bytes.is_null() {
+        return ffi_error::NULL_POINTER;
+    }
+    
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let engine = &*(engine_ptr as *mut ZenithEngine);
+        let slice = std::slice::from_raw_parts(wasm_bytes, len);
+        
+        match engine.load_plugin(slice) {
+            Ok(_) => ffi_error::SUCCESS,
+            Err(e) => plugin_load_error(&e),
+        }
+    }));
+    
+    match result {
+        Ok(code) => code,
+        Err(_) => {
+            error!("PANIC in zenith_load_plugin - caught safely");
+            ffi_error::PANIC
+        }
+    }
+}
+
+/// Read a WASM plugin from `path` and load it like `zenith_load_plugin`.
+/// Paths containing `..` are refused.
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Null pointer
+/// - -3: Panic occurred
+/// - -7: Plugin could not be compiled
+/// - -8: Plugin was rejected at instantiation/validation
+/// - -15: Path is not UTF-8, too long or contains traversal
+/// - -16: File could not be read
+///
+/// # Safety
+/// - engine_ptr must be valid pointer from zenith_init
+/// - path must be a valid NUL-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn zenith_load_plugin_file(
+    engine_ptr: *mut c_void,
+    path: *const c_char
+) -> i32 {
+    if engine_ptr.is_null() || path.is_null() {
+        return ffi_error::NULL_POINTER;
+    }
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let engine = &*(engine_ptr as *mut ZenithEngine);
+        let Ok(path) = CStr::from_ptr(path).to_str() else {
+            return ffi_error::INVALID_PATH;
+        };
+        if let Err(e) = Validator::new().validate_path(path) {
+            warn!("Refusing plugin path: {}", e);
+            return ffi_error::INVALID_PATH;
+        }
+        let wasm = match std::fs::read(path) {
+            Ok(wasm) => wasm,
+            Err(e) => {
+                warn!("Failed to read plugin {}: {}", path, e);
+                return ffi_error::FILE_READ_FAILED;
+            }
+        };
+
+        match engine.load_plugin(&wasm) {
+            Ok(_) => ffi_error::SUCCESS,
+            Err(e) => plugin_load_error(&e),
+        }
+    }));
+
+    match result {
+        Ok(code) => code,
+        Err(_) => {
+            error!("PANIC in zenith_load_plugin_file - caught safely");
+            ffi_error::PANIC
+        }
+    }
+}
+
+/// Load a WASM plugin with per-plugin sandbox limits. `zenith_load_plugin`
+/// loads without them. A plugin that runs past `cpu_timeout_ms` or makes
+/// more than `max_host_calls` host calls in one invocation is disabled, and
+/// growing memory beyond `max_memory_bytes` fails inside the plugin.
+/// 
+/// # Returns
+/// - 0: Success
+/// - -1: Null pointer
+/// - -3: Panic occurred
+/// - -7: Plugin could not be compiled
+/// - -8: Plugin was rejected at instantiation/validation
+/// - -10: A limit is zero or above its ceiling
+/// 
+/// # Safety
+/// - engine_ptr must be valid pointer from zenith_init
+/// - wasm_bytes must point to valid memory of len bytes
+#[no_mangle]
+pub unsafe extern "C" fn zenith_load_plugin_limited(
+    engine_ptr: *mut c_void,
+    wasm_bytes: *const u8,
+    len: usize,
+    max_memory_bytes: u64,
+    cpu_timeout_ms: u64,
+    max_host_calls: u32
+) -> i32 {
+    if engine_ptr.is_null() || wasm_bytes.is_null() {
+        return ffi_error::NULL_POINTER;
+    }
+    
+    let limits = SandboxLimits {
+        max_memory_bytes,
+        cpu_timeout: Duration::from_millis(cpu_timeout_ms),
+        max_host_calls,
+    };
+    if let Err(e) = limits.validate() {
+        warn!("{}", e);
+        return ffi_error::INVALID_LIMITS;
+    }
+    
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let engine = &*(engine_ptr as *mut ZenithEngine);
+        let slice = std::slice::from_raw_parts(wasm_bytes, len);
+        
+        match engine.load_plugin_limited(slice, limits) {
+            Ok(_) => ffi_error::SUCCESS,
+            Err(e) => plugin_load_error(&e),
+        }
+    }));
+    
+    match result {
+        Ok(code) => code,
+        Err(_) => {
+            error!("PANIC in zenith_load_plugin_limited - caught safely");
+            ffi_error::PANIC
+        }
+    }
+}
+
+/// Compile a WASM plugin ahead of time for `zenith_load_precompiled_plugin`.
+/// Returns a buffer of `*out_len` bytes to be released with
+/// `zenith_free_buffer`, or null if the module doesn't compile.
+/// 
+/// # Safety
+/// - wasm_bytes must point to valid memory of len bytes
+/// - out_len must be a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn zenith_precompile_plugin(
+    wasm_bytes: *const u8,
+    len: usize,
+    out_len: *mut usize
+) -> *mut u8 {
+    if wasm_bytes.is_null() || out_len.is_null() {
+        return std::ptr::null_mut();
+    }
+    
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let slice = std::slice::from_raw_parts(wasm_bytes, len);
+        match WasmHost::precompile(slice) {
+            Ok(artifact) => {
+                let artifact = artifact.into_boxed_slice();
+                *out_len = artifact.len();
+                Box::into_raw(artifact) as *mut u8
+            }
+            Err(e) => {
+                warn!("{}", e);
+                std::ptr::null_mut()
+            }
+        }
+    }));
+    
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => {
+            error!("PANIC in zenith_precompile_plugin - caught safely");
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Free a buffer returned by `zenith_precompile_plugin`
+/// 
+/// # Safety
+/// - ptr and len must come from the same `zenith_precompile_plugin` call
+/// - Must not be called twice on the same buffer
+#[no_mangle]
+pub unsafe extern "C" fn zenith_free_buffer(ptr: *mut u8, len: usize) {
+    if ptr.is_null() {
+        return;
+    }
+    drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, len)));
+}
+
+/// Load a plugin from an artifact produced by `zenith_precompile_plugin`,
+/// skipping compilation. Only load artifacts from a trusted build.
+/// 
+/// # Returns
+/// - 0: Success
+/// - -1: Null pointer
+/// - -3: Panic occurred
+/// - -8: Plugin was rejected at instantiation/validation
+/// - -11: Not an artifact, or built for another wasmtime version/CPU
+/// 
+/// # Safety
+/// - engine_ptr must be valid pointer from zenith_init
+/// - artifact must point to valid memory of len bytes
+#[no_mangle]
+pub unsafe extern "C" fn zenith_load_precompiled_plugin(
+    engine_ptr: *mut c_void,
+    artifact: *const u8,
+    len: usize
+) -> i32 {
+    if engine_ptr.is_null() || artifact.is_null() {
+        return ffi_error::NULL_POINTER;
+    }
+    
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let engine = &*(engine_ptr as *mut ZenithEngine);
+        let slice = std::slice::from_raw_parts(artifact, len);
+        
+        match engine.load_precompiled_plugin(slice) {
+            Ok(_) => ffi_error::SUCCESS,
+            Err(e @ ZenithError::IncompatibleArtifact(_)) => {
+                warn!("{}", e);
+                ffi_error::INCOMPATIBLE_ARTIFACT
+            }
+            Err(e) => plugin_load_error(&e),
+        }
+    }));
+    
+    match result {
+        Ok(code) => code,
+        Err(_) => {
+            error!("PANIC in zenith_load_precompiled_plugin - caught safely");
+            ffi_error::PANIC
+        }
+    }
+}
+
+/// C callback receiving forwarded events: `(user_data, source_id, seq_no, timestamp_ns)`
+pub type ZenithSinkCallback = unsafe extern "C" fn(*mut c_void, u32, u64, u64);
+
+/// `user_data` handed back to the sink callback
+struct SinkUserData(*mut c_void);
+
+// SAFETY: zenith_set_sink requires user_data to be usable from the consumer thread
+unsafe impl Send for SinkUserData {}
+unsafe impl Sync for SinkUserData {}
+
+impl SinkUserData {
+    /// Accessed through a method so closures capture the whole wrapper
+    fn get(&self) -> *mut c_void {
+        self.0
+    }
+}
+
+/// Register a callback invoked for every event that passes the filter and
+/// plugins. Passing a null callback removes the sink.
+///
+/// The callback runs on the engine's consumer threads. With more than one
+/// consumer thread it may be called concurrently, so it must be
+/// thread-safe. It must also return quickly: a slow sink backs up the ring
+/// buffer. It only receives the event header; the payload is not exposed.
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Null engine pointer
+/// - -3: Panic occurred
+///
+/// # Safety
+/// - engine_ptr must be valid pointer from zenith_init
+/// - callback and user_data must stay valid, and be safe to use from
+///   another thread, until the sink is replaced or the engine is freed
+#[no_mangle]
+pub unsafe extern "C" fn zenith_set_sink(
+    engine_ptr: *mut c_void,
+    callback: Option<ZenithSinkCallback>,
+    user_data: *mut c_void
+) -> i32 {
+    if engine_ptr.is_null() {
+        return ffi_error::NULL_POINTER;
+    }
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let engine = &*(engine_ptr as *mut ZenithEngine);
+        match callback {
+            Some(callback) => {
+                let user_data = SinkUserData(user_data);
+                engine.set_sink(Box::new(move |event| {
+                    let header = &event.header;
+                    // SAFETY: guaranteed by the zenith_set_sink contract
+                    unsafe { callback(user_data.get(), header.source_id, header.seq_no, header.timestamp_ns) };
+                }));
+            }
+            None => engine.clear_sink(),
+        }
+        ffi_error::SUCCESS
+    }));
+
+    match result {
+        Ok(code) => code,
+        Err(_) => {
+            error!("PANIC in zenith_set_sink - caught safely");
+            ffi_error::PANIC
+        }
+    }
+}
+
+/// Set the native event filter, e.g. `"source_id != 0 && seq_no % 100 != 0"`.
+/// Events the filter rejects are dropped before any plugin runs. An empty
+/// string clears the filter; on error the previous filter stays in place.
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Null pointer
+/// - -3: Panic occurred
+/// - -9: Malformed expression
+///
+/// # Safety
+/// - engine_ptr must be valid pointer from zenith_init
+/// - expr must be a valid NUL-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn zenith_set_filter(
+    engine_ptr: *mut c_void,
+    expr: *const c_char
+) -> i32 {
+    if engine_ptr.is_null() || expr.is_null() {
+        return ffi_error::NULL_POINTER;
+    }
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let engine = &*(engine_ptr as *mut ZenithEngine);
+        let Ok(expr) = CStr::from_ptr(expr).to_str() else {
+            return ffi_error::INVALID_FILTER;
+        };
+
+        match engine.set_filter(expr) {
+            Ok(()) => ffi_error::SUCCESS,
+            Err(e) => {
+                warn!("{}", e);
+                ffi_error::INVALID_FILTER
+            }
+        }
+    }));
+
+    match result {
+        Ok(code) => code,
+        Err(_) => {
+            error!("PANIC in zenith_set_filter - caught safely");
+            ffi_error::PANIC
+        }
+    }
+}
+
+/// Copy a snapshot of the engine's counters into `out`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Null pointer
+/// - -3: Panic occurred
+///
 /// # Safety
 /// - engine_ptr must be valid pointer from zenith_init
-/// - wasm_bytes must point to valid memory of len bytes
+/// - out must point to writable memory for one EngineStats
 #[no_mangle]
-pub unsafe extern "C" fn zenith_load_plugin(
+pub unsafe extern "C" fn zenith_stats(engine_ptr: *mut c_void, out: *mut EngineStats) -> i32 {
+    if engine_ptr.is_null() || out.is_null() {
+        return ffi_error::NULL_POINTER;
+    }
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let engine = &*(engine_ptr as *mut ZenithEngine);
+        out.write(engine.stats());
+        ffi_error::SUCCESS
+    }));
+
+    match result {
+        Ok(code) => code,
+        Err(_) => {
+            error!("PANIC in zenith_stats - caught safely");
+            ffi_error::PANIC
+        }
+    }
+}
+
+/// Describe the next event in the ring buffer without removing it. Meant
+/// for callers draining the engine themselves: with consumer threads
+/// running, the peeked event may be processed at any moment.
+///
+/// # Returns
+/// - 1: An event is waiting; its header was written to `out`
+/// - 0: The buffer is empty; `out` is untouched
+/// - -1: Null pointer
+/// - -3: Panic occurred
+///
+/// # Safety
+/// - engine_ptr must be valid pointer from zenith_init
+/// - out must point to writable memory for one ZenithEventHeader
+#[no_mangle]
+pub unsafe extern "C" fn zenith_peek_header(engine_ptr: *mut c_void, out: *mut ZenithEventHeader) -> i32 {
+    if engine_ptr.is_null() || out.is_null() {
+        return ffi_error::NULL_POINTER;
+    }
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let engine = &*(engine_ptr as *mut ZenithEngine);
+        match engine.get_ring_buffer().peek_with(|event| ZenithEventHeader::from(event)) {
+            Some(header) => {
+                out.write(header);
+                1
+            }
+            None => 0,
+        }
+    }));
+
+    match result {
+        Ok(code) => code,
+        Err(_) => {
+            error!("PANIC in zenith_peek_header - caught safely");
+            ffi_error::PANIC
+        }
+    }
+}
+
+/// Publish `total_events` synthetic events from `source_id` at
+/// `events_per_second` (0 = as fast as possible), using the default
+/// `LoadConfig` schema. Blocks the calling thread until all have been
+/// attempted.
+///
+/// # Returns
+/// - 0: Success; counts written to `out_published` and `out_buffer_full`
+/// - -1: Null pointer
+/// - -3: Panic occurred
+/// - -4: Synthetic batch could not be built
+///
+/// # Safety
+/// - engine_ptr must be valid pointer from zenith_init
+/// - out_published and out_buffer_full must point to writable u64s
+#[no_mangle]
+pub unsafe extern "C" fn zenith_generate_load(
     engine_ptr: *mut c_void,
-    wasm_bytes: *const u8,
-    len: usize
+    source_id: u32,
+    events_per_second: u64,
+    total_events: u64,
+    out_published: *mut u64,
+    out_buffer_full: *mut u64
 ) -> i32 {
-    if engine_ptr.is_null() || wasm_bytes.is_null() {
+    if engine_ptr.is_null() || out_published.is_null() || out_buffer_full.is_null() {
         return ffi_error::NULL_POINTER;
     }
-    
+
     let result = catch_unwind(AssertUnwindSafe(|| {
         let engine = &*(engine_ptr as *mut ZenithEngine);
-        let slice = std::slice::from_raw_parts(wasm_bytes, len);
-        
-        match engine.load_plugin(slice) {
-            Ok(_) => ffi_error::SUCCESS,
-            Err(_) => ffi_error::BUFFER_FULL, // Reusing -2 for load failed
+        let config = LoadConfig { events_per_second, total_events, source_id, ..LoadConfig::default() };
+        match LoadGenerator::new(config) {
+            Ok(generator) => {
+                let report = generator.run(engine);
+                out_published.write(report.events_published);
+                out_buffer_full.write(report.buffer_full);
+                ffi_error::SUCCESS
+            }
+            Err(_) => ffi_error::FFI_ERROR,
         }
     }));
-    
+
     match result {
         Ok(code) => code,
         Err(_) => {
-            eprintln!("[zenith] PANIC in zenith_load_plugin - caught safely");
+            error!("PANIC in zenith_generate_load - caught safely");
             ffi_error::PANIC
         }
     }
 }
 
+/// Install the engine's log subscriber, writing to stderr. Safe to call
+/// before any engine exists, and more than once: the format is fixed by
+/// the first call (or `zenith_set_log_level`), later calls only change
+/// the level.
+///
+/// `level`: 0 = off, 1 = error, 2 = warn, 3 = info, 4 = debug, 5 = trace.
+/// `format`: 0 = text, 1 = JSON lines.
+///
+/// # Returns
+/// - 0: Success
+/// - -3: Panic occurred
+/// - -5: The host process already installed its own subscriber
+/// - -12: Level or format out of range
+#[no_mangle]
+pub extern "C" fn zenith_init_logging(level: u32, format: u32) -> i32 {
+    let (Some(level), Some(format)) = (
+        logging::level_from_u32(level),
+        match format {
+            0 => Some(LogFormat::Text),
+            1 => Some(LogFormat::Json),
+            _ => None,
+        },
+    ) else {
+        return ffi_error::INVALID_LOG_LEVEL;
+    };
+
+    match catch_unwind(|| logging::init_logging(level, format)) {
+        Ok(true) => ffi_error::SUCCESS,
+        Ok(false) => ffi_error::INIT_FAILED,
+        Err(_) => ffi_error::PANIC,
+    }
+}
+
+/// Change the log level, installing a text subscriber on stderr if none
+/// has been set up yet. Levels are numbered as for `zenith_init_logging`.
+///
+/// # Returns
+/// - 0: Success
+/// - -3: Panic occurred
+/// - -5: The host process already installed its own subscriber
+/// - -12: Level out of range
+#[no_mangle]
+pub extern "C" fn zenith_set_log_level(level: u32) -> i32 {
+    let Some(level) = logging::level_from_u32(level) else {
+        return ffi_error::INVALID_LOG_LEVEL;
+    };
+
+    match catch_unwind(|| logging::set_log_level(level)) {
+        Ok(true) => ffi_error::SUCCESS,
+        Ok(false) => ffi_error::INIT_FAILED,
+        Err(_) => ffi_error::PANIC,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -195,6 +988,16 @@ mod tests {
         assert_eq!(ffi_error::PANIC, -3);
         assert_eq!(ffi_error::FFI_ERROR, -4);
         assert_eq!(ffi_error::INIT_FAILED, -5);
+        assert_eq!(ffi_error::PLUGIN_LOAD_FAILED, -7);
+        assert_eq!(ffi_error::PLUGIN_VALIDATION_FAILED, -8);
+        assert_eq!(ffi_error::INVALID_FILTER, -9);
+        assert_eq!(ffi_error::INVALID_LIMITS, -10);
+        assert_eq!(ffi_error::INCOMPATIBLE_ARTIFACT, -11);
+        assert_eq!(ffi_error::INVALID_LOG_LEVEL, -12);
+        assert_eq!(ffi_error::SCHEMA_MISMATCH, -13);
+        assert_eq!(ffi_error::DICTIONARY_REJECTED, -14);
+        assert_eq!(ffi_error::INVALID_PATH, -15);
+        assert_eq!(ffi_error::FILE_READ_FAILED, -16);
         
         // Verify all error codes are distinct (negative numbers)
         assert!(ffi_error::NULL_POINTER < 0);
@@ -276,6 +1079,175 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_zenith_publish_blocking() {
+        use arrow::array::{Array, Int32Array, StructArray};
+        use arrow::datatypes::{DataType, Field, Schema};
+        use std::mem::ManuallyDrop;
+        use std::sync::Arc;
+
+        // Ownership moves into Rust, so the caller's copies are never dropped
+        let publish = |engine_ptr: *mut c_void, seq_no: u64, timeout_ms: u64| {
+            let schema = Arc::new(Schema::new(vec![Field::new("value", DataType::Int32, false)]));
+            let batch = RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(vec![1]))]).unwrap();
+            let (array, schema) = arrow::ffi::to_ffi(&StructArray::from(batch).to_data()).unwrap();
+            let mut array = ManuallyDrop::new(array);
+            let mut schema = ManuallyDrop::new(schema);
+            unsafe { zenith_publish_blocking(engine_ptr, &mut *array, &mut *schema, 1, seq_no, timeout_ms) }
+        };
+
+        // Not started, so nothing drains the buffer until the test does
+        let engine_ptr = Box::into_raw(Box::new(ZenithEngine::new(2).unwrap())) as *mut c_void;
+        let engine = unsafe { &*(engine_ptr as *mut ZenithEngine) };
+        assert_eq!(publish(std::ptr::null_mut(), 0, 0), ffi_error::NULL_POINTER);
+        assert_eq!(publish(engine_ptr, 1, 0), ffi_error::SUCCESS);
+        assert_eq!(publish(engine_ptr, 2, 0), ffi_error::SUCCESS);
+        assert_eq!(publish(engine_ptr, 3, 0), ffi_error::BUFFER_FULL);
+        assert_eq!(publish(engine_ptr, 3, 10), ffi_error::BUFFER_FULL);
+
+        let ring_buffer = engine.get_ring_buffer();
+        let consumer = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            ring_buffer.pop().unwrap().header.seq_no
+        });
+        assert_eq!(publish(engine_ptr, 3, 10_000), ffi_error::SUCCESS);
+        assert_eq!(consumer.join().unwrap(), 1);
+        assert_eq!(engine.get_ring_buffer().len(), 2);
+
+        unsafe { zenith_free(engine_ptr) };
+    }
+    
+    #[test]
+    fn test_zenith_publish_expected_schema() {
+        use arrow::array::{Array, Int32Array, StructArray};
+        use arrow::datatypes::{DataType, Field};
+        use std::mem::ManuallyDrop;
+        use std::sync::Arc;
+
+        let publish = |engine_ptr: *mut c_void, data_type: DataType, nullable: bool| {
+            let schema = Arc::new(Schema::new(vec![Field::new("value", data_type.clone(), nullable)]));
+            let column = arrow::compute::cast(&Int32Array::from(vec![1]), &data_type).unwrap();
+            let batch = RecordBatch::try_new(schema, vec![column]).unwrap();
+            let (array, schema) = arrow::ffi::to_ffi(&StructArray::from(batch).to_data()).unwrap();
+            let mut array = ManuallyDrop::new(array);
+            let mut schema = ManuallyDrop::new(schema);
+            unsafe { zenith_publish(engine_ptr, &mut *array, &mut *schema, 1, 0) }
+        };
+
+        let engine_ptr = Box::into_raw(Box::new(ZenithEngine::new(16).unwrap())) as *mut c_void;
+        let expected = Schema::new(vec![Field::new("value", DataType::Int32, true)]);
+        let ffi_schema = FFI_ArrowSchema::try_from(&expected).unwrap();
+        unsafe {
+            assert_eq!(zenith_set_expected_schema(std::ptr::null_mut(), &ffi_schema), ffi_error::NULL_POINTER);
+            assert_eq!(zenith_set_expected_schema(engine_ptr, &ffi_schema), ffi_error::SUCCESS);
+        }
+
+        assert_eq!(publish(engine_ptr, DataType::Int32, true), ffi_error::SUCCESS);
+        assert_eq!(publish(engine_ptr, DataType::Int64, true), ffi_error::SCHEMA_MISMATCH);
+        // Nullable-widening: non-null data fits a nullable column
+        assert_eq!(publish(engine_ptr, DataType::Int32, false), ffi_error::SUCCESS);
+
+        let engine = unsafe { &*(engine_ptr as *mut ZenithEngine) };
+        assert_eq!(engine.get_ring_buffer().len(), 2);
+
+        unsafe {
+            assert_eq!(zenith_set_expected_schema(engine_ptr, std::ptr::null()), ffi_error::SUCCESS);
+        }
+        assert_eq!(publish(engine_ptr, DataType::Int64, true), ffi_error::SUCCESS);
+
+        unsafe { zenith_free(engine_ptr) };
+    }
+
+    #[test]
+    fn test_zenith_publish_dictionary_column() {
+        use arrow::array::{Array, DictionaryArray, StringArray, StructArray};
+        use arrow::datatypes::{DataType, Field, Int32Type};
+        use std::mem::ManuallyDrop;
+        use std::sync::Arc;
+
+        let publish = |engine_ptr: *mut c_void| {
+            let column: DictionaryArray<Int32Type> = vec!["eu", "us", "eu"].into_iter().collect();
+            let schema = Arc::new(Schema::new(vec![Field::new("region", column.data_type().clone(), false)]));
+            let batch = RecordBatch::try_new(schema, vec![Arc::new(column)]).unwrap();
+            let (array, schema) = arrow::ffi::to_ffi(&StructArray::from(batch).to_data()).unwrap();
+            let mut array = ManuallyDrop::new(array);
+            let mut schema = ManuallyDrop::new(schema);
+            unsafe { zenith_publish(engine_ptr, &mut *array, &mut *schema, 1, 0) }
+        };
+
+        let engine_ptr = Box::into_raw(Box::new(ZenithEngine::new(16).unwrap())) as *mut c_void;
+        let engine = unsafe { &*(engine_ptr as *mut ZenithEngine) };
+
+        // Decoded by default, and checked against the expected schema as such
+        engine.set_expected_schema(Some(Arc::new(Schema::new(vec![Field::new("region", DataType::Utf8, false)]))));
+        assert_eq!(publish(engine_ptr), ffi_error::SUCCESS);
+        let batch = engine.get_ring_buffer().pop().unwrap().batch().unwrap().clone();
+        assert_eq!(batch.schema().field(0).data_type(), &DataType::Utf8);
+        let regions = batch.column(0).as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(regions, &StringArray::from(vec!["eu", "us", "eu"]));
+
+        unsafe {
+            assert_eq!(zenith_set_dictionary_decoding(std::ptr::null_mut(), 0), ffi_error::NULL_POINTER);
+            assert_eq!(zenith_set_dictionary_decoding(engine_ptr, 0), ffi_error::SUCCESS);
+        }
+        assert_eq!(publish(engine_ptr), ffi_error::DICTIONARY_REJECTED);
+        assert!(engine.get_ring_buffer().is_empty());
+
+        unsafe {
+            assert_eq!(zenith_set_dictionary_decoding(engine_ptr, 1), ffi_error::SUCCESS);
+        }
+        assert_eq!(publish(engine_ptr), ffi_error::SUCCESS);
+
+        unsafe { zenith_free(engine_ptr) };
+    }
+
+    #[test]
+    fn test_zenith_publish_ipc() {
+        use arrow::array::Int64Array;
+        use arrow::datatypes::{DataType, Field, Schema};
+        use arrow::ipc::writer::StreamWriter;
+        use std::sync::Arc;
+
+        let schema = Arc::new(Schema::new(vec![Field::new("value", DataType::Int64, false)]));
+        let mut ipc = Vec::new();
+        let mut writer = StreamWriter::try_new(&mut ipc, &schema).unwrap();
+        for chunk in [vec![1, 2], vec![3]] {
+            let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(Int64Array::from(chunk))]).unwrap();
+            writer.write(&batch).unwrap();
+        }
+        writer.finish().unwrap();
+
+        // Not started, so published events stay queued
+        let engine_ptr = Box::into_raw(Box::new(ZenithEngine::new(16).unwrap())) as *mut c_void;
+
+        unsafe {
+            assert_eq!(zenith_publish_ipc(std::ptr::null_mut(), ipc.as_ptr(), ipc.len(), 1, 1),
+                ffi_error::NULL_POINTER);
+            assert_eq!(zenith_publish_ipc(engine_ptr, std::ptr::null(), 0, 1, 1),
+                ffi_error::NULL_POINTER);
+            let garbage = b"not an arrow stream";
+            assert_eq!(zenith_publish_ipc(engine_ptr, garbage.as_ptr(), garbage.len(), 1, 1),
+                ffi_error::FFI_ERROR);
+            // Truncated mid-batch
+            assert_eq!(zenith_publish_ipc(engine_ptr, ipc.as_ptr(), ipc.len() - 16, 1, 1),
+                ffi_error::FFI_ERROR);
+
+            let engine = &*(engine_ptr as *mut ZenithEngine);
+            assert!(engine.get_ring_buffer().is_empty());
+            assert_eq!(zenith_publish_ipc(engine_ptr, ipc.as_ptr(), ipc.len(), 7, 42), ffi_error::SUCCESS);
+
+            let event = engine.get_ring_buffer().pop().expect("event should be queued");
+            assert_eq!(event.header.source_id, 7);
+            assert_eq!(event.header.seq_no, 42);
+            let payload = event.payload.expect("event should carry the batch");
+            assert_eq!(payload.schema(), schema);
+            let values = payload.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+            assert_eq!(values.values(), &[1, 2, 3]);
+
+            zenith_free(engine_ptr);
+        }
+    }
+    
     #[test]
     fn test_zenith_load_plugin_null_pointers() {
         unsafe {
@@ -320,14 +1292,307 @@ mod tests {
                 invalid_wasm.as_ptr(),
                 invalid_wasm.len()
             );
-            // Should return an error (BUFFER_FULL is reused for load failed)
-            assert_eq!(result, ffi_error::BUFFER_FULL,
-                "Invalid WASM should return error");
+            assert_eq!(result, ffi_error::PLUGIN_LOAD_FAILED,
+                "Invalid WASM should return PLUGIN_LOAD_FAILED");
+            
+            zenith_free(engine_ptr);
+        }
+    }
+    
+    #[test]
+    fn test_zenith_load_plugin_rejected_wasm() {
+        let engine_ptr = zenith_init(1024);
+        assert!(!engine_ptr.is_null());
+        
+        // Well-formed module importing a host function we don't provide
+        let rejected_wasm = wat::parse_str(r#"
+            (module
+                (import "env" "forbidden" (func))
+                (func (export "on_event") (param i32 i64) (result i32) i32.const 1))
+        "#).unwrap();
+        
+        unsafe {
+            let result = zenith_load_plugin(
+                engine_ptr,
+                rejected_wasm.as_ptr(),
+                rejected_wasm.len()
+            );
+            assert_eq!(result, ffi_error::PLUGIN_VALIDATION_FAILED,
+                "Well-formed but rejected WASM should return PLUGIN_VALIDATION_FAILED");
+            
+            let no_on_event = wat::parse_str("(module)").unwrap();
+            assert_eq!(zenith_load_plugin(engine_ptr, no_on_event.as_ptr(), no_on_event.len()),
+                ffi_error::PLUGIN_VALIDATION_FAILED);
+            
+            zenith_free(engine_ptr);
+        }
+    }
+    
+    #[test]
+    fn test_zenith_load_plugin_file() {
+        use std::ffi::CString;
+        
+        let dir = std::env::temp_dir().join(format!("zenith-plugin-file-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let valid = dir.join("accept.wasm");
+        std::fs::write(&valid, wat::parse_str(r#"
+            (module
+                (func (export "on_event") (param i32 i64) (result i32)
+                    i32.const 1))
+        "#).unwrap()).unwrap();
+        let garbage = dir.join("garbage.wasm");
+        std::fs::write(&garbage, b"this is not valid wasm").unwrap();
+        
+        let load = |engine_ptr: *mut c_void, path: &str| {
+            let path = CString::new(path).unwrap();
+            unsafe { zenith_load_plugin_file(engine_ptr, path.as_ptr()) }
+        };
+        
+        let engine_ptr = zenith_init(1024);
+        assert_eq!(load(engine_ptr, valid.to_str().unwrap()), ffi_error::SUCCESS);
+        assert_eq!(load(engine_ptr, dir.join("missing.wasm").to_str().unwrap()), ffi_error::FILE_READ_FAILED);
+        assert_eq!(load(engine_ptr, garbage.to_str().unwrap()), ffi_error::PLUGIN_LOAD_FAILED);
+        
+        // Refused before touching the file system, even though it resolves
+        // to the valid plugin
+        let traversal = format!("{}/../{}/accept.wasm", dir.display(), dir.file_name().unwrap().to_str().unwrap());
+        assert_eq!(load(engine_ptr, &traversal), ffi_error::INVALID_PATH);
+        assert_eq!(load(engine_ptr, &"a".repeat(validation::MAX_PATH_LENGTH + 1)), ffi_error::INVALID_PATH);
+        
+        let engine = unsafe { &*(engine_ptr as *mut ZenithEngine) };
+        assert_eq!(engine.stats().plugins_loaded, 1);
+        unsafe {
+            assert_eq!(zenith_load_plugin_file(engine_ptr, std::ptr::null()), ffi_error::NULL_POINTER);
+            zenith_free(engine_ptr);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn test_zenith_load_plugin_limited() {
+        // Not started, so the test drives the consumer itself
+        let engine_ptr = Box::into_raw(Box::new(ZenithEngine::new(16).unwrap())) as *mut c_void;
+        let spinning = wat::parse_str(r#"
+            (module
+                (func (export "on_event") (param i32 i64) (result i32)
+                    (loop $spin (br $spin))
+                    i32.const 0))
+        "#).unwrap();
+        
+        unsafe {
+            let load = |memory, timeout_ms, host_calls| zenith_load_plugin_limited(
+                engine_ptr, spinning.as_ptr(), spinning.len(), memory, timeout_ms, host_calls);
+            assert_eq!(zenith_load_plugin_limited(std::ptr::null_mut(), spinning.as_ptr(), spinning.len(), 1, 1, 1),
+                ffi_error::NULL_POINTER);
+            assert_eq!(load(0, 10, 10), ffi_error::INVALID_LIMITS);
+            assert_eq!(load(1 << 20, 0, 10), ffi_error::INVALID_LIMITS);
+            assert_eq!(load(1 << 20, 10, 0), ffi_error::INVALID_LIMITS);
+            assert_eq!(load(1 << 20, 60_001, 10), ffi_error::INVALID_LIMITS);
+            
+            assert_eq!(load(1 << 20, 20, 10), ffi_error::SUCCESS);
+            
+            // Only the timeout can stop the plugin
+            let engine = &*(engine_ptr as *mut ZenithEngine);
+            engine.set_plugin_fuel_budget(u64::MAX);
+            engine.get_ring_buffer().push(ZenithEvent::new(1, 1, arrow::record_batch::RecordBatch::new_empty(
+                std::sync::Arc::new(arrow::datatypes::Schema::empty())))).unwrap();
+            let started = std::time::Instant::now();
+            assert_eq!(engine.process_one(), Some(true));
+            assert!(started.elapsed() < Duration::from_secs(5),
+                "Plugin should be interrupted at its timeout");
+            
+            zenith_free(engine_ptr);
+        }
+    }
+    
+    #[test]
+    fn test_zenith_precompile_and_load() {
+        let engine_ptr = zenith_init(1024);
+        assert!(!engine_ptr.is_null());
+        let wasm = wat::parse_str(r#"
+            (module
+                (func (export "on_event") (param i32 i64) (result i32)
+                    i32.const 1))
+        "#).unwrap();
+        
+        unsafe {
+            let mut len = 0;
+            assert!(zenith_precompile_plugin(b"junk".as_ptr(), 4, &mut len).is_null());
+            
+            let artifact = zenith_precompile_plugin(wasm.as_ptr(), wasm.len(), &mut len);
+            assert!(!artifact.is_null());
+            assert!(len > 0);
+            assert_eq!(zenith_load_precompiled_plugin(engine_ptr, artifact, len), ffi_error::SUCCESS);
+            zenith_free_buffer(artifact, len);
             
+            assert_eq!(zenith_load_precompiled_plugin(engine_ptr, wasm.as_ptr(), wasm.len()),
+                ffi_error::INCOMPATIBLE_ARTIFACT);
+            assert_eq!(zenith_load_precompiled_plugin(std::ptr::null_mut(), wasm.as_ptr(), wasm.len()),
+                ffi_error::NULL_POINTER);
+            
+            zenith_free(engine_ptr);
+        }
+    }
+    
+    unsafe extern "C" fn count_sink(user_data: *mut c_void, _source_id: u32, seq_no: u64, _timestamp_ns: u64) {
+        let seen = &*(user_data as *const std::sync::Mutex<Vec<u64>>);
+        seen.lock().unwrap().push(seq_no);
+    }
+    
+    #[test]
+    fn test_zenith_set_sink() {
+        // Not started, so the test drives the consumer itself
+        let engine_ptr = Box::into_raw(Box::new(ZenithEngine::new(16).unwrap())) as *mut c_void;
+        let seen = std::sync::Mutex::new(Vec::<u64>::new());
+        let user_data = &seen as *const _ as *mut c_void;
+        let event = |seq_no| ZenithEvent::new(1, seq_no, arrow::record_batch::RecordBatch::new_empty(
+            std::sync::Arc::new(arrow::datatypes::Schema::empty())));
+        
+        unsafe {
+            assert_eq!(zenith_set_sink(std::ptr::null_mut(), Some(count_sink), user_data), ffi_error::NULL_POINTER);
+            assert_eq!(zenith_set_sink(engine_ptr, Some(count_sink), user_data), ffi_error::SUCCESS);
+            assert_eq!(zenith_set_filter(engine_ptr, c"seq_no > 1".as_ptr()), ffi_error::SUCCESS);
+            
+            let engine = &*(engine_ptr as *mut ZenithEngine);
+            for seq_no in 1..=3 {
+                engine.get_ring_buffer().push(event(seq_no)).unwrap();
+            }
+            while engine.process_one().is_some() {}
+            assert_eq!(*seen.lock().unwrap(), vec![2, 3]);
+            
+            assert_eq!(zenith_set_sink(engine_ptr, None, std::ptr::null_mut()), ffi_error::SUCCESS);
+            engine.get_ring_buffer().push(event(4)).unwrap();
+            engine.process_one();
+            assert_eq!(seen.lock().unwrap().len(), 2);
+            
+            zenith_free(engine_ptr);
+        }
+    }
+    
+    #[test]
+    fn test_zenith_stats() {
+        // Not started, so the test drives the consumer itself
+        let engine_ptr = Box::into_raw(Box::new(ZenithEngine::new(16).unwrap())) as *mut c_void;
+        let mut stats = EngineStats::default();
+        
+        unsafe {
+            assert_eq!(zenith_stats(std::ptr::null_mut(), &mut stats), ffi_error::NULL_POINTER);
+            assert_eq!(zenith_stats(engine_ptr, std::ptr::null_mut()), ffi_error::NULL_POINTER);
+            
+            assert_eq!(zenith_set_filter(engine_ptr, c"seq_no > 1".as_ptr()), ffi_error::SUCCESS);
+            let engine = &*(engine_ptr as *mut ZenithEngine);
+            for seq_no in [1, 2, 5] {
+                engine.get_ring_buffer().push(ZenithEvent::new(1, seq_no, arrow::record_batch::RecordBatch::new_empty(
+                    std::sync::Arc::new(arrow::datatypes::Schema::empty())))).unwrap();
+            }
+            engine.process_one();
+            engine.process_one();
+            
+            assert_eq!(zenith_stats(engine_ptr, &mut stats), ffi_error::SUCCESS);
+            assert_eq!(stats, EngineStats {
+                buffer_len: 1,
+                buffer_capacity: engine.get_ring_buffer().capacity() as u64,
+                plugins_loaded: 0,
+                events_processed: 2,
+                events_dropped: 1,
+                gaps_detected: 0,
+                plugin_breaker_trips: 0,
+                // Wall-clock dependent; see the engine's latency test
+                latency_p50_ns: stats.latency_p50_ns,
+                latency_p95_ns: stats.latency_p95_ns,
+                latency_p99_ns: stats.latency_p99_ns,
+            });
+            assert!(stats.latency_p50_ns <= stats.latency_p99_ns);
+            
+            zenith_free(engine_ptr);
+        }
+    }
+    
+    #[test]
+    fn test_zenith_peek_header() {
+        use arrow::array::Int32Array;
+        use arrow::datatypes::{DataType, Field};
+        use std::sync::Arc;
+        
+        let engine_ptr = Box::into_raw(Box::new(ZenithEngine::new(16).unwrap())) as *mut c_void;
+        let engine = unsafe { &*(engine_ptr as *mut ZenithEngine) };
+        let mut header = ZenithEventHeader::default();
+        
+        unsafe {
+            assert_eq!(zenith_peek_header(std::ptr::null_mut(), &mut header), ffi_error::NULL_POINTER);
+            assert_eq!(zenith_peek_header(engine_ptr, std::ptr::null_mut()), ffi_error::NULL_POINTER);
+            assert_eq!(zenith_peek_header(engine_ptr, &mut header), 0);
+            assert_eq!(header, ZenithEventHeader::default(), "Left untouched when empty");
+        }
+        
+        let schema = Arc::new(Schema::new(vec![Field::new("value", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(vec![1, 2, 3, 4]))]).unwrap();
+        let event = ZenithEvent::new(7, 42, batch);
+        let ingest_ns = event.header.ingest_ns;
+        engine.get_ring_buffer().push(event).unwrap();
+        
+        unsafe {
+            assert_eq!(zenith_peek_header(engine_ptr, &mut header), 1);
+        }
+        assert_eq!(header, ZenithEventHeader { source_id: 7, seq_no: 42, ingest_ns, num_rows: 4 });
+        
+        // Peeking leaves the event for the next pop
+        assert_eq!(engine.get_ring_buffer().len(), 1);
+        assert_eq!(engine.process_one(), Some(true));
+        unsafe {
+            assert_eq!(zenith_peek_header(engine_ptr, &mut header), 0);
             zenith_free(engine_ptr);
         }
     }
     
+    #[test]
+    fn test_zenith_generate_load() {
+        // Not started, so the buffer fills up
+        let engine_ptr = Box::into_raw(Box::new(ZenithEngine::new(16).unwrap())) as *mut c_void;
+        let (mut published, mut buffer_full) = (0u64, 0u64);
+
+        unsafe {
+            assert_eq!(
+                zenith_generate_load(engine_ptr, 3, 0, 10, std::ptr::null_mut(), &mut buffer_full),
+                ffi_error::NULL_POINTER
+            );
+
+            assert_eq!(zenith_generate_load(engine_ptr, 3, 0, 40, &mut published, &mut buffer_full), ffi_error::SUCCESS);
+            assert_eq!((published, buffer_full), (16, 24));
+            let engine = &*(engine_ptr as *mut ZenithEngine);
+            assert_eq!(engine.get_ring_buffer().pop().unwrap().header.source_id, 3);
+
+            zenith_free(engine_ptr);
+        }
+    }
+
+    #[test]
+    fn test_zenith_set_filter() {
+        let engine_ptr = zenith_init(1024);
+        assert!(!engine_ptr.is_null());
+
+        unsafe {
+            assert_eq!(zenith_set_filter(std::ptr::null_mut(), c"seq_no > 1".as_ptr()), ffi_error::NULL_POINTER);
+            assert_eq!(zenith_set_filter(engine_ptr, std::ptr::null()), ffi_error::NULL_POINTER);
+
+            assert_eq!(zenith_set_filter(engine_ptr, c"source_id != 0 && seq_no % 100 != 0".as_ptr()), ffi_error::SUCCESS);
+            let engine = &*(engine_ptr as *mut ZenithEngine);
+            assert!(engine.filter().is_some());
+
+            // A bad expression leaves the current filter installed
+            assert_eq!(zenith_set_filter(engine_ptr, c"source_id = 0".as_ptr()), ffi_error::INVALID_FILTER);
+            assert!(engine.filter().is_some());
+
+            let invalid_utf8 = [0xFFu8, 0xFE, 0x00];
+            assert_eq!(zenith_set_filter(engine_ptr, invalid_utf8.as_ptr() as *const c_char), ffi_error::INVALID_FILTER);
+
+            assert_eq!(zenith_set_filter(engine_ptr, c"".as_ptr()), ffi_error::SUCCESS);
+            assert!(engine.filter().is_none());
+
+            zenith_free(engine_ptr);
+        }
+    }
+
     #[test]
     fn test_engine_and_event_reexports() {
         // Test that Engine and Event are properly re-exported
@@ -0,0 +1,234 @@
+//! Synthetic publisher for reproducing throughput problems.
+//!
+//! A `LoadGenerator` pushes events carrying a generated batch of the
+//! configured schema onto an engine's ring buffer at a target rate, and
+//! reports how many the buffer took and how many it turned away.
+
+use crate::engine::ZenithEngine;
+use crate::error::{Result, ZenithError};
+use crate::event::ZenithEvent;
+use arrow::array::{
+    ArrayRef, BooleanArray, Float32Array, Float64Array, Int32Array, Int64Array, StringArray,
+    UInt32Array, UInt64Array,
+};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct LoadConfig {
+    /// Target publish rate; 0 publishes as fast as the buffer accepts
+    pub events_per_second: u64,
+    /// Events to attempt before `run` returns
+    pub total_events: u64,
+    /// Rows in each event's batch
+    pub rows_per_batch: usize,
+    /// Columns of the generated batch. Integer, float, boolean and string
+    /// columns are supported.
+    pub schema: SchemaRef,
+    pub source_id: u32,
+}
+
+impl Default for LoadConfig {
+    fn default() -> Self {
+        Self {
+            events_per_second: 10_000,
+            total_events: 10_000,
+            rows_per_batch: 64,
+            schema: Arc::new(Schema::new(vec![
+                Field::new("id", DataType::Int64, false),
+                Field::new("value", DataType::Float64, false),
+                Field::new("tag", DataType::Utf8, false),
+            ])),
+            source_id: 0,
+        }
+    }
+}
+
+/// Outcome of one `LoadGenerator::run`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LoadReport {
+    /// Events the ring buffer accepted
+    pub events_published: u64,
+    /// Events rejected because the buffer was full
+    pub buffer_full: u64,
+    pub elapsed: Duration,
+}
+
+impl LoadReport {
+    /// Accepted events per second over the whole run
+    pub fn achieved_rate(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.events_published as f64 / secs
+        } else {
+            0.0
+        }
+    }
+}
+
+pub struct LoadGenerator {
+    config: LoadConfig,
+    /// Every event shares this batch, so generating load costs no more
+    /// than the engine spends on it
+    batch: RecordBatch,
+}
+
+impl LoadGenerator {
+    /// Fails if the schema has a column type that can't be synthesized
+    pub fn new(config: LoadConfig) -> Result<Self> {
+        let rows = config.rows_per_batch;
+        let columns = config.schema.fields().iter()
+            .map(|field| synthetic_column(field, rows))
+            .collect::<Result<Vec<_>>>()?;
+        let batch = RecordBatch::try_new(config.schema.clone(), columns)?;
+        Ok(Self { config, batch })
+    }
+
+    pub fn config(&self) -> &LoadConfig {
+        &self.config
+    }
+
+    /// Publish `total_events` onto the engine's ring buffer, pacing them to
+    /// `events_per_second`, and block until done. Events are numbered from
+    /// seq_no 0, and a rejected event's seq_no is skipped, so consumers see
+    /// the rejections as gaps. Falling behind schedule (e.g. after a slow
+    /// push) is caught up by publishing without pauses.
+    pub fn run(&self, engine: &ZenithEngine) -> LoadReport {
+        let buffer = engine.get_ring_buffer();
+        let interval = (self.config.events_per_second > 0)
+            .then(|| Duration::from_secs_f64(1.0 / self.config.events_per_second as f64));
+        let mut report = LoadReport::default();
+        let start = Instant::now();
+
+        for seq_no in 0..self.config.total_events {
+            if let Some(interval) = interval {
+                let due = start + interval.mul_f64(seq_no as f64);
+                let now = Instant::now();
+                if due > now {
+                    thread::sleep(due - now);
+                }
+            }
+
+            let event = ZenithEvent::new(self.config.source_id, seq_no, self.batch.clone());
+            match buffer.push(event) {
+                Ok(()) => report.events_published += 1,
+                Err(_) => report.buffer_full += 1,
+            }
+        }
+
+        report.elapsed = start.elapsed();
+        report
+    }
+}
+
+fn synthetic_column(field: &Field, rows: usize) -> Result<ArrayRef> {
+    let column: ArrayRef = match field.data_type() {
+        DataType::Int32 => Arc::new(Int32Array::from_iter_values((0..rows).map(|i| i as i32))),
+        DataType::Int64 => Arc::new(Int64Array::from_iter_values((0..rows).map(|i| i as i64))),
+        DataType::UInt32 => Arc::new(UInt32Array::from_iter_values((0..rows).map(|i| i as u32))),
+        DataType::UInt64 => Arc::new(UInt64Array::from_iter_values((0..rows).map(|i| i as u64))),
+        DataType::Float32 => Arc::new(Float32Array::from_iter_values((0..rows).map(|i| i as f32 * 0.5))),
+        DataType::Float64 => Arc::new(Float64Array::from_iter_values((0..rows).map(|i| i as f64 * 0.5))),
+        DataType::Boolean => Arc::new(BooleanArray::from_iter((0..rows).map(|i| Some(i % 2 == 0)))),
+        DataType::Utf8 => Arc::new(StringArray::from_iter_values((0..rows).map(|i| format!("row-{}", i)))),
+        other => {
+            return Err(ZenithError::Arrow(format!(
+                "Load generator can't synthesize {} column '{}'",
+                other,
+                field.name()
+            )))
+        }
+    };
+    Ok(column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    fn config(events_per_second: u64, total_events: u64) -> LoadConfig {
+        LoadConfig { events_per_second, total_events, ..LoadConfig::default() }
+    }
+
+    #[test]
+    fn test_burst_reaches_consumer() {
+        let engine = ZenithEngine::new(1024).unwrap();
+        let seen = Arc::new(AtomicU64::new(0));
+        let rows = Arc::new(AtomicU64::new(0));
+        let (sink_seen, sink_rows) = (seen.clone(), rows.clone());
+        engine.set_sink(Box::new(move |event| {
+            sink_seen.fetch_add(1, Ordering::Relaxed);
+            sink_rows.fetch_add(event.batch().map_or(0, |batch| batch.num_rows() as u64), Ordering::Relaxed);
+        }));
+        engine.start();
+
+        let report = LoadGenerator::new(config(50_000, 500)).unwrap().run(&engine);
+        assert_eq!(report.events_published + report.buffer_full, 500);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while engine.stats().events_processed < report.events_published && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(1));
+        }
+        engine.shutdown();
+
+        let stats = engine.stats();
+        assert_eq!(stats.events_processed, report.events_published);
+        assert_eq!(seen.load(Ordering::Relaxed), report.events_published);
+        assert_eq!(rows.load(Ordering::Relaxed), report.events_published * 64);
+        if report.buffer_full == 0 {
+            assert_eq!(stats.gaps_detected, 0);
+        }
+        assert!(report.achieved_rate() > 0.0);
+    }
+
+    #[test]
+    fn test_full_buffer_is_counted() {
+        // No consumer, so the buffer only takes its capacity
+        let engine = ZenithEngine::new(8).unwrap();
+        let report = LoadGenerator::new(config(0, 20)).unwrap().run(&engine);
+
+        assert_eq!(report.events_published, 8);
+        assert_eq!(report.buffer_full, 12);
+        assert_eq!(engine.get_ring_buffer().len(), 8);
+        let mut drained = 0;
+        while engine.process_one().is_some() {
+            drained += 1;
+        }
+        assert_eq!(drained, report.events_published);
+    }
+
+    #[test]
+    fn test_rate_is_paced() {
+        let engine = ZenithEngine::new(64).unwrap();
+        let report = LoadGenerator::new(config(1_000, 50)).unwrap().run(&engine);
+
+        assert_eq!(report.events_published, 50);
+        assert!(report.elapsed >= Duration::from_millis(45), "{:?}", report.elapsed);
+        assert!(report.achieved_rate() <= 1_200.0, "{}", report.achieved_rate());
+    }
+
+    #[test]
+    fn test_custom_schema() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("flag", DataType::Boolean, false),
+            Field::new("count", DataType::UInt32, false),
+        ]));
+        let generator = LoadGenerator::new(LoadConfig {
+            schema: schema.clone(),
+            rows_per_batch: 3,
+            ..config(0, 1)
+        }).unwrap();
+        assert_eq!(generator.batch.schema(), schema);
+        assert_eq!(generator.batch.num_rows(), 3);
+
+        let unsupported = Arc::new(Schema::new(vec![Field::new("at", DataType::Date32, false)]));
+        let err = LoadGenerator::new(LoadConfig { schema: unsupported, ..LoadConfig::default() })
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("can't synthesize Date32 column 'at'"), "{}", err);
+    }
+}
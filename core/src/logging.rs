@@ -0,0 +1,140 @@
+//! Process-wide `tracing` subscriber for embedders (mostly FFI callers)
+//! that don't install their own.
+//!
+//! The subscriber is installed once, by the first `init_logging` or
+//! `set_log_level`; later calls only change the level. If the host
+//! process already installed a global subscriber, that one is left in
+//! place and the level can't be changed from here.
+
+use std::io;
+use std::sync::OnceLock;
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::fmt::{self, MakeWriter};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{reload, Registry};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per event
+    Json,
+}
+
+type LevelHandle = reload::Handle<LevelFilter, Registry>;
+
+/// Set by the first install attempt; `None` if another subscriber won
+static LEVEL_HANDLE: OnceLock<Option<LevelHandle>> = OnceLock::new();
+
+/// Install the engine's subscriber, writing to stderr, and set its level.
+/// `format` only matters on the first call. Returns false if another
+/// global subscriber is in use.
+pub fn init_logging(level: LevelFilter, format: LogFormat) -> bool {
+    install(level, format, io::stderr);
+    set_level(level)
+}
+
+/// Change the level, installing a text subscriber first if there is none
+pub fn set_log_level(level: LevelFilter) -> bool {
+    install(level, LogFormat::Text, io::stderr);
+    set_level(level)
+}
+
+/// FFI level numbering: 0 = off, 1 = error, 2 = warn, 3 = info,
+/// 4 = debug, 5 = trace
+pub fn level_from_u32(level: u32) -> Option<LevelFilter> {
+    match level {
+        0 => Some(LevelFilter::OFF),
+        1 => Some(LevelFilter::ERROR),
+        2 => Some(LevelFilter::WARN),
+        3 => Some(LevelFilter::INFO),
+        4 => Some(LevelFilter::DEBUG),
+        5 => Some(LevelFilter::TRACE),
+        _ => None,
+    }
+}
+
+fn install<W>(level: LevelFilter, format: LogFormat, writer: W)
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    LEVEL_HANDLE.get_or_init(|| {
+        let (filter, handle) = reload::Layer::new(level);
+        let registry = Registry::default().with(filter);
+        let installed = match format {
+            LogFormat::Text => tracing::subscriber::set_global_default(
+                registry.with(fmt::layer().with_writer(writer)),
+            ),
+            LogFormat::Json => tracing::subscriber::set_global_default(
+                registry.with(fmt::layer().json().with_writer(writer)),
+            ),
+        };
+        installed.ok().map(|()| handle)
+    });
+}
+
+fn set_level(level: LevelFilter) -> bool {
+    match LEVEL_HANDLE.get() {
+        Some(Some(handle)) => handle.reload(level).is_ok(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ffi_error;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Capture {
+        /// The captured JSON event whose message contains `marker`
+        fn event(&self, marker: &str) -> Option<serde_json::Value> {
+            let text = String::from_utf8(self.0.lock().unwrap().clone()).unwrap();
+            let line = text.lines().find(|line| line.contains(marker))?;
+            Some(serde_json::from_str(line).expect("JSON log line"))
+        }
+    }
+
+    // The subscriber is global, so this is the only test that installs it
+    #[test]
+    fn test_level_controls_captured_events() {
+        let captured = Capture::default();
+        let writer = captured.clone();
+        install(LevelFilter::WARN, LogFormat::Json, move || writer.clone());
+
+        assert!(set_log_level(LevelFilter::WARN));
+        tracing::info!("log-test hidden info");
+        tracing::warn!("log-test shown warning");
+        assert!(captured.event("log-test hidden info").is_none());
+        let event = captured.event("log-test shown warning").unwrap();
+        assert_eq!(event["level"], "WARN");
+        assert_eq!(event["fields"]["message"], "log-test shown warning");
+
+        // Through FFI; initialising again keeps the installed subscriber
+        assert_eq!(crate::zenith_set_log_level(3), ffi_error::SUCCESS);
+        tracing::info!("log-test info after raising");
+        assert!(captured.event("log-test info after raising").is_some());
+
+        assert_eq!(crate::zenith_init_logging(1, 0), ffi_error::SUCCESS);
+        tracing::warn!("log-test warning below error");
+        assert!(captured.event("log-test warning below error").is_none());
+
+        assert_eq!(crate::zenith_set_log_level(6), ffi_error::INVALID_LOG_LEVEL);
+        assert_eq!(crate::zenith_init_logging(3, 2), ffi_error::INVALID_LOG_LEVEL);
+    }
+}
@@ -0,0 +1,336 @@
+//! Parquet sink: persists allowed events' payloads to rolling Parquet files.
+//!
+//! Batches are buffered in memory and written out as a new file
+//! (`{prefix}-{n:06}.parquet`) once `max_rows` rows are pending or
+//! `flush_interval` has passed. The interval is checked as events arrive,
+//! so an idle sink holds its rows until the next event, `flush()` or drop.
+
+use crate::engine::EventSink;
+use crate::error::{Result, ZenithError};
+use crate::event::ZenithEvent;
+use crate::validation::Validator;
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{error, warn};
+
+#[derive(Debug, Clone)]
+pub struct ParquetSinkConfig {
+    /// Directory the Parquet files are written to; created if missing
+    pub dir: PathBuf,
+    /// File name prefix
+    pub file_prefix: String,
+    /// Roll to a new file once this many rows are pending
+    pub max_rows: usize,
+    /// Roll to a new file once pending rows are this old
+    pub flush_interval: Duration,
+}
+
+impl Default for ParquetSinkConfig {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("zenith-events"),
+            file_prefix: "events".to_string(),
+            max_rows: 100_000,
+            flush_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+struct SinkState {
+    /// Fixed by the first event; later events must match it
+    schema: Option<SchemaRef>,
+    pending: Vec<RecordBatch>,
+    pending_rows: usize,
+    last_flush: Instant,
+    files_written: usize,
+}
+
+pub struct ParquetSink {
+    config: ParquetSinkConfig,
+    state: Mutex<SinkState>,
+    rows_written: AtomicU64,
+    /// Events the engine forwarded that could not be persisted
+    rejected_events: AtomicU64,
+}
+
+impl ParquetSink {
+    pub fn new(config: ParquetSinkConfig) -> Result<Self> {
+        let validator = Validator::new();
+        let dir = config.dir.to_str()
+            .ok_or_else(|| ZenithError::Sink(format!("sink directory {:?} is not valid UTF-8", config.dir)))?;
+        validator.require_non_empty("dir", dir)?;
+        validator.validate_path(dir)?;
+        validator.validate_name("file_prefix", &config.file_prefix)?;
+        if config.max_rows == 0 {
+            return Err(ZenithError::Sink("max_rows must be positive".to_string()));
+        }
+        fs::create_dir_all(&config.dir)?;
+
+        Ok(Self {
+            config,
+            state: Mutex::new(SinkState {
+                schema: None,
+                pending: Vec::new(),
+                pending_rows: 0,
+                last_flush: Instant::now(),
+                files_written: 0,
+            }),
+            rows_written: AtomicU64::new(0),
+            rejected_events: AtomicU64::new(0),
+        })
+    }
+
+    /// Buffer an event's payload, rolling to a new file if a threshold is
+    /// reached. Header-only events are ignored. Fails without buffering
+    /// anything if the payload's schema differs from earlier events.
+    pub fn write(&self, event: &ZenithEvent) -> Result<()> {
+        let Some(batch) = event.payload.as_ref() else {
+            return Ok(());
+        };
+
+        let mut state = self.lock_state();
+        match &state.schema {
+            Some(schema) if schema != &batch.schema() => {
+                return Err(ZenithError::Sink(format!(
+                    "schema of seq_no {} differs from the sink's schema: expected {}, got {}",
+                    event.header.seq_no, schema, batch.schema()
+                )));
+            }
+            Some(_) => {}
+            None => state.schema = Some(batch.schema()),
+        }
+
+        state.pending_rows += batch.num_rows();
+        state.pending.push(batch.clone());
+        if state.pending_rows >= self.config.max_rows
+            || state.last_flush.elapsed() >= self.config.flush_interval
+        {
+            self.flush_locked(&mut state)?;
+        }
+        Ok(())
+    }
+
+    /// Write pending rows to a new file now. Returns the file, or `None`
+    /// if nothing was pending.
+    pub fn flush(&self) -> Result<Option<PathBuf>> {
+        let mut state = self.lock_state();
+        self.flush_locked(&mut state)
+    }
+
+    fn flush_locked(&self, state: &mut SinkState) -> Result<Option<PathBuf>> {
+        state.last_flush = Instant::now();
+        let (Some(schema), false) = (state.schema.clone(), state.pending.is_empty()) else {
+            return Ok(None);
+        };
+
+        let path = self.config.dir.join(format!("{}-{:06}.parquet", self.config.file_prefix, state.files_written));
+        // Written under a temporary name so readers never see a partial file
+        let tmp_path = path.with_extension("parquet.tmp");
+        write_parquet(&tmp_path, schema, &state.pending)?;
+        fs::rename(&tmp_path, &path)?;
+
+        self.rows_written.fetch_add(state.pending_rows as u64, Ordering::Relaxed);
+        state.files_written += 1;
+        state.pending.clear();
+        state.pending_rows = 0;
+        Ok(Some(path))
+    }
+
+    /// Rows flushed to disk so far
+    pub fn rows_written(&self) -> u64 {
+        self.rows_written.load(Ordering::Relaxed)
+    }
+
+    /// Rows buffered but not yet written
+    pub fn pending_rows(&self) -> usize {
+        self.lock_state().pending_rows
+    }
+
+    pub fn files_written(&self) -> usize {
+        self.lock_state().files_written
+    }
+
+    /// Events passed through `into_event_sink` that failed to persist
+    pub fn rejected_events(&self) -> u64 {
+        self.rejected_events.load(Ordering::Relaxed)
+    }
+
+    /// Adapt the sink for `ZenithEngine::set_sink`. The engine's sink can't
+    /// return errors, so failures are logged and counted in
+    /// `rejected_events`.
+    pub fn into_event_sink(self: Arc<Self>) -> EventSink {
+        Box::new(move |event| {
+            if let Err(e) = self.write(event) {
+                self.rejected_events.fetch_add(1, Ordering::Relaxed);
+                warn!("Parquet sink dropped seq_no {}: {}", event.header.seq_no, e);
+            }
+        })
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, SinkState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Drop for ParquetSink {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            error!("Parquet sink failed to flush on drop: {}", e);
+        }
+    }
+}
+
+fn write_parquet(path: &Path, schema: SchemaRef, batches: &[RecordBatch]) -> Result<()> {
+    let parquet_err = |e: parquet::errors::ParquetError| ZenithError::Sink(e.to_string());
+    let mut writer = ArrowWriter::try_new(File::create(path)?, schema, None).map_err(parquet_err)?;
+    for batch in batches {
+        writer.write(batch).map_err(parquet_err)?;
+    }
+    writer.close().map_err(parquet_err)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::ZenithEngine;
+    use crate::validation::ValidationError;
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("zenith-parquet-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn config(dir: &Path, max_rows: usize) -> ParquetSinkConfig {
+        ParquetSinkConfig {
+            dir: dir.to_path_buf(),
+            file_prefix: "events".to_string(),
+            max_rows,
+            flush_interval: Duration::from_secs(3600),
+        }
+    }
+
+    fn int_event(seq_no: u64, rows: i32) -> ZenithEvent {
+        let schema = Arc::new(Schema::new(vec![Field::new("value", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from_iter_values(0..rows))]).unwrap();
+        ZenithEvent::new(1, seq_no, batch)
+    }
+
+    fn read_rows(dir: &Path) -> usize {
+        let mut files: Vec<_> = fs::read_dir(dir).unwrap().map(|e| e.unwrap().path()).collect();
+        files.sort();
+        files.iter()
+            .map(|path| {
+                assert_eq!(path.extension().unwrap(), "parquet", "no temporary files left behind");
+                ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap()).unwrap()
+                    .build().unwrap()
+                    .map(|batch| batch.unwrap().num_rows())
+                    .sum::<usize>()
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_engine_events_persisted() {
+        let dir = test_dir("engine");
+        let sink = Arc::new(ParquetSink::new(config(&dir, 1000)).unwrap());
+
+        let engine = ZenithEngine::new(16).unwrap();
+        engine.set_filter("seq_no != 2").unwrap();
+        engine.set_sink(sink.clone().into_event_sink());
+        for seq_no in 1..=4 {
+            engine.get_ring_buffer().push(int_event(seq_no, 5)).unwrap();
+        }
+        while engine.process_one().is_some() {}
+
+        assert_eq!(sink.pending_rows(), 15);
+        assert!(sink.flush().unwrap().is_some());
+        assert_eq!(sink.rows_written(), 15);
+        assert_eq!(read_rows(&dir), 15);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rolls_by_row_count() {
+        let dir = test_dir("rolling");
+        let sink = ParquetSink::new(config(&dir, 10)).unwrap();
+
+        for seq_no in 0..5 {
+            sink.write(&int_event(seq_no, 4)).unwrap();
+        }
+        // Rolled once 12 rows were pending
+        assert_eq!(sink.files_written(), 1);
+        assert_eq!(sink.pending_rows(), 8);
+        sink.write(&int_event(5, 3)).unwrap();
+        assert_eq!(sink.files_written(), 2);
+        // Remainder is written on drop
+        sink.write(&int_event(6, 2)).unwrap();
+        drop(sink);
+
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 3);
+        assert_eq!(read_rows(&dir), 25);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rolls_by_interval() {
+        let dir = test_dir("interval");
+        let sink = ParquetSink::new(ParquetSinkConfig {
+            flush_interval: Duration::ZERO,
+            ..config(&dir, 1000)
+        }).unwrap();
+
+        sink.write(&int_event(1, 2)).unwrap();
+        assert_eq!(sink.files_written(), 1);
+        assert_eq!(sink.rows_written(), 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_schema_divergence_rejected() {
+        let dir = test_dir("schema");
+        let sink = Arc::new(ParquetSink::new(config(&dir, 1000)).unwrap());
+        sink.write(&int_event(1, 2)).unwrap();
+
+        let schema = Arc::new(Schema::new(vec![Field::new("value", DataType::Utf8, false)]));
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(StringArray::from(vec!["a"]))]).unwrap();
+        let other = ZenithEvent::new(1, 2, batch);
+        assert!(matches!(sink.write(&other), Err(ZenithError::Sink(_))));
+        assert_eq!(sink.pending_rows(), 2);
+
+        let event_sink = sink.clone().into_event_sink();
+        event_sink(&other);
+        assert_eq!(sink.rejected_events(), 1);
+        drop(event_sink);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        assert!(matches!(
+            ParquetSink::new(config(Path::new("/tmp/../etc"), 10)),
+            Err(ZenithError::Validation(_))
+        ));
+        let dir = test_dir("config");
+        assert!(ParquetSink::new(config(&dir, 0)).is_err());
+        let err = ParquetSink::new(ParquetSinkConfig {
+            file_prefix: "a/b".to_string(),
+            ..config(&dir, 10)
+        }).err().unwrap();
+        assert!(matches!(
+            err,
+            ZenithError::Validation(ValidationError::InvalidChars { ref field, .. }) if field == "file_prefix"
+        ));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
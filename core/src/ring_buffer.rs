@@ -1,33 +1,321 @@
 use crossbeam::queue::ArrayQueue;
-use std::sync::Arc;
+use dashmap::DashMap;
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
 use crate::error::{Result, ZenithError};
-use crate::event::ZenithEvent;
+use crate::event::{EventHeader, ZenithEvent};
 
+/// What `push` does when the buffer is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Fail with `ZenithError::BufferFull` (default)
+    #[default]
+    Reject,
+    /// Evict the oldest queued event to make room for the new one
+    DropOldest,
+    /// Silently discard the incoming event
+    DropNewest,
+}
+
+/// Bounded MPMC event queue.
+///
+/// `peek_header` is the one single-consumer operation: `ArrayQueue` can't
+/// peek, so peeking moves the next event into a front slot that `pop`
+/// drains first. With one consumer the event seen by a peek is the one the
+/// next pop returns; with several consumers another one may take it first.
 pub struct ZenithRingBuffer {
     queue: Arc<ArrayQueue<ZenithEvent>>,
+    policy: OverflowPolicy,
+    dropped: Arc<AtomicU64>,
+    /// Event taken off the queue by `peek_header` but not yet popped
+    front: Arc<Mutex<Option<ZenithEvent>>>,
+    /// Whether `front` is occupied, so pops skip the lock when it isn't
+    front_held: Arc<AtomicBool>,
+    /// Wakes producers blocked in `push_timeout` when a slot frees up
+    space: Arc<SpaceSignal>,
+    /// Events accepted per source_id; `None` unless enabled with
+    /// `with_source_counts`
+    source_counts: Option<Arc<DashMap<u32, u64>>>,
+}
+
+#[derive(Default)]
+struct SpaceSignal {
+    lock: Mutex<()>,
+    freed: Condvar,
+    /// Wakes `push_async` callers
+    freed_async: Notify,
+    /// Producers currently waiting, so consumers skip signalling when zero
+    waiters: AtomicUsize,
+}
+
+impl SpaceSignal {
+    fn notify(&self) {
+        // SeqCst pairs with the increment in `wait`: either the producer's
+        // retry sees the freed slot or we see the waiter
+        if self.waiters.load(Ordering::SeqCst) > 0 {
+            let _guard = self.lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            self.freed.notify_all();
+            self.freed_async.notify_waiters();
+        }
+    }
+
+    /// Count the caller as waiting until the returned guard is dropped
+    fn wait(&self) -> WaiterGuard<'_> {
+        self.waiters.fetch_add(1, Ordering::SeqCst);
+        WaiterGuard(&self.waiters)
+    }
+}
+
+/// Decrements the waiter count on drop, including when an async push is
+/// cancelled mid-wait
+struct WaiterGuard<'a>(&'a AtomicUsize);
+
+impl Drop for WaiterGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
 impl ZenithRingBuffer {
     pub fn new(capacity: usize) -> Self {
+        Self::with_policy(capacity, OverflowPolicy::Reject)
+    }
+
+    pub fn with_policy(capacity: usize, policy: OverflowPolicy) -> Self {
         Self {
             queue: Arc::new(ArrayQueue::new(capacity)),
+            policy,
+            dropped: Arc::new(AtomicU64::new(0)),
+            front: Arc::new(Mutex::new(None)),
+            front_held: Arc::new(AtomicBool::new(false)),
+            space: Arc::new(SpaceSignal::default()),
+            source_counts: None,
         }
     }
 
+    /// Count accepted events per source_id, for spotting skew between
+    /// producers. Clones made afterwards share the counts.
+    pub fn with_source_counts(mut self) -> Self {
+        self.source_counts = Some(Arc::new(DashMap::new()));
+        self
+    }
+
+    fn count_source(&self, source_id: u32) {
+        if let Some(counts) = &self.source_counts {
+            *counts.entry(source_id).or_insert(0) += 1;
+        }
+    }
+
+    /// Push an event, applying the configured `OverflowPolicy` when full.
+    ///
+    /// Only `Reject` ever returns an error; the drop policies count the
+    /// discarded event in `dropped_count` and return `Ok`.
     pub fn push(&self, event: ZenithEvent) -> Result<()> {
-        self.queue.push(event).map_err(|_| ZenithError::BufferFull)
+        let source_id = event.header.source_id;
+        match self.policy {
+            OverflowPolicy::Reject => {
+                self.queue.push(event).map_err(|_| ZenithError::BufferFull)?;
+                self.count_source(source_id);
+            }
+            OverflowPolicy::DropOldest => {
+                // force_push swaps out the oldest slot in a single atomic step
+                if self.queue.force_push(event).is_some() {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+                self.count_source(source_id);
+            }
+            OverflowPolicy::DropNewest => {
+                if self.queue.push(event).is_err() {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                } else {
+                    self.count_source(source_id);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Push each event independently, in order, returning one result per
+    /// event. A full buffer fails only the events that didn't fit, so the
+    /// caller can retry just those; the rest keep their relative order.
+    pub fn push_many(&self, events: Vec<ZenithEvent>) -> Vec<Result<()>> {
+        events.into_iter().map(|event| self.push(event)).collect()
+    }
+
+    /// Push an event, waiting up to `timeout` for space when the buffer is
+    /// full. Fails with `ZenithError::BufferFull` if none frees up in time.
+    ///
+    /// A zero timeout behaves exactly like `push`, as do the drop policies,
+    /// which never wait.
+    pub fn push_timeout(&self, event: ZenithEvent, timeout: Duration) -> Result<()> {
+        if self.policy != OverflowPolicy::Reject || timeout.is_zero() {
+            return self.push(event);
+        }
+        let source_id = event.header.source_id;
+        let mut event = match self.queue.push(event) {
+            Ok(()) => {
+                self.count_source(source_id);
+                return Ok(());
+            }
+            Err(event) => event,
+        };
+
+        let deadline = Instant::now() + timeout;
+        let _waiting = self.space.wait();
+        let mut guard = self.space.lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        loop {
+            // Retried under the lock so a pop can't slip in unnoticed
+            // between the failed push and the wait
+            match self.queue.push(event) {
+                Ok(()) => {
+                    self.count_source(source_id);
+                    return Ok(());
+                }
+                Err(rejected) => event = rejected,
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(ZenithError::BufferFull);
+            }
+            guard = self.space.freed.wait_timeout(guard, deadline - now)
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .0;
+        }
+    }
+
+    /// Push an event, waiting without blocking the thread until there is
+    /// space. Never fails under the `Reject` policy; the drop policies
+    /// behave like `push`.
+    ///
+    /// Cancellation-safe: dropping the future before it completes discards
+    /// the event without queueing it and leaves the buffer untouched.
+    pub async fn push_async(&self, event: ZenithEvent) -> Result<()> {
+        if self.policy != OverflowPolicy::Reject {
+            return self.push(event);
+        }
+        let source_id = event.header.source_id;
+        let mut event = match self.queue.push(event) {
+            Ok(()) => {
+                self.count_source(source_id);
+                return Ok(());
+            }
+            Err(event) => event,
+        };
+
+        let _waiting = self.space.wait();
+        loop {
+            // Registered before the retry so a pop in between still wakes us
+            let freed = self.space.freed_async.notified();
+            tokio::pin!(freed);
+            freed.as_mut().enable();
+            match self.queue.push(event) {
+                Ok(()) => {
+                    self.count_source(source_id);
+                    return Ok(());
+                }
+                Err(rejected) => event = rejected,
+            }
+            freed.await;
+        }
+    }
+
+    pub fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    /// Number of events discarded by the overflow policy so far
+    pub fn dropped_count(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Whether per-source counting was enabled with `with_source_counts`
+    pub fn tracks_sources(&self) -> bool {
+        self.source_counts.is_some()
+    }
+
+    /// Events accepted so far from each source_id, including any later
+    /// evicted by `DropOldest`. Empty when counting is disabled.
+    pub fn source_counts(&self) -> HashMap<u32, u64> {
+        self.source_counts.as_ref()
+            .map(|counts| counts.iter().map(|entry| (*entry.key(), *entry.value())).collect())
+            .unwrap_or_default()
     }
 
     pub fn pop(&self) -> Option<ZenithEvent> {
-        self.queue.pop()
+        self.take_front().or_else(|| self.pop_queue())
+    }
+
+    fn pop_queue(&self) -> Option<ZenithEvent> {
+        let event = self.queue.pop()?;
+        self.space.notify();
+        Some(event)
+    }
+
+    /// Copy of the next event's header, without removing the event.
+    ///
+    /// Single consumer only: the next `pop` from the same consumer returns
+    /// this event. The peeked event leaves the queue proper, so producers
+    /// may fill the freed slot and the buffer briefly holds `capacity + 1`
+    /// events; `DropOldest` never evicts a peeked event.
+    pub fn peek_header(&self) -> Option<EventHeader> {
+        self.peek_with(|event| event.header.clone())
+    }
+
+    /// Like `peek_header`, but hands `f` the whole event, e.g. to read its
+    /// row count. `f` runs with the front slot locked, so keep it short.
+    pub fn peek_with<R>(&self, f: impl FnOnce(&ZenithEvent) -> R) -> Option<R> {
+        let mut front = self.lock_front();
+        if front.is_none() {
+            *front = Some(self.pop_queue()?);
+            self.front_held.store(true, Ordering::Release);
+        }
+        front.as_ref().map(f)
+    }
+
+    fn lock_front(&self) -> MutexGuard<'_, Option<ZenithEvent>> {
+        self.front.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn take_front(&self) -> Option<ZenithEvent> {
+        if !self.front_held.load(Ordering::Acquire) {
+            return None;
+        }
+        let mut front = self.lock_front();
+        self.front_held.store(false, Ordering::Release);
+        front.take()
+    }
+
+    /// Pop up to `max` events in FIFO order, appending them to `out`.
+    ///
+    /// Returns the number of events popped. `out` is not cleared first, so
+    /// callers can reuse one allocation across calls.
+    pub fn pop_batch(&self, max: usize, out: &mut Vec<ZenithEvent>) -> usize {
+        let mut popped = 0;
+        while popped < max {
+            match self.pop() {
+                Some(event) => {
+                    out.push(event);
+                    popped += 1;
+                }
+                None => break,
+            }
+        }
+        popped
     }
 
     pub fn len(&self) -> usize {
-        self.queue.len()
+        self.queue.len() + self.front_held.load(Ordering::Acquire) as usize
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.queue.capacity()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.queue.is_empty()
+        self.len() == 0
     }
 }
 
@@ -35,6 +323,12 @@ impl Clone for ZenithRingBuffer {
     fn clone(&self) -> Self {
         Self {
             queue: self.queue.clone(),
+            policy: self.policy,
+            dropped: self.dropped.clone(),
+            front: self.front.clone(),
+            front_held: self.front_held.clone(),
+            space: self.space.clone(),
+            source_counts: self.source_counts.clone(),
         }
     }
 }
@@ -201,4 +495,269 @@ mod tests {
         let third = buffer.pop().unwrap();
         assert_eq!(third.header.seq_no, 300, "Third pop should have seq_no 300");
     }
+    
+    #[test]
+    fn test_ring_buffer_pop_batch() {
+        let buffer = ZenithRingBuffer::new(16);
+        for seq in 0..10 {
+            buffer.push(create_test_event(1, seq)).unwrap();
+        }
+        
+        let mut out = Vec::new();
+        let mut counts = Vec::new();
+        loop {
+            let popped = buffer.pop_batch(4, &mut out);
+            if popped == 0 {
+                break;
+            }
+            counts.push(popped);
+        }
+        
+        assert_eq!(counts, vec![4, 4, 2], "Should drain in batches of at most 4");
+        let seqs: Vec<u64> = out.iter().map(|e| e.header.seq_no).collect();
+        assert_eq!(seqs, (0..10).collect::<Vec<_>>(), "Batches should preserve FIFO order");
+        assert!(buffer.is_empty());
+    }
+    
+    #[test]
+    fn test_overflow_reject() {
+        let buffer = ZenithRingBuffer::with_policy(2, OverflowPolicy::Reject);
+        buffer.push(create_test_event(1, 1)).unwrap();
+        buffer.push(create_test_event(1, 2)).unwrap();
+        
+        let result = buffer.push(create_test_event(1, 3));
+        assert!(matches!(result, Err(ZenithError::BufferFull)));
+        assert_eq!(buffer.dropped_count(), 0, "Rejected pushes are not counted as drops");
+        assert_eq!(buffer.pop().unwrap().header.seq_no, 1);
+        assert_eq!(buffer.pop().unwrap().header.seq_no, 2);
+    }
+    
+    #[test]
+    fn test_push_many_reports_per_item() {
+        let buffer = ZenithRingBuffer::new(3);
+        buffer.push(create_test_event(1, 0)).unwrap();
+        
+        let results = buffer.push_many((1..=4).map(|seq| create_test_event(1, seq)).collect());
+        let accepted: Vec<bool> = results.iter().map(|r| r.is_ok()).collect();
+        assert_eq!(accepted, vec![true, true, false, false]);
+        assert!(matches!(results[2], Err(ZenithError::BufferFull)));
+        
+        // Space freed mid-way is used by later items, still in order
+        buffer.pop();
+        let results = buffer.push_many(vec![create_test_event(1, 3), create_test_event(1, 4)]);
+        assert!(results[0].is_ok() && results[1].is_err());
+        let seqs: Vec<u64> = std::iter::from_fn(|| buffer.pop()).map(|e| e.header.seq_no).collect();
+        assert_eq!(seqs, vec![1, 2, 3]);
+        
+        assert!(buffer.push_many(Vec::new()).is_empty());
+    }
+    
+    #[test]
+    fn test_push_timeout_waits_for_space() {
+        let buffer = ZenithRingBuffer::new(2);
+        buffer.push(create_test_event(1, 1)).unwrap();
+        buffer.push(create_test_event(1, 2)).unwrap();
+        
+        // Zero timeout doesn't wait; a short one gives up
+        assert!(matches!(buffer.push_timeout(create_test_event(1, 3), Duration::ZERO),
+            Err(ZenithError::BufferFull)));
+        let started = Instant::now();
+        assert!(matches!(buffer.push_timeout(create_test_event(1, 3), Duration::from_millis(20)),
+            Err(ZenithError::BufferFull)));
+        assert!(started.elapsed() >= Duration::from_millis(20));
+        
+        let consumer = buffer.clone();
+        let drain = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            consumer.pop().unwrap().header.seq_no
+        });
+        buffer.push_timeout(create_test_event(1, 3), Duration::from_secs(10)).unwrap();
+        assert_eq!(drain.join().unwrap(), 1);
+        
+        let seqs: Vec<u64> = std::iter::from_fn(|| buffer.pop()).map(|e| e.header.seq_no).collect();
+        assert_eq!(seqs, vec![2, 3]);
+    }
+    
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_push_async_with_draining_task() {
+        let buffer = ZenithRingBuffer::new(4);
+        let consumer = buffer.clone();
+        let drain = tokio::spawn(async move {
+            let mut seqs = Vec::new();
+            while seqs.len() < 100 {
+                match consumer.pop() {
+                    Some(event) => seqs.push(event.header.seq_no),
+                    None => tokio::time::sleep(Duration::from_millis(1)).await,
+                }
+            }
+            seqs
+        });
+        
+        let producers: Vec<_> = (0..4u64).map(|p| {
+            let buffer = buffer.clone();
+            tokio::spawn(async move {
+                for i in 0..25 {
+                    buffer.push_async(create_test_event(p as u32, p * 100 + i)).await.unwrap();
+                }
+            })
+        }).collect();
+        for producer in producers {
+            producer.await.unwrap();
+        }
+        
+        let mut seqs = drain.await.unwrap();
+        assert!(buffer.is_empty());
+        seqs.sort_unstable();
+        let expected: Vec<u64> = (0..4).flat_map(|p| (0..25).map(move |i| p * 100 + i)).collect();
+        assert_eq!(seqs, expected, "Every event should arrive exactly once");
+    }
+    
+    #[tokio::test]
+    async fn test_push_async_cancelled() {
+        let buffer = ZenithRingBuffer::new(1);
+        buffer.push(create_test_event(1, 1)).unwrap();
+        
+        let pending = tokio::time::timeout(Duration::from_millis(20), buffer.push_async(create_test_event(1, 2)));
+        assert!(pending.await.is_err(), "Push into a full buffer should still be waiting");
+        assert_eq!(buffer.space.waiters.load(Ordering::SeqCst), 0, "Cancelled push should deregister");
+        
+        assert_eq!(buffer.pop().unwrap().header.seq_no, 1);
+        assert!(buffer.is_empty(), "Cancelled event should not be queued");
+        buffer.push_async(create_test_event(1, 3)).await.unwrap();
+        assert_eq!(buffer.pop().unwrap().header.seq_no, 3);
+    }
+    
+    #[test]
+    fn test_push_timeout_ignores_drop_policies() {
+        let buffer = ZenithRingBuffer::with_policy(1, OverflowPolicy::DropNewest);
+        buffer.push(create_test_event(1, 1)).unwrap();
+        assert!(buffer.push_timeout(create_test_event(1, 2), Duration::from_secs(10)).is_ok());
+        assert_eq!(buffer.dropped_count(), 1);
+    }
+    
+    #[test]
+    fn test_overflow_drop_oldest() {
+        let buffer = ZenithRingBuffer::with_policy(2, OverflowPolicy::DropOldest);
+        for seq in 1..=4 {
+            assert!(buffer.push(create_test_event(1, seq)).is_ok());
+        }
+        
+        assert_eq!(buffer.len(), 2);
+        assert_eq!(buffer.dropped_count(), 2);
+        assert_eq!(buffer.pop().unwrap().header.seq_no, 3, "Oldest events should be evicted");
+        assert_eq!(buffer.pop().unwrap().header.seq_no, 4);
+    }
+    
+    #[test]
+    fn test_overflow_drop_newest() {
+        let buffer = ZenithRingBuffer::with_policy(2, OverflowPolicy::DropNewest);
+        for seq in 1..=4 {
+            assert!(buffer.push(create_test_event(1, seq)).is_ok());
+        }
+        
+        assert_eq!(buffer.len(), 2);
+        assert_eq!(buffer.dropped_count(), 2);
+        assert_eq!(buffer.pop().unwrap().header.seq_no, 1, "Queued events should be kept");
+        assert_eq!(buffer.pop().unwrap().header.seq_no, 2);
+    }
+    
+    #[test]
+    fn test_overflow_policy_shared_by_clones() {
+        let buffer = ZenithRingBuffer::with_policy(1, OverflowPolicy::DropNewest);
+        let cloned = buffer.clone();
+        assert_eq!(cloned.policy(), OverflowPolicy::DropNewest);
+        assert_eq!(ZenithRingBuffer::new(1).policy(), OverflowPolicy::Reject);
+        
+        buffer.push(create_test_event(1, 1)).unwrap();
+        cloned.push(create_test_event(1, 2)).unwrap();
+        assert_eq!(buffer.dropped_count(), 1, "Drop counter should be shared across clones");
+    }
+    
+    #[test]
+    fn test_ring_buffer_pop_batch_appends() {
+        let buffer = ZenithRingBuffer::new(4);
+        buffer.push(create_test_event(1, 7)).unwrap();
+        
+        let mut out = vec![create_test_event(1, 6)];
+        assert_eq!(buffer.pop_batch(4, &mut out), 1);
+        assert_eq!(out.len(), 2, "pop_batch should append, not overwrite");
+        assert_eq!(out[1].header.seq_no, 7);
+        
+        assert_eq!(buffer.pop_batch(0, &mut out), 0);
+    }
+    
+    #[test]
+    fn test_peek_header() {
+        let buffer = ZenithRingBuffer::new(4);
+        assert!(buffer.peek_header().is_none());
+        
+        buffer.push(create_test_event(1, 10)).unwrap();
+        buffer.push(create_test_event(2, 20)).unwrap();
+        
+        // Peeking twice returns the same event and removes nothing
+        let first = buffer.peek_header().unwrap();
+        let again = buffer.peek_header().unwrap();
+        assert_eq!((first.source_id, first.seq_no), (1, 10));
+        assert_eq!((again.source_id, again.seq_no), (1, 10));
+        assert_eq!(again.ingest_ns, first.ingest_ns);
+        assert_eq!(buffer.len(), 2);
+        
+        // Pop returns the peeked event, then continues in FIFO order
+        assert_eq!(buffer.pop().unwrap().header.seq_no, 10);
+        assert_eq!(buffer.peek_header().unwrap().seq_no, 20);
+        assert_eq!(buffer.pop().unwrap().header.seq_no, 20);
+        assert!(buffer.is_empty());
+        assert!(buffer.peek_header().is_none());
+    }
+    
+    #[test]
+    fn test_peek_then_pop_batch_and_clone() {
+        let buffer = ZenithRingBuffer::new(2);
+        buffer.push(create_test_event(1, 1)).unwrap();
+        buffer.push(create_test_event(1, 2)).unwrap();
+        
+        // Clones share the front slot
+        assert_eq!(buffer.clone().peek_header().unwrap().seq_no, 1);
+        
+        // The peeked event freed a queue slot
+        buffer.push(create_test_event(1, 3)).unwrap();
+        assert_eq!(buffer.len(), 3);
+        
+        let mut out = Vec::new();
+        assert_eq!(buffer.pop_batch(8, &mut out), 3);
+        let seqs: Vec<u64> = out.iter().map(|e| e.header.seq_no).collect();
+        assert_eq!(seqs, vec![1, 2, 3]);
+        assert!(buffer.is_empty());
+    }
+    
+    #[test]
+    fn test_source_counts() {
+        let buffer = ZenithRingBuffer::new(16).with_source_counts();
+        let clone = buffer.clone();
+        for seq in 0..5 {
+            buffer.push(create_test_event(1, seq)).unwrap();
+        }
+        for seq in 0..3 {
+            clone.push(create_test_event(2, seq)).unwrap();
+        }
+        buffer.push_timeout(create_test_event(3, 0), Duration::from_millis(10)).unwrap();
+        
+        // Pops don't decrement; the counts are of events pushed through
+        buffer.pop();
+        let counts = buffer.source_counts();
+        assert_eq!(counts, HashMap::from([(1, 5), (2, 3), (3, 1)]));
+        assert_eq!(clone.source_counts(), counts);
+        
+        // Rejected pushes aren't counted
+        let full = ZenithRingBuffer::new(1).with_source_counts();
+        full.push(create_test_event(4, 0)).unwrap();
+        assert!(full.push(create_test_event(4, 1)).is_err());
+        assert_eq!(full.source_counts(), HashMap::from([(4, 1)]));
+        
+        // Disabled by default
+        let plain = ZenithRingBuffer::new(4);
+        plain.push(create_test_event(1, 0)).unwrap();
+        assert!(!plain.tracks_sources());
+        assert!(plain.source_counts().is_empty());
+    }
 }
@@ -17,6 +17,10 @@ pub const MAX_COMMAND_LENGTH: usize = 65536;
 pub const MAX_ENV_VARS: usize = 1000;
 /// Maximum number of arguments
 pub const MAX_ARGUMENTS: usize = 1000;
+/// Maximum memory request in MB (8TB)
+pub const MAX_MEMORY_MB: u64 = 8 * 1024 * 1024;
+/// Maximum CPU cores per request
+pub const MAX_CPU_CORES: u32 = 1024;
 
 /// Validation error types
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -56,6 +60,33 @@ impl std::fmt::Display for ValidationError {
     }
 }
 
+impl ValidationError {
+    /// Stable machine-readable reason, for mapping to API error details.
+    /// Unlike the display text, these never change between releases.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Empty(_) => "EMPTY",
+            Self::TooLong { .. } => "TOO_LONG",
+            Self::InvalidChars { .. } => "INVALID_CHARS",
+            Self::ForbiddenPattern { .. } => "FORBIDDEN_PATTERN",
+            Self::OutOfRange { .. } => "OUT_OF_RANGE",
+            Self::Invalid(_) => "INVALID",
+        }
+    }
+
+    /// Name of the offending field, if the error is tied to one
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            Self::Empty(field)
+            | Self::TooLong { field, .. }
+            | Self::InvalidChars { field, .. }
+            | Self::ForbiddenPattern { field, .. }
+            | Self::OutOfRange { field, .. } => Some(field),
+            Self::Invalid(_) => None,
+        }
+    }
+}
+
 impl std::error::Error for ValidationError {}
 
 /// Result type for validation operations
@@ -65,6 +96,8 @@ pub type ValidationResult<T> = Result<T, ValidationError>;
 pub struct Validator {
     /// Forbidden command patterns (for security)
     forbidden_patterns: HashSet<String>,
+    /// Restrict job names to ASCII, ruling out lookalike letters
+    ascii_only_names: bool,
 }
 
 impl Default for Validator {
@@ -82,16 +115,29 @@ impl Default for Validator {
         forbidden_patterns.insert("<".to_string());
         forbidden_patterns.insert("..".to_string());  // Path traversal
         
-        Self { forbidden_patterns }
+        Self { forbidden_patterns, ascii_only_names: false }
     }
 }
 
+/// Bidirectional embedding, override and isolate controls, which can make
+/// text display in a different order than it is processed
+fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
 impl Validator {
     /// Create a new validator with default rules
     pub fn new() -> Self {
         Self::default()
     }
     
+    /// Only accept ASCII letters and digits in job names, so names can't
+    /// use Unicode letters that look like ASCII ones (e.g. Cyrillic "а")
+    pub fn ascii_only_names(mut self, enabled: bool) -> Self {
+        self.ascii_only_names = enabled;
+        self
+    }
+    
     /// Validate a string is not empty
     pub fn require_non_empty(&self, field: &str, value: &str) -> ValidationResult<()> {
         if value.trim().is_empty() {
@@ -116,16 +162,32 @@ impl Validator {
     
     /// Validate a job name (alphanumeric, dashes, underscores)
     pub fn validate_job_name(&self, name: &str) -> ValidationResult<()> {
-        self.require_non_empty("job_name", name)?;
-        self.validate_length("job_name", name, MAX_JOB_NAME_LENGTH)?;
+        self.validate_name("job_name", name)
+    }
+    
+    /// Validate a name-like `field` by the same rules as a job name
+    pub fn validate_name(&self, field: &str, name: &str) -> ValidationResult<()> {
+        self.require_non_empty(field, name)?;
+        self.validate_length(field, name, MAX_JOB_NAME_LENGTH)?;
+        
+        // Rejected in every mode, and reported as such
+        if let Some(c) = name.chars().find(|c| is_bidi_control(*c)) {
+            return Err(ValidationError::ForbiddenPattern {
+                field: field.to_string(),
+                pattern: format!("bidi control U+{:04X}", c as u32),
+            });
+        }
         
         let invalid: String = name.chars()
-            .filter(|c| !c.is_alphanumeric() && *c != '-' && *c != '_')
+            .filter(|c| {
+                let allowed = if self.ascii_only_names { c.is_ascii_alphanumeric() } else { c.is_alphanumeric() };
+                !allowed && *c != '-' && *c != '_'
+            })
             .collect();
         
         if !invalid.is_empty() {
             Err(ValidationError::InvalidChars {
-                field: "job_name".to_string(),
+                field: field.to_string(),
                 invalid,
             })
         } else {
@@ -173,6 +235,34 @@ impl Validator {
         Ok(())
     }
     
+    /// Validate exec arguments (count, length, no null bytes).
+    ///
+    /// Arguments go straight to exec rather than through a shell, so shell
+    /// metacharacters are legitimate here and not rejected.
+    pub fn validate_arguments(&self, args: &[String]) -> ValidationResult<()> {
+        if args.len() > MAX_ARGUMENTS {
+            return Err(ValidationError::TooLong {
+                field: "arguments".to_string(),
+                max: MAX_ARGUMENTS,
+                actual: args.len(),
+            });
+        }
+        
+        for (i, arg) in args.iter().enumerate() {
+            let field = format!("arguments[{}]", i);
+            self.validate_length(&field, arg, MAX_STRING_LENGTH)?;
+            
+            if arg.contains('\0') {
+                return Err(ValidationError::InvalidChars {
+                    field,
+                    invalid: "null byte".to_string(),
+                });
+            }
+        }
+        
+        Ok(())
+    }
+    
     /// Validate a numeric value is in range
     pub fn validate_range(&self, field: &str, value: i64, min: i64, max: i64) -> ValidationResult<()> {
         if value < min || value > max {
@@ -197,6 +287,31 @@ impl Validator {
         self.validate_range("priority", priority as i64, -1000, 1000)
     }
     
+    /// Validate a memory request in MB (non-zero, at most 8TB)
+    pub fn validate_memory_mb(&self, mb: u64) -> ValidationResult<()> {
+        self.memory_mb_to_bytes(mb).map(|_| ())
+    }
+    
+    /// Validate a memory request in MB and convert it to bytes
+    pub fn memory_mb_to_bytes(&self, mb: u64) -> ValidationResult<u64> {
+        if mb == 0 || mb > MAX_MEMORY_MB {
+            return Err(ValidationError::OutOfRange {
+                field: "memory_mb".to_string(),
+                min: 1,
+                max: MAX_MEMORY_MB as i64,
+                actual: i64::try_from(mb).unwrap_or(i64::MAX),
+            });
+        }
+        
+        mb.checked_mul(1024 * 1024)
+            .ok_or_else(|| ValidationError::Invalid(format!("memory_mb {} overflows bytes", mb)))
+    }
+    
+    /// Validate CPU core count
+    pub fn validate_cpu_cores(&self, cores: u32) -> ValidationResult<()> {
+        self.validate_range("cpu_cores", cores as i64, 1, MAX_CPU_CORES as i64)
+    }
+    
     /// Validate buffer size
     pub fn validate_buffer_size(&self, size: usize) -> ValidationResult<()> {
         self.validate_range("buffer_size", size as i64, 1, 1024 * 1024 * 1024)  // 1GB max
@@ -214,7 +329,12 @@ pub fn sanitize_string(input: &str) -> String {
 pub fn sanitize_log_message(message: &str) -> String {
     let sanitized = sanitize_string(message);
     if sanitized.len() > MAX_STRING_LENGTH {
-        format!("{}... [truncated]", &sanitized[..MAX_STRING_LENGTH])
+        // Back off to a char boundary; slicing mid-character panics
+        let mut end = MAX_STRING_LENGTH;
+        while !sanitized.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}... [truncated]", &sanitized[..end])
     } else {
         sanitized
     }
@@ -235,6 +355,36 @@ mod tests {
         assert!(v.validate_job_name("job;rm -rf").is_err());  // injection attempt
     }
     
+    #[test]
+    fn test_job_name_homoglyphs() {
+        // Cyrillic "а" and "е" in place of their ASCII lookalikes
+        let spoofed = "tr\u{0430}in-bas\u{0435}";
+        assert!(Validator::new().validate_job_name(spoofed).is_ok());
+        
+        let strict = Validator::new().ascii_only_names(true);
+        assert!(strict.validate_job_name("train-base_2").is_ok());
+        assert_eq!(strict.validate_job_name(spoofed), Err(ValidationError::InvalidChars {
+            field: "job_name".to_string(),
+            invalid: "\u{0430}\u{0435}".to_string(),
+        }));
+    }
+    
+    #[test]
+    fn test_job_name_bidi_controls() {
+        // "job-<RLO>txt.exe" displays as "job-exe.txt"
+        for name in ["job-\u{202E}txt.exe", "a\u{202A}b", "a\u{2066}b", "a\u{2069}"] {
+            for v in [Validator::new(), Validator::new().ascii_only_names(true)] {
+                let err = v.validate_job_name(name).unwrap_err();
+                assert_eq!(err.code(), "FORBIDDEN_PATTERN", "{:?}", name);
+                assert!(err.to_string().contains("bidi control"));
+            }
+        }
+        assert_eq!(
+            Validator::new().validate_job_name("x\u{202E}").unwrap_err().to_string(),
+            "job_name contains forbidden pattern: bidi control U+202E"
+        );
+    }
+    
     #[test]
     fn test_validate_path() {
         let v = Validator::new();
@@ -263,6 +413,42 @@ mod tests {
         assert!(v.validate_command("echo > /etc/passwd").is_err());
     }
     
+    #[test]
+    fn test_validate_arguments() {
+        let v = Validator::new();
+        
+        assert!(v.validate_arguments(&[]).is_ok());
+        // Shell metacharacters are fine, args are not interpreted by a shell
+        let args = vec!["train.py".to_string(), "--filter=a|b;c".to_string()];
+        assert!(v.validate_arguments(&args).is_ok());
+        
+        let too_many = vec!["x".to_string(); MAX_ARGUMENTS + 1];
+        assert_eq!(
+            v.validate_arguments(&too_many),
+            Err(ValidationError::TooLong {
+                field: "arguments".to_string(),
+                max: MAX_ARGUMENTS,
+                actual: MAX_ARGUMENTS + 1,
+            })
+        );
+        assert!(v.validate_arguments(&too_many[..MAX_ARGUMENTS]).is_ok());
+        
+        let null_byte = vec!["ok".to_string(), "bad\0arg".to_string()];
+        assert_eq!(
+            v.validate_arguments(&null_byte),
+            Err(ValidationError::InvalidChars {
+                field: "arguments[1]".to_string(),
+                invalid: "null byte".to_string(),
+            })
+        );
+        
+        let too_long = vec!["a".repeat(MAX_STRING_LENGTH + 1)];
+        assert!(matches!(
+            v.validate_arguments(&too_long),
+            Err(ValidationError::TooLong { max: MAX_STRING_LENGTH, .. })
+        ));
+    }
+    
     #[test]
     fn test_validate_range() {
         let v = Validator::new();
@@ -405,6 +591,21 @@ mod tests {
             "At max should not truncate - catches > to < mutation");
     }
     
+    #[test]
+    fn test_sanitize_log_message_multibyte_truncation() {
+        // 3-byte CJK and 4-byte emoji, offset so the limit falls mid-character
+        for (prefix, unit) in [("", "日本"), ("a", "🦀")] {
+            let message = format!("{}{}", prefix, unit.repeat(MAX_STRING_LENGTH));
+            assert!(!message.is_char_boundary(MAX_STRING_LENGTH));
+            
+            let truncated = sanitize_log_message(&message);
+            let kept = truncated.strip_suffix("... [truncated]").expect("should be truncated");
+            assert!(kept.len() <= MAX_STRING_LENGTH);
+            assert!(MAX_STRING_LENGTH - kept.len() < 4, "should keep as much as fits");
+            assert!(message.starts_with(kept));
+        }
+    }
+    
     #[test]
     fn test_sanitize_log_message_returns_string() {
         // Catches mutation: replace with String::new() or "xyzzy".into()
@@ -520,6 +721,39 @@ mod tests {
         assert!(v.validate_priority(1001).is_err());
     }
     
+    #[test]
+    fn test_validate_memory_mb() {
+        let v = Validator::new();
+        
+        assert!(matches!(
+            v.validate_memory_mb(0),
+            Err(ValidationError::OutOfRange { actual: 0, .. })
+        ));
+        
+        // Would overflow u64 once converted to bytes
+        assert!(matches!(
+            v.validate_memory_mb(u64::MAX),
+            Err(ValidationError::OutOfRange { actual: i64::MAX, .. })
+        ));
+        assert!(v.validate_memory_mb(MAX_MEMORY_MB + 1).is_err());
+        
+        assert!(v.validate_memory_mb(1).is_ok());
+        assert!(v.validate_memory_mb(MAX_MEMORY_MB).is_ok());
+        assert_eq!(v.memory_mb_to_bytes(16384), Ok(16384 * 1024 * 1024));
+        assert_eq!(v.memory_mb_to_bytes(MAX_MEMORY_MB), Ok(8 * 1024 * 1024 * 1024 * 1024));
+    }
+    
+    #[test]
+    fn test_validate_cpu_cores() {
+        let v = Validator::new();
+        
+        assert!(v.validate_cpu_cores(0).is_err());
+        assert!(v.validate_cpu_cores(1).is_ok());
+        assert!(v.validate_cpu_cores(MAX_CPU_CORES).is_ok());
+        assert!(v.validate_cpu_cores(MAX_CPU_CORES + 1).is_err());
+        assert!(v.validate_cpu_cores(u32::MAX).is_err());
+    }
+    
     #[test]
     fn test_sanitize_string_control_chars() {
         // Test various control characters are removed
@@ -537,4 +771,34 @@ mod tests {
         assert_eq!(sanitize_string("hello world"), "hello world");
         assert_eq!(sanitize_string(""), "");
     }
+    
+    #[test]
+    fn test_error_codes() {
+        let cases = [
+            (ValidationError::Empty("name".to_string()), "EMPTY"),
+            (ValidationError::TooLong { field: "name".to_string(), max: 1, actual: 2 }, "TOO_LONG"),
+            (ValidationError::InvalidChars { field: "name".to_string(), invalid: " ".to_string() }, "INVALID_CHARS"),
+            (ValidationError::ForbiddenPattern { field: "name".to_string(), pattern: ";".to_string() }, "FORBIDDEN_PATTERN"),
+            (ValidationError::OutOfRange { field: "name".to_string(), min: 0, max: 1, actual: 2 }, "OUT_OF_RANGE"),
+            (ValidationError::Invalid("bad".to_string()), "INVALID"),
+        ];
+        for (err, code) in cases {
+            assert_eq!(err.code(), code, "{:?}", err);
+        }
+    }
+    
+    #[test]
+    fn test_error_field() {
+        let v = Validator::new();
+        
+        let err = v.validate_job_name("").unwrap_err();
+        assert_eq!((err.code(), err.field()), ("EMPTY", Some("job_name")));
+        let err = v.validate_job_name("job;rm").unwrap_err();
+        assert_eq!(err.field(), Some("job_name"));
+        let err = v.validate_path("../etc").unwrap_err();
+        assert_eq!((err.code(), err.field()), ("FORBIDDEN_PATTERN", Some("path")));
+        let err = v.validate_range("priority", 11, 0, 10).unwrap_err();
+        assert_eq!((err.code(), err.field()), ("OUT_OF_RANGE", Some("priority")));
+        assert_eq!(ValidationError::Invalid("bad".to_string()).field(), None);
+    }
 }
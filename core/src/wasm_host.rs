@@ -1,14 +1,201 @@
 // WasmHost implementation
-use wasmtime::{Engine, Linker, Module, Store, Config};
+use wasmtime::{CallHook, Engine, ExternType, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Config, Trap};
 use wasmtime_wasi::WasiCtxBuilder;
 use wasmtime_wasi::p1::{self, WasiP1Ctx};
-use crate::error::Result;
-use std::sync::{Arc, Mutex};
+use crate::capabilities::{Capabilities, Capability, CapabilityDenied};
+use crate::circuit_breaker::CircuitBreaker;
+use crate::error::{Result, ZenithError};
+use crate::validation::ValidationError;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread;
+use std::time::Duration;
+
+/// Default fuel budget for a single plugin invocation. Fuel is consumed
+/// roughly one unit per WASM instruction, so this bounds how long a
+/// plugin can run before being interrupted.
+pub const DEFAULT_FUEL_BUDGET: u64 = 10_000_000;
+
+/// Largest per-plugin linear memory `SandboxLimits` accepts (the wasm32 maximum)
+pub const MAX_PLUGIN_MEMORY_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+/// Longest per-invocation timeout `SandboxLimits` accepts
+pub const MAX_PLUGIN_CPU_TIMEOUT: Duration = Duration::from_secs(60);
+/// Most host calls per invocation `SandboxLimits` accepts
+pub const MAX_PLUGIN_HOST_CALLS: u32 = 1_000_000;
+
+/// Functions a module must export to be loaded as a plugin
+pub const REQUIRED_EXPORTS: [&str; 1] = ["on_event"];
+/// Functions of the plugin ABI a module may leave out; a
+/// `MissingExports` report lists the absent ones alongside the required
+pub const OPTIONAL_EXPORTS: [&str; 2] = ["init", "version"];
+
+/// WASI calls a plugin may only import when its manifest grants the
+/// matching capability; ungranted ones trap with `CapabilityDenied`
+const GATED_WASI_CALLS: [(&str, Capability); 4] = [
+    ("clock_time_get", Capability::Clock),
+    ("clock_res_get", Capability::Clock),
+    ("poll_oneoff", Capability::Clock),
+    ("random_get", Capability::Random),
+];
+
+/// Granularity of the epoch clock that enforces `cpu_timeout`
+const EPOCH_TICK: Duration = Duration::from_millis(1);
+/// Epoch deadline for plugins without a timeout; never reached in practice
+const NO_EPOCH_DEADLINE: u64 = u64::MAX / 2;
 
 /// Type alias for WASI state in wasmtime v39+
-/// WasiP1Ctx is now used directly as the store state
+/// WasiP1Ctx is held in each plugin's `PluginState`
 pub type WasiState = WasiP1Ctx;
 
+/// Per-plugin resource limits, applied on top of the fuel budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SandboxLimits {
+    /// Maximum size of each linear memory (bytes)
+    pub max_memory_bytes: u64,
+    /// Wall-clock limit per invocation
+    pub cpu_timeout: Duration,
+    /// Maximum host (WASI) calls per invocation
+    pub max_host_calls: u32,
+}
+
+impl SandboxLimits {
+    /// Check every limit is positive and within its ceiling
+    pub fn validate(&self) -> std::result::Result<(), ValidationError> {
+        let out_of_range = |field: &str, max: u64, actual: u64| ValidationError::OutOfRange {
+            field: field.to_string(),
+            min: 1,
+            max: max as i64,
+            actual: i64::try_from(actual).unwrap_or(i64::MAX),
+        };
+
+        if self.max_memory_bytes == 0 || self.max_memory_bytes > MAX_PLUGIN_MEMORY_BYTES {
+            return Err(out_of_range("max_memory_bytes", MAX_PLUGIN_MEMORY_BYTES, self.max_memory_bytes));
+        }
+        let timeout_ms = u64::try_from(self.cpu_timeout.as_millis()).unwrap_or(u64::MAX);
+        let max_timeout_ms = MAX_PLUGIN_CPU_TIMEOUT.as_millis() as u64;
+        if timeout_ms == 0 || timeout_ms > max_timeout_ms {
+            return Err(out_of_range("cpu_timeout_ms", max_timeout_ms, timeout_ms));
+        }
+        if self.max_host_calls == 0 || self.max_host_calls > MAX_PLUGIN_HOST_CALLS {
+            return Err(out_of_range("max_host_calls", MAX_PLUGIN_HOST_CALLS as u64, self.max_host_calls as u64));
+        }
+        Ok(())
+    }
+
+    /// Epoch ticks covering `cpu_timeout`, plus one for the tick in progress
+    fn timeout_ticks(&self) -> u64 {
+        let ticks = self.cpu_timeout.as_nanos().div_ceil(EPOCH_TICK.as_nanos());
+        u64::try_from(ticks).unwrap_or(NO_EPOCH_DEADLINE).saturating_add(1)
+    }
+}
+
+/// Why a module was refused at load, carried by
+/// `ZenithError::PluginRejected`. Serializes with a `kind` tag, e.g.
+/// `{"kind": "missing_exports", "missing": ["on_event"], "missing_optional": ["version"]}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PluginLoadReport {
+    /// Not a well-formed WebAssembly module
+    Parse {
+        /// Byte offset at which wasmparser gave up
+        offset: usize,
+        message: String,
+    },
+    /// Some of `REQUIRED_EXPORTS` aren't exported as functions
+    MissingExports {
+        missing: Vec<&'static str>,
+        /// Absent `OPTIONAL_EXPORTS`; these alone never refuse a module
+        missing_optional: Vec<&'static str>,
+    },
+    /// Well-formed, but the sandbox won't run it: a bad capability
+    /// manifest, imports the host doesn't provide, a start function that
+    /// fails, or features the engine doesn't enable
+    Rejected { reason: String },
+}
+
+impl PluginLoadReport {
+    fn rejected(reason: impl fmt::Display) -> Self {
+        Self::Rejected { reason: reason.to_string() }
+    }
+}
+
+impl fmt::Display for PluginLoadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse { offset, message } => write!(f, "malformed module at offset {:#x}: {}", offset, message),
+            Self::MissingExports { missing, missing_optional } => {
+                write!(f, "missing required exports: {}", missing.join(", "))?;
+                if !missing_optional.is_empty() {
+                    write!(f, " (optional, also absent: {})", missing_optional.join(", "))?;
+                }
+                Ok(())
+            }
+            Self::Rejected { reason } => write!(f, "rejected by sandbox policy: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for PluginLoadReport {}
+
+/// Store data for a plugin: WASI context plus limit bookkeeping
+pub struct PluginState {
+    wasi: WasiP1Ctx,
+    limits: StoreLimits,
+    host_calls: u32,
+    max_host_calls: u32,
+}
+
+/// Returned from the call hook when a plugin makes too many host calls
+#[derive(Debug)]
+struct HostCallLimitExceeded;
+
+impl std::fmt::Display for HostCallLimitExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "plugin exceeded its host call limit")
+    }
+}
+
+impl std::error::Error for HostCallLimitExceeded {}
+
+/// Snapshot of the compiled-module cache counters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ModuleCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Distinct modules currently cached
+    pub entries: usize,
+}
+
+/// Advances the engine epoch every `EPOCH_TICK` until dropped
+struct EpochTicker {
+    stop: Arc<AtomicBool>,
+}
+
+impl EpochTicker {
+    fn start(engine: Engine) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = stop.clone();
+        thread::spawn(move || {
+            while !stopped.load(Ordering::Relaxed) {
+                thread::sleep(EPOCH_TICK);
+                engine.increment_epoch();
+            }
+        });
+        Self { stop }
+    }
+}
+
+impl Drop for EpochTicker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
 /// Helper function to create a new WASI context
 pub fn new_wasi_ctx() -> WasiP1Ctx {
     WasiCtxBuilder::new()
@@ -17,64 +204,367 @@ pub fn new_wasi_ctx() -> WasiP1Ctx {
 }
 
 pub struct WasmPlugin {
-    store: Arc<Mutex<Store<WasiState>>>,
+    store: Arc<Mutex<Store<PluginState>>>,
     instance: wasmtime::Instance,
+    fuel_budget: AtomicU64,
+    disabled: AtomicBool,
+    /// Trips when `on_event` keeps failing; consulted by the engine
+    breaker: CircuitBreaker,
+    /// Epoch ticks allowed per invocation
+    epoch_ticks: u64,
+    limits: Option<SandboxLimits>,
+    /// Granted by the module's manifest
+    capabilities: Capabilities,
 }
 
 pub struct WasmHost {
     engine: Engine,
-    linker: Linker<WasiState>,
+    linker: Linker<PluginState>,
+    /// Started by the first plugin loaded with a `cpu_timeout`
+    ticker: OnceLock<EpochTicker>,
+    /// Compiled modules keyed by the SHA-256 of their bytes. `Module` is
+    /// reference counted, so a hit hands out the same compiled code.
+    module_cache: Mutex<HashMap<[u8; 32], Module>>,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+}
+
+/// Engine settings shared by `WasmHost` and `WasmHost::precompile`; a
+/// precompiled artifact only loads into an engine configured the same way.
+fn engine_config() -> Config {
+    let mut config = Config::new();
+    // Fuel metering lets us bound each plugin invocation
+    config.consume_fuel(true);
+    // Epochs enforce per-plugin wall-clock timeouts
+    config.epoch_interruption(true);
+    config
 }
 
 impl WasmHost {
     pub fn new() -> Result<Self> {
-        let config = Config::new();
-        
-        let engine = Engine::new(&config)?;
+        let engine = Engine::new(&engine_config())?;
         let mut linker = Linker::new(&engine);
         
         // wasmtime v39+ uses p1 module for WASIp1 (preview1) compatibility
-        p1::add_to_linker_sync(&mut linker, |s: &mut WasiState| s)?;
+        p1::add_to_linker_sync(&mut linker, |s: &mut PluginState| &mut s.wasi)?;
 
         Ok(Self {
             engine,
             linker,
+            ticker: OnceLock::new(),
+            module_cache: Mutex::new(HashMap::new()),
+            cache_hits: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
         })
     }
 
+    pub fn module_cache_stats(&self) -> ModuleCacheStats {
+        ModuleCacheStats {
+            hits: self.cache_hits.load(Ordering::Relaxed),
+            misses: self.cache_misses.load(Ordering::Relaxed),
+            entries: self.module_cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).len(),
+        }
+    }
+
+    /// Compile `wasm_bytes`, or reuse the module compiled from identical
+    /// bytes earlier. Modules that fail to compile are not cached.
+    fn compile(&self, wasm_bytes: &[u8]) -> Result<Module> {
+        let key: [u8; 32] = Sha256::digest(wasm_bytes).into();
+        if let Some(module) = self.module_cache.lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(&key)
+        {
+            self.cache_hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(module.clone());
+        }
+        
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
+        // Compile without holding the lock; a concurrent miss on the same
+        // bytes just compiles twice
+        let module = Module::new(&self.engine, wasm_bytes)
+            .map_err(|e| compile_error(wasm_bytes, e))?;
+        self.module_cache.lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(key, module.clone());
+        Ok(module)
+    }
 
+    /// Compile and instantiate a plugin, granting the capabilities its
+    /// manifest declares (see `crate::capabilities`). A refused module
+    /// fails with `ZenithError::PluginRejected` saying why.
     pub fn load_plugin(&self, wasm_bytes: &[u8]) -> Result<WasmPlugin> {
-        let wasi_state = new_wasi_ctx();
+        let module = self.compile(wasm_bytes)?;
+        self.instantiate(&module, None, manifest(wasm_bytes)?)
+    }
+
+    /// Compile `wasm` ahead of time into an artifact for `load_precompiled`.
+    /// The artifact is tied to this wasmtime version and the host CPU.
+    pub fn precompile(wasm: &[u8]) -> Result<Vec<u8>> {
+        let engine = Engine::new(&engine_config())?;
+        engine.precompile_module(wasm)
+            .map_err(|e| ZenithError::PluginLoad(e.to_string()))
+    }
+
+    /// Load an artifact produced by `precompile`, skipping compilation.
+    /// Artifacts don't keep the manifest, so the plugin gets no
+    /// capabilities.
+    ///
+    /// Artifacts are native code, so only load ones produced by a trusted
+    /// build: wasmtime checks the version, target and engine settings, but
+    /// not that the code itself is safe.
+    pub fn load_precompiled(&self, artifact: &[u8]) -> Result<WasmPlugin> {
+        if Engine::detect_precompiled(artifact).is_none() {
+            return Err(ZenithError::IncompatibleArtifact(
+                "not a precompiled wasmtime module".to_string()));
+        }
+        // SAFETY: the bytes are a wasmtime artifact (checked above) and the
+        // caller vouches for their origin; wasmtime rejects artifacts built
+        // for another version, CPU or engine configuration.
+        let module = unsafe { Module::deserialize(&self.engine, artifact) }
+            .map_err(|e| ZenithError::IncompatibleArtifact(format!("{:#}", e)))?;
+        self.instantiate(&module, None, Capabilities::NONE)
+    }
+
+    /// Load a plugin confined to `limits`. Exceeding the timeout or host
+    /// call limit fails the invocation with `ZenithError::BudgetExceeded`;
+    /// `memory.grow` past the memory limit returns -1 to the plugin.
+    pub fn load_plugin_with_limits(&self, wasm_bytes: &[u8], limits: SandboxLimits) -> Result<WasmPlugin> {
+        limits.validate()?;
+        self.ticker.get_or_init(|| EpochTicker::start(self.engine.clone()));
+        let module = self.compile(wasm_bytes)?;
+        self.instantiate(&module, Some(limits), manifest(wasm_bytes)?)
+    }
+
+    /// The shared linker, or a copy of it whose WASI calls not granted by
+    /// `capabilities` are shadowed by stubs that trap when called
+    fn linker_for(&self, module: &Module, capabilities: Capabilities) -> Result<Cow<'_, Linker<PluginState>>> {
+        let denied: Vec<_> = module.imports()
+            .filter(|import| import.module() == "wasi_snapshot_preview1")
+            .filter_map(|import| {
+                let (_, cap) = GATED_WASI_CALLS.iter().find(|(name, _)| *name == import.name())?;
+                match import.ty() {
+                    ExternType::Func(ty) if !capabilities.contains(*cap) => Some((import.name(), ty, *cap)),
+                    _ => None,
+                }
+            })
+            .collect();
+        if denied.is_empty() {
+            return Ok(Cow::Borrowed(&self.linker));
+        }
+
+        let mut linker = self.linker.clone();
+        linker.allow_shadowing(true);
+        for (name, ty, cap) in denied {
+            linker.func_new("wasi_snapshot_preview1", name, ty, move |_, _, _| Err(CapabilityDenied(cap).into()))?;
+        }
+        Ok(Cow::Owned(linker))
+    }
+
+    fn instantiate(&self, module: &Module, limits: Option<SandboxLimits>, capabilities: Capabilities) -> Result<WasmPlugin> {
+        let absent = |names: &[&'static str]| -> Vec<&'static str> {
+            names.iter().copied()
+                .filter(|name| !matches!(module.get_export(name), Some(ExternType::Func(_))))
+                .collect()
+        };
+        let missing = absent(&REQUIRED_EXPORTS);
+        if !missing.is_empty() {
+            let missing_optional = absent(&OPTIONAL_EXPORTS);
+            return Err(PluginLoadReport::MissingExports { missing, missing_optional }.into());
+        }
+
+        let store_limits = match limits {
+            Some(limits) => StoreLimitsBuilder::new()
+                .memory_size(usize::try_from(limits.max_memory_bytes).unwrap_or(usize::MAX))
+                .build(),
+            None => StoreLimits::default(),
+        };
+        let state = PluginState {
+            wasi: new_wasi_ctx(),
+            limits: store_limits,
+            host_calls: 0,
+            max_host_calls: limits.map_or(u32::MAX, |l| l.max_host_calls),
+        };
+        let epoch_ticks = limits.map_or(NO_EPOCH_DEADLINE, |l| l.timeout_ticks());
         
-        let mut store = Store::new(&self.engine, wasi_state);
-        let module = Module::new(&self.engine, wasm_bytes)?;
-        let instance = self.linker.instantiate(&mut store, &module)?;
+        let mut store = Store::new(&self.engine, state);
+        store.limiter(|s| &mut s.limits);
+        store.call_hook(|mut ctx, hook| {
+            if matches!(hook, CallHook::CallingHost) {
+                let state = ctx.data_mut();
+                state.host_calls = state.host_calls.saturating_add(1);
+                if state.host_calls > state.max_host_calls {
+                    return Err(HostCallLimitExceeded.into());
+                }
+            }
+            Ok(())
+        });
+        // Start functions run during instantiation and need fuel too
+        store.set_fuel(DEFAULT_FUEL_BUDGET)?;
+        store.set_epoch_deadline(epoch_ticks);
+        // The module compiled, so a failure here means it was rejected
+        // (unresolved imports, trapping start function, ...)
+        let instance = self.linker_for(module, capabilities)?.instantiate(&mut store, module)
+            .map_err(|e| PluginLoadReport::rejected(format_args!("{:#}", e)))?;
 
         Ok(WasmPlugin {
             store: Arc::new(Mutex::new(store)),
             instance,
+            fuel_budget: AtomicU64::new(DEFAULT_FUEL_BUDGET),
+            disabled: AtomicBool::new(false),
+            breaker: CircuitBreaker::default(),
+            epoch_ticks,
+            limits,
+            capabilities,
         })
     }
 }
 
 impl WasmPlugin {
+    /// Set the fuel available to each subsequent invocation
+    pub fn set_fuel_budget(&self, fuel: u64) {
+        self.fuel_budget.store(fuel, Ordering::Relaxed);
+    }
+
+    pub fn fuel_budget(&self) -> u64 {
+        self.fuel_budget.load(Ordering::Relaxed)
+    }
+
+    /// Mark the plugin as disabled; the engine skips disabled plugins
+    pub fn disable(&self) {
+        self.disabled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.disabled.load(Ordering::Relaxed)
+    }
+
+    pub fn breaker(&self) -> &CircuitBreaker {
+        &self.breaker
+    }
+
+    /// Limits the plugin was loaded with, if any
+    pub fn limits(&self) -> Option<SandboxLimits> {
+        self.limits
+    }
+
+    /// Capabilities granted by the plugin's manifest
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    /// Run the plugin's `on_event` export with a fresh fuel budget.
+    /// Returns `ZenithError::BudgetExceeded` if the budget runs out.
     pub fn on_event(&self, source_id: u32, seq_no: u64) -> Result<bool> {
         let mut store = self.store.lock().expect("Lock poisoned");
+        self.begin_call(&mut store)?;
         // Look for a function named "on_event" that takes (i32, i64) -> i32
         // Rust u32 -> wasm i32, u64 -> i64 usually
         let func = self.instance.get_typed_func::<(i32, i64), i32>(&mut *store, "on_event");
         
         match func {
             Ok(f) => {
-                let res = f.call(&mut *store, (source_id as i32, seq_no as i64))?;
+                let res = f.call(&mut *store, (source_id as i32, seq_no as i64))
+                    .map_err(call_error)?;
                 Ok(res != 0)
             }
             Err(_) => {
-                // If not found, allow by default
+                // Loading checked it's exported, so the signature is
+                // wrong; allow by default
                 Ok(true)
             }
         }
     }
+
+    /// Whether the plugin can hand its state to a replacement: it exports
+    /// `memory`, `get_state: () -> i64` and `set_state: (i32, i32) -> ()`
+    pub fn has_state_exports(&self) -> bool {
+        let mut store = self.store.lock().expect("Lock poisoned");
+        self.instance.get_memory(&mut *store, "memory").is_some()
+            && self.instance.get_typed_func::<(), i64>(&mut *store, "get_state").is_ok()
+            && self.instance.get_typed_func::<(i32, i32), ()>(&mut *store, "set_state").is_ok()
+    }
+
+    /// Serialized state from the plugin's `get_state` export, which returns
+    /// the location of the bytes in its memory packed as `(ptr << 32) | len`
+    pub fn save_state(&self) -> Result<Vec<u8>> {
+        let mut store = self.store.lock().expect("Lock poisoned");
+        self.begin_call(&mut store)?;
+        let (memory, get_state) = self.state_exports(&mut store)?;
+        let packed = get_state.call(&mut *store, ()).map_err(call_error)? as u64;
+        let (ptr, len) = ((packed >> 32) as usize, (packed & 0xFFFF_FFFF) as usize);
+
+        let mut state = vec![0; len];
+        memory.read(&*store, ptr, &mut state)
+            .map_err(|_| ValidationError::Invalid(format!(
+                "get_state returned {} bytes at {:#x}, outside the plugin's memory", len, ptr)))?;
+        Ok(state)
+    }
+
+    /// Pass state saved from another plugin to this one's `set_state(ptr,
+    /// len)` export. The bytes are written to newly grown pages so they
+    /// can't overlap anything the plugin allocated; it should copy what it
+    /// needs out of them before returning.
+    pub fn restore_state(&self, state: &[u8]) -> Result<()> {
+        let mut store = self.store.lock().expect("Lock poisoned");
+        self.begin_call(&mut store)?;
+        let (memory, _) = self.state_exports(&mut store)?;
+        let set_state = self.instance.get_typed_func::<(i32, i32), ()>(&mut *store, "set_state")?;
+
+        let ptr = if state.is_empty() {
+            0
+        } else {
+            let page_size = memory.page_size(&*store);
+            let old_pages = memory.grow(&mut *store, (state.len() as u64).div_ceil(page_size))?;
+            let ptr = usize::try_from(old_pages * page_size)
+                .map_err(|_| anyhow::anyhow!("plugin memory is too large to hold its state"))?;
+            memory.write(&mut *store, ptr, state).map_err(anyhow::Error::from)?;
+            ptr
+        };
+        // wasm32 addresses and lengths fit in u32; the ABI passes them as i32
+        set_state.call(&mut *store, (ptr as u32 as i32, state.len() as u32 as i32))
+            .map_err(call_error)
+    }
+
+    fn state_exports(&self, store: &mut Store<PluginState>) -> Result<(wasmtime::Memory, wasmtime::TypedFunc<(), i64>)> {
+        let memory = self.instance.get_memory(&mut *store, "memory")
+            .ok_or_else(|| ValidationError::Invalid("plugin does not export its memory".to_string()))?;
+        let get_state = self.instance.get_typed_func::<(), i64>(&mut *store, "get_state")?;
+        Ok((memory, get_state))
+    }
+
+    /// Reset the per-invocation budgets before calling into the plugin
+    fn begin_call(&self, store: &mut Store<PluginState>) -> Result<()> {
+        store.set_fuel(self.fuel_budget())?;
+        store.set_epoch_deadline(self.epoch_ticks);
+        store.data_mut().host_calls = 0;
+        Ok(())
+    }
+}
+
+/// Classify a module wasmtime wouldn't compile. wasmparser pinpoints
+/// malformed input; anything it accepts was refused by the engine's
+/// configuration.
+fn compile_error(wasm: &[u8], e: anyhow::Error) -> ZenithError {
+    let report = match wasmtime::wasmparser::Validator::new().validate_all(wasm) {
+        Err(parse) => PluginLoadReport::Parse { offset: parse.offset(), message: parse.message().to_string() },
+        Ok(_) => PluginLoadReport::rejected(format_args!("{:#}", e)),
+    };
+    report.into()
+}
+
+/// Capabilities granted by the module's manifest; a bad manifest
+/// rejects the plugin
+fn manifest(wasm: &[u8]) -> Result<Capabilities> {
+    Capabilities::from_wasm(wasm).map_err(|e| PluginLoadReport::rejected(e).into())
+}
+
+/// Classify a failed call into the plugin
+fn call_error(e: anyhow::Error) -> ZenithError {
+    match e.downcast_ref::<Trap>() {
+        Some(Trap::OutOfFuel | Trap::Interrupt) => ZenithError::BudgetExceeded,
+        _ if e.is::<HostCallLimitExceeded>() => ZenithError::BudgetExceeded,
+        _ => ZenithError::from(e),
+    }
 }
 
 #[cfg(test)]
@@ -114,32 +604,335 @@ mod tests {
         let _ = result; // We just want to ensure no panic
     }
     
-    /// Test that on_event returns Ok(true) when function is not found
-    /// This catches the mutation: replace return Ok(true) with Ok(false)
+    const ACCEPT_WAT: &str = r#"
+        (module
+            (func (export "on_event") (param i32 i64) (result i32)
+                i32.const 1))
+    "#;
+    
     #[test]
-    fn test_wasm_plugin_on_event_default_true() {
+    fn test_load_report_parse_error() {
         let host = WasmHost::new().unwrap();
         
-        // Minimal WASM module without on_event function
-        let minimal_wasm = &[
-            0x00, 0x61, 0x73, 0x6D,  // WASM magic number
-            0x01, 0x00, 0x00, 0x00,  // Version 1
-        ];
+        // Valid header, then a type section whose size runs past the end
+        let truncated = [0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, 0x01, 0x10, 0x01];
+        match host.load_plugin(&truncated) {
+            Err(ZenithError::PluginRejected(PluginLoadReport::Parse { offset, message })) => {
+                assert!(offset >= 8, "offset {} should point past the header", offset);
+                assert!(!message.is_empty());
+            }
+            Err(e) => panic!("expected a parse report, got {e}"),
+            Ok(_) => panic!("truncated module was accepted"),
+        }
+        assert_eq!(host.module_cache_stats().entries, 0);
+    }
+    
+    #[test]
+    fn test_load_report_missing_on_event() {
+        let host = WasmHost::new().unwrap();
+        let wasm = wat::parse_str(r#"
+            (module
+                (func (export "init"))
+                (global (export "on_event") i32 (i32.const 0)))
+        "#).unwrap();
+        
+        let err = host.load_plugin(&wasm).err().expect("module without an on_event function loaded");
+        let ZenithError::PluginRejected(report) = &err else { panic!("expected a load report, got {err}") };
+        assert_eq!(*report, PluginLoadReport::MissingExports {
+            missing: vec!["on_event"],
+            missing_optional: vec!["version"],
+        });
+        assert_eq!(
+            serde_json::to_value(report).unwrap(),
+            serde_json::json!({"kind": "missing_exports", "missing": ["on_event"], "missing_optional": ["version"]})
+        );
+        assert_eq!(err.to_string(),
+            "Plugin load failed: missing required exports: on_event (optional, also absent: version)");
+        
+        // Without any export every name is listed
+        let err = host.load_plugin(&wat::parse_str("(module)").unwrap()).err().unwrap();
+        assert!(matches!(err, ZenithError::PluginRejected(PluginLoadReport::MissingExports { ref missing, ref missing_optional })
+            if missing == &["on_event"] && missing_optional == &["init", "version"]));
+    }
+    
+    #[test]
+    fn test_load_report_valid_module() {
+        let host = WasmHost::new().unwrap();
+        // Exports neither init nor version; both are optional
+        let plugin = host.load_plugin(&wat::parse_str(ACCEPT_WAT).unwrap()).unwrap();
+        assert!(plugin.on_event(1, 1).unwrap());
+        
+        // Text format goes through the same path
+        assert!(host.load_plugin(ACCEPT_WAT.as_bytes()).is_ok());
+    }
+    
+    #[test]
+    fn test_on_event_budget_exceeded() {
+        let host = WasmHost::new().unwrap();
+        let wasm = wat::parse_str(r#"
+            (module
+                (func (export "on_event") (param i32 i64) (result i32)
+                    (loop $spin (br $spin))
+                    i32.const 1))
+        "#).unwrap();
+        
+        let plugin = host.load_plugin(&wasm).unwrap();
+        plugin.set_fuel_budget(10_000);
+        assert_eq!(plugin.fuel_budget(), 10_000);
+        
+        let result = plugin.on_event(1, 1);
+        assert!(matches!(result, Err(ZenithError::BudgetExceeded)),
+            "Spinning plugin should run out of fuel");
+    }
+    
+    #[test]
+    fn test_save_state_checks_bounds() {
+        let host = WasmHost::new().unwrap();
+        let plugin = host.load_plugin(&wat::parse_str(r#"
+            (module
+                (memory (export "memory") 1)
+                (func (export "get_state") (result i64)
+                    (i64.or (i64.shl (i64.const 65000) (i64.const 32)) (i64.const 1000)))
+                (func (export "set_state") (param i32 i32))
+                (func (export "on_event") (param i32 i64) (result i32) i32.const 1))
+        "#).unwrap()).unwrap();
+        assert!(plugin.has_state_exports());
+        assert!(matches!(plugin.save_state(), Err(ZenithError::Validation(_))));
+        
+        let stateless = host.load_plugin(&wat::parse_str(r#"
+            (module
+                (memory (export "memory") 1)
+                (func (export "on_event") (param i32 i64) (result i32) i32.const 1))
+        "#).unwrap()).unwrap();
+        assert!(!stateless.has_state_exports());
+    }
+    
+    #[test]
+    fn test_on_event_budget_refilled_per_call() {
+        let host = WasmHost::new().unwrap();
+        let wasm = wat::parse_str(r#"
+            (module
+                (func (export "on_event") (param i32 i64) (result i32)
+                    i32.const 1))
+        "#).unwrap();
+        
+        let plugin = host.load_plugin(&wasm).unwrap();
+        plugin.set_fuel_budget(100);
+        for seq in 0..100 {
+            assert!(plugin.on_event(1, seq).unwrap(), "Each call should get a fresh budget");
+        }
+        assert!(!plugin.is_disabled());
+        plugin.disable();
+        assert!(plugin.is_disabled());
+    }
+    
+    #[test]
+    fn test_module_cache_hit() {
+        let host = WasmHost::new().unwrap();
+        let wasm = wat::parse_str(r#"
+            (module
+                (func (export "on_event") (param i32 i64) (result i32)
+                    i32.const 1))
+        "#).unwrap();
+        
+        let first = host.load_plugin(&wasm).unwrap();
+        assert_eq!(host.module_cache_stats(), ModuleCacheStats { hits: 0, misses: 1, entries: 1 });
         
-        let plugin_result = host.load_plugin(minimal_wasm);
+        // Same bytes hit the cache; each load still gets its own instance
+        let second = host.load_plugin_with_limits(&wasm, tight_limits()).unwrap();
+        assert_eq!(host.module_cache_stats(), ModuleCacheStats { hits: 1, misses: 1, entries: 1 });
+        assert!(first.on_event(1, 1).unwrap());
+        assert!(second.on_event(1, 1).unwrap());
         
-        // If plugin loads successfully, test on_event
-        if let Ok(plugin) = plugin_result {
-            let result = plugin.on_event(1, 100);
-            assert!(result.is_ok(), "on_event should return Ok");
-            
-            // CRITICAL: This catches the mutation Ok(true) -> Ok(false)
-            // When on_event function is not found, it should return true (allow by default)
-            assert!(result.unwrap(), 
-                "on_event should return true when function not found - catches Ok(false) mutation");
+        // Invalid bytes miss and are not cached
+        assert!(host.load_plugin(&[0xFF; 4]).is_err());
+        assert!(host.load_plugin(&[0xFF; 4]).is_err());
+        assert_eq!(host.module_cache_stats(), ModuleCacheStats { hits: 1, misses: 3, entries: 1 });
+    }
+    
+    #[test]
+    fn test_load_precompiled() {
+        let host = WasmHost::new().unwrap();
+        let wasm = wat::parse_str(r#"
+            (module
+                (func (export "on_event") (param i32 i64) (result i32)
+                    (i64.eqz (i64.rem_u (local.get 1) (i64.const 2)))))
+        "#).unwrap();
+        
+        let artifact = WasmHost::precompile(&wasm).unwrap();
+        let plugin = host.load_precompiled(&artifact).unwrap();
+        assert!(plugin.on_event(1, 2).unwrap());
+        assert!(!plugin.on_event(1, 3).unwrap());
+        // Nothing was compiled on this host
+        assert_eq!(host.module_cache_stats().misses, 0);
+        
+        // Raw WASM and corrupted artifacts are refused with a clear error
+        assert!(matches!(host.load_precompiled(&wasm), Err(ZenithError::IncompatibleArtifact(_))));
+        let mut corrupted = artifact.clone();
+        corrupted.truncate(artifact.len() / 2);
+        assert!(matches!(host.load_precompiled(&corrupted), Err(ZenithError::IncompatibleArtifact(_))));
+        
+        // An artifact from an engine with different settings is rejected
+        let other = Engine::new(&Config::new()).unwrap().precompile_module(&wasm).unwrap();
+        match host.load_precompiled(&other) {
+            Err(ZenithError::IncompatibleArtifact(msg)) => assert!(!msg.is_empty()),
+            Err(e) => panic!("expected IncompatibleArtifact, got {e}"),
+            Ok(_) => panic!("artifact from a differently configured engine was accepted"),
+        }
+    }
+    
+    #[test]
+    fn test_manifest_grants_capabilities() {
+        use crate::capabilities::Capability;
+        
+        let host = WasmHost::new().unwrap();
+        let plugin = |manifest: &str| host.load_plugin(&wat::parse_str(format!(
+            r#"(module (@custom "zenith.capabilities" "{}") (func (export "on_event") (param i32 i64) (result i32) i32.const 1))"#,
+            manifest)).unwrap());
+        
+        let granted = plugin(r#"[\"clock\", \"kv-store\"]"#).unwrap().capabilities();
+        assert!(granted.contains(Capability::Clock) && granted.contains(Capability::KvStore));
+        assert!(!granted.contains(Capability::Logging));
+        
+        // No manifest, no capabilities
+        let plain = host.load_plugin(&wat::parse_str(ACCEPT_WAT).unwrap()).unwrap();
+        assert!(plain.capabilities().is_empty());
+        
+        assert!(matches!(
+            plugin(r#"[\"sockets\"]"#),
+            Err(ZenithError::PluginRejected(PluginLoadReport::Rejected { .. }))
+        ));
+    }
+    
+    #[test]
+    fn test_wasi_clock_requires_capability() {
+        let host = WasmHost::new().unwrap();
+        let plugin = |manifest: &str| host.load_plugin(&wat::parse_str(format!(
+            r#"(module
+                (import "wasi_snapshot_preview1" "clock_time_get" (func $now (param i32 i64 i32) (result i32)))
+                (memory (export "memory") 1)
+                (@custom "zenith.capabilities" "{}")
+                (func (export "on_event") (param i32 i64) (result i32)
+                    (call $now (i32.const 0) (i64.const 0) (i32.const 0))
+                    i32.eqz))"#,
+            manifest)).unwrap()).unwrap();
+        
+        // Without the capability the import resolves to a trapping stub
+        match plugin("[]").on_event(1, 1) {
+            Err(ZenithError::WasmError(e)) => assert_eq!(e.downcast_ref(), Some(&CapabilityDenied(Capability::Clock))),
+            other => panic!("expected a capability trap, got {:?}", other),
+        }
+        
+        assert!(plugin(r#"[\"clock\"]"#).on_event(1, 1).unwrap());
+    }
+    
+    fn tight_limits() -> SandboxLimits {
+        SandboxLimits {
+            max_memory_bytes: 64 * 1024,
+            cpu_timeout: Duration::from_millis(20),
+            max_host_calls: 3,
         }
     }
     
+    #[test]
+    fn test_sandbox_limits_validate() {
+        assert!(tight_limits().validate().is_ok());
+        
+        let zero_memory = SandboxLimits { max_memory_bytes: 0, ..tight_limits() };
+        assert!(zero_memory.validate().is_err());
+        let huge_memory = SandboxLimits { max_memory_bytes: MAX_PLUGIN_MEMORY_BYTES + 1, ..tight_limits() };
+        assert!(huge_memory.validate().is_err());
+        let no_timeout = SandboxLimits { cpu_timeout: Duration::ZERO, ..tight_limits() };
+        assert!(no_timeout.validate().is_err());
+        let long_timeout = SandboxLimits { cpu_timeout: MAX_PLUGIN_CPU_TIMEOUT + Duration::from_millis(1), ..tight_limits() };
+        assert!(long_timeout.validate().is_err());
+        let no_calls = SandboxLimits { max_host_calls: 0, ..tight_limits() };
+        assert!(no_calls.validate().is_err());
+        
+        let host = WasmHost::new().unwrap();
+        let wasm = wat::parse_str(ACCEPT_WAT).unwrap();
+        assert!(matches!(host.load_plugin_with_limits(&wasm, no_calls), Err(ZenithError::Validation(_))));
+        let plugin = host.load_plugin_with_limits(&wasm, tight_limits()).unwrap();
+        assert_eq!(plugin.limits(), Some(tight_limits()));
+        assert_eq!(host.load_plugin(&wasm).unwrap().limits(), None);
+    }
+    
+    #[test]
+    fn test_memory_limit_honored() {
+        let host = WasmHost::new().unwrap();
+        // Accepts the event only if it managed to grow memory by one page
+        let wasm = wat::parse_str(r#"
+            (module
+                (memory 1)
+                (func (export "on_event") (param i32 i64) (result i32)
+                    (i32.ne (memory.grow (i32.const 1)) (i32.const -1))))
+        "#).unwrap();
+        
+        let limited = host.load_plugin_with_limits(&wasm, tight_limits()).unwrap();
+        assert!(!limited.on_event(1, 1).unwrap(), "grow past 64KiB should fail");
+        
+        let unlimited = host.load_plugin(&wasm).unwrap();
+        assert!(unlimited.on_event(1, 1).unwrap());
+        
+        // Initial memory already over the limit is rejected at load
+        let big = wat::parse_str(r#"
+            (module
+                (memory 2)
+                (func (export "on_event") (param i32 i64) (result i32) i32.const 1))
+        "#).unwrap();
+        assert!(matches!(
+            host.load_plugin_with_limits(&big, tight_limits()),
+            Err(ZenithError::PluginRejected(PluginLoadReport::Rejected { .. }))
+        ));
+    }
+    
+    #[test]
+    fn test_cpu_timeout_honored() {
+        let host = WasmHost::new().unwrap();
+        let wasm = wat::parse_str(r#"
+            (module
+                (func (export "on_event") (param i32 i64) (result i32)
+                    (loop $spin (br $spin))
+                    i32.const 1))
+        "#).unwrap();
+        
+        let plugin = host.load_plugin_with_limits(&wasm, tight_limits()).unwrap();
+        // Plenty of fuel, so only the timeout can stop it
+        plugin.set_fuel_budget(u64::MAX);
+        
+        let started = std::time::Instant::now();
+        assert!(matches!(plugin.on_event(1, 1), Err(ZenithError::BudgetExceeded)));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+    
+    #[test]
+    fn test_host_call_limit_honored() {
+        let mut host = WasmHost::new().unwrap();
+        host.linker.func_wrap("env", "ping", || {}).unwrap();
+        // Calls the host `n = seq_no` times
+        let wasm = wat::parse_str(r#"
+            (module
+                (import "env" "ping" (func $ping))
+                (func (export "on_event") (param i32 i64) (result i32)
+                    (block $done
+                        (loop $more
+                            (br_if $done (i64.eqz (local.get 1)))
+                            (call $ping)
+                            (local.set 1 (i64.sub (local.get 1) (i64.const 1)))
+                            (br $more)))
+                    i32.const 1))
+        "#).unwrap();
+        
+        let plugin = host.load_plugin_with_limits(&wasm, tight_limits()).unwrap();
+        assert!(plugin.on_event(1, 3).unwrap());
+        // The count resets per invocation
+        assert!(plugin.on_event(1, 3).unwrap());
+        assert!(matches!(plugin.on_event(1, 4), Err(ZenithError::BudgetExceeded)));
+        
+        let unlimited = host.load_plugin(&wasm).unwrap();
+        assert!(unlimited.on_event(1, 100).unwrap());
+    }
+    
     /// Test that verifies the != 0 logic in on_event
     /// This is harder to test without a real WASM plugin, but we document the expected behavior
     #[test]
@@ -3,7 +3,7 @@
 use crate::sandbox::{Sandbox, SandboxLimits};
 use crate::scheduler::{Scheduler, Priority};
 use crate::vm::VM;
-use crate::host_calls::HostCallInterface;
+use crate::host_calls::{HostCallInterface, PluginMetricsSnapshot};
 use anyhow::Result;
 use std::sync::Arc;
 use std::collections::HashMap;
@@ -29,7 +29,6 @@ pub struct RuntimeEngine {
     plugins: Arc<RwLock<HashMap<String, PluginEntry>>>,
     scheduler: Arc<Scheduler>,
     sandbox: Arc<Sandbox>,
-    #[allow(dead_code)]
     host_calls: Arc<HostCallInterface>,
 }
 
@@ -76,7 +75,7 @@ impl RuntimeEngine {
         ctx.start();
         
         // Execute
-        let result = entry.vm.execute(function, args)?;
+        let result = entry.vm.execute_i64(function, args)?;
         
         // Check timeout
         ctx.check_timeout()?;
@@ -84,6 +83,23 @@ impl RuntimeEngine {
         Ok(result)
     }
 
+    /// Execute a byte-exchanging plugin function under the sandbox limits
+    pub async fn execute_plugin_bytes(&self, plugin_id: &str, function: &str, input: &[u8]) -> Result<Vec<u8>> {
+        let plugins = self.plugins.read().await;
+        let entry = plugins.get(plugin_id)
+            .ok_or_else(|| anyhow::anyhow!("Plugin not found"))?;
+        
+        let mut ctx = self.sandbox.create_context();
+        ctx.start();
+        
+        let mut instance = entry.vm.instance()?;
+        let output = instance.call_bytes_in(&ctx, function, input)?;
+        
+        ctx.check_timeout()?;
+        
+        Ok(output)
+    }
+
     /// Schedule task for async execution
     pub fn schedule_task(&self, priority: Priority, payload: Vec<u8>) -> u64 {
         self.scheduler.submit(priority, payload)
@@ -94,6 +110,11 @@ impl RuntimeEngine {
         self.plugins.read().await.len()
     }
 
+    /// Counters and gauges emitted by plugins, for the metrics endpoint
+    pub fn plugin_metrics(&self) -> PluginMetricsSnapshot {
+        self.host_calls.metrics_snapshot()
+    }
+
     /// Get scheduler stats
     pub fn pending_tasks(&self) -> usize {
         self.scheduler.pending_count()
@@ -1,36 +1,254 @@
 /// Host Call Interface for WASM Plugins
 /// Provides safe API for plugins to interact with Zenith runtime
 use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use zenith_core::capabilities::{Capabilities, Capability, CapabilityDenied};
+
+/// Maximum distinct counter (and, separately, gauge) names plugins may create
+pub const MAX_PLUGIN_METRICS: usize = 256;
+/// Default cap on the scratch store, counting key and value bytes
+pub const DEFAULT_KV_CAPACITY_BYTES: usize = 1024 * 1024;
+/// How often, at most, a "suppressed N messages" line is logged
+const LOG_SUPPRESSION_SUMMARY_NS: u64 = 1_000_000_000;
+
+/// Time source for `get_timestamp_ns`
+pub trait Clock: Send + Sync {
+    /// Nanoseconds since UNIX epoch
+    fn now_ns(&self) -> u64;
+}
+
+/// Wall-clock time from `SystemTime`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_ns(&self) -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as u64
+    }
+}
+
+/// Deterministic clock for tests: returns a fixed time, optionally
+/// advancing by `step_ns` after every read
+#[derive(Debug, Default)]
+pub struct MockClock {
+    now_ns: AtomicU64,
+    step_ns: u64,
+}
+
+impl MockClock {
+    /// Clock that always reads `now_ns`
+    pub fn fixed(now_ns: u64) -> Self {
+        Self::advancing(now_ns, 0)
+    }
+
+    /// Clock that starts at `start_ns` and moves forward `step_ns` per read
+    pub fn advancing(start_ns: u64, step_ns: u64) -> Self {
+        Self { now_ns: AtomicU64::new(start_ns), step_ns }
+    }
+
+    /// Jump to an absolute time
+    pub fn set(&self, now_ns: u64) {
+        self.now_ns.store(now_ns, Ordering::SeqCst);
+    }
+
+    /// Move the clock forward
+    pub fn advance(&self, by_ns: u64) {
+        self.now_ns.fetch_add(by_ns, Ordering::SeqCst);
+    }
+}
+
+impl Clock for MockClock {
+    fn now_ns(&self) -> u64 {
+        self.now_ns.fetch_add(self.step_ns, Ordering::SeqCst)
+    }
+}
+
+/// Shared clocks, so a test can keep a handle and move time forward
+impl<C: Clock + ?Sized> Clock for Arc<C> {
+    fn now_ns(&self) -> u64 {
+        (**self).now_ns()
+    }
+}
+
+/// Token-bucket limit on plugin log messages
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LogRateLimit {
+    /// Sustained messages per second
+    pub per_second: f64,
+    /// Messages that may be logged back to back before the rate applies
+    pub burst: u32,
+}
+
+impl Default for LogRateLimit {
+    fn default() -> Self {
+        Self { per_second: 100.0, burst: 200 }
+    }
+}
+
+#[derive(Debug)]
+struct LogBucket {
+    limit: LogRateLimit,
+    tokens: f64,
+    /// Clock reading tokens were last topped up at; `None` until first use
+    refilled_ns: Option<u64>,
+    /// Dropped since the last summary line
+    suppressed: u64,
+    summary_ns: u64,
+}
+
+impl LogBucket {
+    fn new(limit: LogRateLimit) -> Self {
+        Self {
+            limit,
+            tokens: f64::from(limit.burst),
+            refilled_ns: None,
+            suppressed: 0,
+            summary_ns: 0,
+        }
+    }
+
+    fn try_take(&mut self, now_ns: u64) -> bool {
+        if let Some(last) = self.refilled_ns {
+            let elapsed = now_ns.saturating_sub(last) as f64 / 1e9;
+            self.tokens = (self.tokens + elapsed * self.limit.per_second).min(f64::from(self.limit.burst));
+        }
+        self.refilled_ns = Some(now_ns);
+        
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
 
 /// Host functions exposed to WASM plugins
+///
+/// Each plugin gets its own interface, so the scratch store behind
+/// `kv_set`/`kv_get` is private to that plugin. Logging, the clock and
+/// the scratch store are only available with the matching capability;
+/// a new interface grants none.
 pub struct HostCallInterface {
     call_count: std::sync::atomic::AtomicU32,
+    capabilities: Capabilities,
+    clock: Box<dyn Clock>,
+    counters: RwLock<HashMap<String, u64>>,
+    gauges: RwLock<HashMap<String, f64>>,
+    rejected_metrics: AtomicU64,
+    kv: RwLock<KvStore>,
+    kv_capacity: usize,
+    log_bucket: Mutex<LogBucket>,
+    suppressed_logs: AtomicU64,
+}
+
+/// Scratch entries plus their running size
+#[derive(Debug, Default)]
+struct KvStore {
+    entries: HashMap<String, Vec<u8>>,
+    bytes: usize,
+}
+
+/// Point-in-time copy of plugin-emitted metrics
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PluginMetricsSnapshot {
+    /// Counter totals by name
+    pub counters: HashMap<String, u64>,
+    /// Latest gauge values by name
+    pub gauges: HashMap<String, f64>,
+    /// Updates dropped because the name cap was reached
+    pub rejected: u64,
 }
 
 impl HostCallInterface {
     pub fn new() -> Self {
+        Self::with_clock(Box::new(SystemClock))
+    }
+
+    /// Create an interface reading time from `clock`
+    pub fn with_clock(clock: Box<dyn Clock>) -> Self {
         Self {
             call_count: std::sync::atomic::AtomicU32::new(0),
+            capabilities: Capabilities::NONE,
+            clock,
+            counters: RwLock::new(HashMap::new()),
+            gauges: RwLock::new(HashMap::new()),
+            rejected_metrics: AtomicU64::new(0),
+            kv: RwLock::new(KvStore::default()),
+            kv_capacity: DEFAULT_KV_CAPACITY_BYTES,
+            log_bucket: Mutex::new(LogBucket::new(LogRateLimit::default())),
+            suppressed_logs: AtomicU64::new(0),
         }
     }
 
+    /// Grant `capabilities`, normally those from the plugin's manifest
+    /// (`Capabilities::from_wasm`)
+    pub fn with_capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    /// Replace the default limit on `log` messages
+    pub fn with_log_rate_limit(self, limit: LogRateLimit) -> Self {
+        *self.log_bucket.lock().unwrap_or_else(|e| e.into_inner()) = LogBucket::new(limit);
+        self
+    }
+
+    /// Cap the scratch store at `max_bytes` of keys plus values
+    pub fn with_kv_capacity(mut self, max_bytes: usize) -> Self {
+        self.kv_capacity = max_bytes;
+        self
+    }
+
     /// Log a message from the plugin
-    pub fn log(&self, level: LogLevel, message: &str) {
+    ///
+    /// Messages over the rate limit are dropped; a count of them is logged
+    /// at most once a second and when logging resumes.
+    pub fn log(&self, level: LogLevel, message: &str) -> Result<()> {
         self.increment_call_count();
+        self.require(Capability::Logging)?;
+        
+        let now_ns = self.clock.now_ns();
+        let mut bucket = self.log_bucket.lock().unwrap_or_else(|e| e.into_inner());
+        let allowed = bucket.try_take(now_ns);
+        if !allowed {
+            bucket.suppressed += 1;
+            self.suppressed_logs.fetch_add(1, Ordering::Relaxed);
+        }
+        if bucket.suppressed > 0
+            && (allowed || now_ns.saturating_sub(bucket.summary_ns) >= LOG_SUPPRESSION_SUMMARY_NS)
+        {
+            tracing::warn!("[WASM Plugin] suppressed {} log messages over the rate limit", bucket.suppressed);
+            bucket.suppressed = 0;
+            bucket.summary_ns = now_ns;
+        }
+        drop(bucket);
+        if !allowed {
+            return Ok(());
+        }
+        
         match level {
             LogLevel::Info => tracing::info!("[WASM Plugin] {}", message),
             LogLevel::Warn => tracing::warn!("[WASM Plugin] {}", message),
             LogLevel::Error => tracing::error!("[WASM Plugin] {}", message),
         }
+        Ok(())
     }
 
     /// Get current timestamp (nanoseconds since UNIX epoch)
-    pub fn get_timestamp_ns(&self) -> u64 {
+    pub fn get_timestamp_ns(&self) -> Result<u64> {
         self.increment_call_count();
-        std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_nanos() as u64
+        self.require(Capability::Clock)?;
+        Ok(self.clock.now_ns())
     }
 
     /// Read event metadata field
@@ -40,13 +258,123 @@ impl HostCallInterface {
         Ok(field_name.as_bytes().to_vec())
     }
 
+    /// Add `by` to a plugin-defined counter
+    ///
+    /// New names beyond `MAX_PLUGIN_METRICS` are dropped and counted as rejected.
+    pub fn increment_counter(&self, name: &str, by: u64) {
+        self.increment_call_count();
+        
+        let mut counters = self.counters.write().unwrap_or_else(|e| e.into_inner());
+        if let Some(value) = counters.get_mut(name) {
+            *value = value.saturating_add(by);
+        } else if counters.len() < MAX_PLUGIN_METRICS {
+            counters.insert(name.to_string(), by);
+        } else {
+            self.reject_metric(name);
+        }
+    }
+
+    /// Set a plugin-defined gauge to `value`
+    ///
+    /// New names beyond `MAX_PLUGIN_METRICS` are dropped and counted as rejected.
+    pub fn set_gauge(&self, name: &str, value: f64) {
+        self.increment_call_count();
+        
+        let mut gauges = self.gauges.write().unwrap_or_else(|e| e.into_inner());
+        if let Some(slot) = gauges.get_mut(name) {
+            *slot = value;
+        } else if gauges.len() < MAX_PLUGIN_METRICS {
+            gauges.insert(name.to_string(), value);
+        } else {
+            self.reject_metric(name);
+        }
+    }
+
+    /// Store scratch state that outlives a single `on_event` call
+    ///
+    /// Fails, leaving the store unchanged, if the write would take it past
+    /// its byte cap. Replacing a key only counts the size difference.
+    pub fn kv_set(&self, key: &str, value: Vec<u8>) -> Result<()> {
+        self.increment_call_count();
+        self.require(Capability::KvStore)?;
+        
+        let mut kv = self.kv.write().unwrap_or_else(|e| e.into_inner());
+        let replaced = kv.entries.get(key).map_or(0, |old| key.len() + old.len());
+        let bytes = kv.bytes - replaced + key.len() + value.len();
+        if bytes > self.kv_capacity {
+            anyhow::bail!(
+                "Scratch store full: writing '{}' would use {} of {} bytes",
+                key, bytes, self.kv_capacity
+            );
+        }
+        kv.bytes = bytes;
+        kv.entries.insert(key.to_string(), value);
+        Ok(())
+    }
+
+    /// Read scratch state written with `kv_set`
+    pub fn kv_get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        self.increment_call_count();
+        self.require(Capability::KvStore)?;
+        Ok(self.kv.read().unwrap_or_else(|e| e.into_inner()).entries.get(key).cloned())
+    }
+
+    /// Drop a scratch entry, returning its value
+    pub fn kv_remove(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        self.increment_call_count();
+        self.require(Capability::KvStore)?;
+        let mut kv = self.kv.write().unwrap_or_else(|e| e.into_inner());
+        let value = kv.entries.remove(key);
+        if let Some(value) = &value {
+            kv.bytes -= key.len() + value.len();
+        }
+        Ok(value)
+    }
+
+    /// Drop all scratch state, e.g. at the end of a window
+    pub fn kv_clear(&self) {
+        *self.kv.write().unwrap_or_else(|e| e.into_inner()) = KvStore::default();
+    }
+
+    /// Bytes of keys and values currently in the scratch store
+    pub fn kv_bytes(&self) -> usize {
+        self.kv.read().unwrap_or_else(|e| e.into_inner()).bytes
+    }
+
+    /// Snapshot plugin metrics for export through the engine's metrics endpoint
+    pub fn metrics_snapshot(&self) -> PluginMetricsSnapshot {
+        PluginMetricsSnapshot {
+            counters: self.counters.read().unwrap_or_else(|e| e.into_inner()).clone(),
+            gauges: self.gauges.read().unwrap_or_else(|e| e.into_inner()).clone(),
+            rejected: self.rejected_metrics.load(Ordering::Relaxed),
+        }
+    }
+
+    fn reject_metric(&self, name: &str) {
+        self.rejected_metrics.fetch_add(1, Ordering::Relaxed);
+        tracing::debug!("[WASM Plugin] metric '{}' dropped: limit of {} names reached", name, MAX_PLUGIN_METRICS);
+    }
+
+    /// Log messages dropped by the rate limit so far
+    pub fn suppressed_logs(&self) -> u64 {
+        self.suppressed_logs.load(Ordering::Relaxed)
+    }
+
     /// Get total host calls made
     pub fn get_call_count(&self) -> u32 {
-        self.call_count.load(std::sync::atomic::Ordering::Relaxed)
+        self.call_count.load(Ordering::Relaxed)
     }
 
     fn increment_call_count(&self) {
-        self.call_count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        self.call_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn require(&self, capability: Capability) -> Result<()> {
+        if self.capabilities.contains(capability) {
+            Ok(())
+        } else {
+            Err(CapabilityDenied(capability).into())
+        }
     }
 }
 
@@ -72,3 +400,159 @@ impl From<u32> for LogLevel {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plugin_counters_and_gauges() {
+        let host = HostCallInterface::new();
+        
+        host.increment_counter("events_matched", 3);
+        host.increment_counter("events_matched", 4);
+        host.set_gauge("queue_ratio", 0.25);
+        host.set_gauge("queue_ratio", 0.5);
+        
+        let snapshot = host.metrics_snapshot();
+        assert_eq!(snapshot.counters["events_matched"], 7);
+        assert_eq!(snapshot.gauges["queue_ratio"], 0.5);
+        assert_eq!(host.get_call_count(), 4, "Metric updates count as host calls");
+    }
+
+    #[test]
+    fn test_kv_set_get() {
+        let host = HostCallInterface::new().with_capabilities(Capabilities::all());
+        assert_eq!(host.kv_get("window").unwrap(), None);
+        
+        host.kv_set("window", vec![1, 2, 3]).unwrap();
+        host.kv_set("count", 7u64.to_le_bytes().to_vec()).unwrap();
+        assert_eq!(host.kv_get("window").unwrap(), Some(vec![1, 2, 3]));
+        assert_eq!(host.kv_bytes(), "window".len() + 3 + "count".len() + 8);
+        
+        host.kv_set("window", vec![9]).unwrap();
+        assert_eq!(host.kv_get("window").unwrap(), Some(vec![9]));
+        assert_eq!(host.kv_remove("count").unwrap(), Some(7u64.to_le_bytes().to_vec()));
+        assert_eq!(host.kv_remove("count").unwrap(), None);
+        assert_eq!(host.kv_bytes(), "window".len() + 1);
+        assert_eq!(host.get_call_count(), 8, "Scratch store access counts as host calls");
+        
+        host.kv_clear();
+        assert_eq!(host.kv_get("window").unwrap(), None);
+        assert_eq!(host.kv_bytes(), 0);
+        
+        // Stores are per interface, i.e. per plugin
+        let other = HostCallInterface::new().with_capabilities(Capabilities::all());
+        host.kv_set("k", vec![1]).unwrap();
+        assert_eq!(other.kv_get("k").unwrap(), None);
+    }
+
+    #[test]
+    fn test_kv_byte_cap() {
+        let host = HostCallInterface::new()
+            .with_capabilities(Capabilities::NONE.with(Capability::KvStore))
+            .with_kv_capacity(16);
+        
+        host.kv_set("a", vec![0; 10]).unwrap();
+        let err = host.kv_set("b", vec![0; 5]).unwrap_err();
+        assert!(err.to_string().contains("would use 17 of 16 bytes"), "{}", err);
+        assert_eq!(host.kv_get("b").unwrap(), None, "A rejected write stores nothing");
+        assert_eq!(host.kv_bytes(), 11);
+        
+        // Shrinking an entry frees room for another
+        host.kv_set("a", vec![0; 5]).unwrap();
+        host.kv_set("b", vec![0; 5]).unwrap();
+        assert_eq!(host.kv_bytes(), 12);
+        assert!(host.kv_set("c", vec![0; 16]).is_err());
+    }
+
+    #[test]
+    fn test_log_rate_limit() {
+        let clock = Arc::new(MockClock::fixed(1_000_000_000));
+        let host = HostCallInterface::with_clock(Box::new(Arc::clone(&clock)))
+            .with_capabilities(Capabilities::NONE.with(Capability::Logging))
+            .with_log_rate_limit(LogRateLimit { per_second: 10.0, burst: 5 });
+        
+        for i in 0..100 {
+            host.log(LogLevel::Info, &format!("flood {}", i)).unwrap();
+        }
+        assert_eq!(host.suppressed_logs(), 95, "Only the burst gets through at once");
+        assert_eq!(host.get_call_count(), 100, "Dropped messages still count as host calls");
+        
+        // 300ms refills three tokens
+        clock.advance(300_000_000);
+        for _ in 0..10 {
+            host.log(LogLevel::Warn, "again").unwrap();
+        }
+        assert_eq!(host.suppressed_logs(), 95 + 7);
+        
+        // Refill never exceeds the burst
+        clock.advance(60_000_000_000);
+        for _ in 0..10 {
+            host.log(LogLevel::Error, "later").unwrap();
+        }
+        assert_eq!(host.suppressed_logs(), 102 + 5);
+    }
+
+    #[test]
+    fn test_mock_clock_timestamps() {
+        let clock_only = Capabilities::NONE.with(Capability::Clock);
+        let host = HostCallInterface::with_clock(Box::new(MockClock::fixed(1_700_000_000_000_000_000)))
+            .with_capabilities(clock_only);
+        assert_eq!(host.get_timestamp_ns().unwrap(), 1_700_000_000_000_000_000);
+        assert_eq!(host.get_timestamp_ns().unwrap(), 1_700_000_000_000_000_000);
+        
+        let clock = Arc::new(MockClock::advancing(1_000, 10));
+        let host = HostCallInterface::with_clock(Box::new(Arc::clone(&clock))).with_capabilities(clock_only);
+        assert_eq!(host.get_timestamp_ns().unwrap(), 1_000);
+        assert_eq!(host.get_timestamp_ns().unwrap(), 1_010);
+        
+        clock.advance(500);
+        assert_eq!(host.get_timestamp_ns().unwrap(), 1_520);
+        clock.set(42);
+        assert_eq!(host.get_timestamp_ns().unwrap(), 42);
+    }
+
+    #[test]
+    fn test_system_clock_is_default() {
+        let host = HostCallInterface::new().with_capabilities(Capabilities::NONE.with(Capability::Clock));
+        let before = SystemClock.now_ns();
+        let ts = host.get_timestamp_ns().unwrap();
+        assert!(ts >= before && ts <= SystemClock.now_ns());
+    }
+
+    #[test]
+    fn test_denied_capabilities() {
+        let host = HostCallInterface::new();
+        assert!(host.capabilities().is_empty(), "Least privilege by default");
+        
+        let err = host.get_timestamp_ns().unwrap_err();
+        assert_eq!(err.downcast_ref::<CapabilityDenied>(), Some(&CapabilityDenied(Capability::Clock)));
+        assert!(host.log(LogLevel::Info, "hi").is_err());
+        assert!(host.kv_set("k", vec![1]).is_err());
+        assert!(host.kv_get("k").is_err());
+        assert_eq!(host.kv_bytes(), 0);
+        assert_eq!(host.get_call_count(), 4, "Denied calls still count as host calls");
+        
+        // Capabilities are independent
+        let host = HostCallInterface::new().with_capabilities(Capabilities::NONE.with(Capability::Logging));
+        host.log(LogLevel::Info, "hi").unwrap();
+        assert!(host.get_timestamp_ns().is_err());
+    }
+    
+    #[test]
+    fn test_plugin_metric_name_cap() {
+        let host = HostCallInterface::new();
+        
+        for i in 0..MAX_PLUGIN_METRICS + 10 {
+            host.increment_counter(&format!("counter_{}", i), 1);
+        }
+        // Existing names keep updating after the cap is hit
+        host.increment_counter("counter_0", 1);
+        
+        let snapshot = host.metrics_snapshot();
+        assert_eq!(snapshot.counters.len(), MAX_PLUGIN_METRICS);
+        assert_eq!(snapshot.counters["counter_0"], 2);
+        assert_eq!(snapshot.rejected, 10);
+    }
+}
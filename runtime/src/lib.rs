@@ -18,8 +18,9 @@ pub mod host_calls;
 pub use engine::{RuntimeEngine, PluginMetadata};
 pub use sandbox::{Sandbox, SandboxLimits};
 pub use scheduler::{Scheduler, Priority};
-pub use vm::VM;
-pub use host_calls::HostCallInterface;
+pub use vm::{VM, VmInstance, VmValue};
+pub use zenith_core::capabilities::CapabilityDenied;
+pub use host_calls::{HostCallInterface, LogRateLimit, PluginMetricsSnapshot, Clock, SystemClock, MockClock};
 
 /// The Zenith Runtime Manager.
 /// Handles lifecycle, configuration, and hot-reloading of plugins.
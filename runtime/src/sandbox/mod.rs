@@ -13,6 +13,8 @@ pub struct SandboxLimits {
     pub cpu_timeout: Duration,
     /// Maximum number of host calls
     pub max_host_calls: u32,
+    /// Maximum size of a buffer a plugin may return (bytes)
+    pub max_output_bytes: usize,
 }
 
 impl Default for SandboxLimits {
@@ -21,6 +23,7 @@ impl Default for SandboxLimits {
             max_memory: 16 * 1024 * 1024, // 16MB
             cpu_timeout: Duration::from_millis(100),
             max_host_calls: 1000,
+            max_output_bytes: 16 * 1024 * 1024, // 16MB
         }
     }
 }
@@ -55,6 +58,18 @@ impl ExecutionContext {
         Ok(())
     }
 
+    /// Reject plugin output larger than `max_output_bytes`, before it is
+    /// copied out of guest memory
+    pub fn check_output_size(&self, len: usize) -> Result<()> {
+        if len > self.limits.max_output_bytes {
+            return Err(anyhow!(
+                "Plugin output of {} bytes exceeds limit of {} bytes",
+                len, self.limits.max_output_bytes
+            ));
+        }
+        Ok(())
+    }
+
     pub fn record_host_call(&mut self) -> Result<()> {
         self.host_call_count += 1;
         if self.host_call_count > self.limits.max_host_calls {
@@ -92,3 +107,25 @@ impl Sandbox {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_size_limit() {
+        let sandbox = Sandbox::new(SandboxLimits {
+            max_output_bytes: 1024,
+            ..Default::default()
+        });
+        let ctx = sandbox.create_context();
+        
+        assert!(ctx.check_output_size(0).is_ok());
+        assert!(ctx.check_output_size(1024).is_ok());
+        
+        let err = ctx.check_output_size(1025).unwrap_err();
+        assert!(err.to_string().contains("exceeds limit"));
+        
+        assert_eq!(SandboxLimits::default().max_output_bytes, 16 * 1024 * 1024);
+    }
+}
@@ -1,71 +1,400 @@
 /// Virtual Machine abstraction for WASM execution
 /// Wraps Wasmtime with additional runtime features
-use wasmtime::{Engine as WasmEngine, Store, Module, Linker};
-use wasmtime_wasi::WasiCtxBuilder;
+use wasmtime::{Config, Engine as WasmEngine, ExternType, Store, Module, Linker, Instance, Val, ValType};
+use wasmtime::component::{Component, Linker as ComponentLinker, ResourceTable};
+use wasmtime_wasi::{WasiCtx, WasiCtxBuilder, WasiCtxView, WasiView};
 use wasmtime_wasi::p1::{self, WasiP1Ctx};
 use anyhow::Result;
 use std::sync::Arc;
+use crate::sandbox::ExecutionContext;
 
 /// Type alias for WASI state in wasmtime v39+
 type WasiState = WasiP1Ctx;
 
+/// Bindings for components targeting `wit/on-event.wit`
+mod bindings {
+    wasmtime::component::bindgen!({
+        path: "wit",
+        world: "on-event",
+    });
+}
+
+/// Store state for components, which link WASI preview2
+struct ComponentState {
+    ctx: WasiCtx,
+    table: ResourceTable,
+}
+
+impl WasiView for ComponentState {
+    fn ctx(&mut self) -> WasiCtxView<'_> {
+        WasiCtxView { ctx: &mut self.ctx, table: &mut self.table }
+    }
+}
+
+/// WASI preview1 calls that observe wall time, timers or entropy; in
+/// deterministic mode they trap instead
+const NONDETERMINISTIC_WASI_CALLS: &[&str] = &["clock_time_get", "clock_res_get", "poll_oneoff", "random_get"];
+
+/// Whether `wasm` is a component rather than a core module. Both start
+/// with `\0asm`; the version/layer word that follows tells them apart.
+pub fn is_component(wasm: &[u8]) -> bool {
+    wasm.len() >= 8 && &wasm[0..4] == b"\0asm" && wasm[6..8] == [0x01, 0x00]
+}
+
+/// A WASM value passed to or returned from a guest function
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VmValue {
+    I32(i32),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+}
+
+impl VmValue {
+    /// Integer view of the value; `I32` is sign-extended, floats yield `None`
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            VmValue::I32(v) => Some(v as i64),
+            VmValue::I64(v) => Some(v),
+            VmValue::F32(_) | VmValue::F64(_) => None,
+        }
+    }
+
+    fn zero_for(ty: &ValType) -> Result<Val> {
+        match ty {
+            ValType::I32 => Ok(Val::I32(0)),
+            ValType::I64 => Ok(Val::I64(0)),
+            ValType::F32 => Ok(Val::F32(0)),
+            ValType::F64 => Ok(Val::F64(0)),
+            other => Err(anyhow::anyhow!("Unsupported WASM result type: {}", other)),
+        }
+    }
+}
+
+impl From<VmValue> for Val {
+    fn from(value: VmValue) -> Self {
+        match value {
+            VmValue::I32(v) => Val::I32(v),
+            VmValue::I64(v) => Val::I64(v),
+            VmValue::F32(v) => Val::F32(v.to_bits()),
+            VmValue::F64(v) => Val::F64(v.to_bits()),
+        }
+    }
+}
+
+impl TryFrom<&Val> for VmValue {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &Val) -> Result<Self> {
+        match value {
+            Val::I32(v) => Ok(VmValue::I32(*v)),
+            Val::I64(v) => Ok(VmValue::I64(*v)),
+            Val::F32(bits) => Ok(VmValue::F32(f32::from_bits(*bits))),
+            Val::F64(bits) => Ok(VmValue::F64(f64::from_bits(*bits))),
+            other => Err(anyhow::anyhow!("Unsupported WASM value: {:?}", other)),
+        }
+    }
+}
+
+/// Compiled code behind a `VM`
+enum Artifact {
+    /// Legacy core module linked against WASI preview1
+    Module(Module),
+    /// Component implementing the `on-event` world, linked against WASI preview2
+    Component(Component),
+}
+
 /// WASM Virtual Machine
 pub struct VM {
     engine: Arc<WasmEngine>,
-    module: Module,
+    artifact: Artifact,
+    deterministic: bool,
 }
 
 impl VM {
-    /// Create new VM from WASM bytes
+    /// Create new VM from WASM bytes, accepting either a core module or a
+    /// component
     pub fn from_bytes(wasm: &[u8]) -> Result<Self> {
+        Self::compile(wasm, false)
+    }
+
+    /// Create a VM from a component implementing the `on-event` world
+    /// (`wit/on-event.wit`)
+    pub fn from_component_bytes(wasm: &[u8]) -> Result<Self> {
         let engine = Arc::new(WasmEngine::default());
-        let module = Module::new(&engine, wasm)?;
+        let component = Component::new(&engine, wasm)?;
         
-        Ok(Self { engine, module })
+        Ok(Self { engine, artifact: Artifact::Component(component), deterministic: false })
     }
 
-    /// Execute the WASM module's exported function
-    pub fn execute(&self, function_name: &str, args: &[i64]) -> Result<Vec<i64>> {
+    /// Create a VM whose runs can be replayed exactly
+    ///
+    /// Compared to `from_bytes`:
+    /// - stdio is not inherited; guest output is discarded
+    /// - core modules trap when they call the WASI clock, timer
+    ///   (`poll_oneoff`) or random functions
+    /// - components get no WASI imports at all, so one that needs them
+    ///   fails to instantiate
+    /// - NaN results are canonicalized, relaxed SIMD uses its
+    ///   deterministic lowering and shared-memory threads are disabled
+    pub fn deterministic(wasm: &[u8]) -> Result<Self> {
+        Self::compile(wasm, true)
+    }
+
+    fn compile(wasm: &[u8], deterministic: bool) -> Result<Self> {
+        let engine = if deterministic {
+            let mut config = Config::new();
+            config.cranelift_nan_canonicalization(true)
+                .relaxed_simd_deterministic(true)
+                .wasm_threads(false);
+            WasmEngine::new(&config)?
+        } else {
+            WasmEngine::default()
+        };
+        let artifact = if is_component(wasm) {
+            Artifact::Component(Component::new(&engine, wasm)?)
+        } else {
+            Artifact::Module(Module::new(&engine, wasm)?)
+        };
+        
+        Ok(Self { engine: Arc::new(engine), artifact, deterministic })
+    }
+
+    /// Whether this VM was created with `deterministic`
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    /// Whether this VM runs a component rather than a core module
+    pub fn is_component(&self) -> bool {
+        matches!(self.artifact, Artifact::Component(_))
+    }
+
+    fn module(&self) -> Result<&Module> {
+        match &self.artifact {
+            Artifact::Module(module) => Ok(module),
+            Artifact::Component(_) => Err(anyhow::anyhow!(
+                "Plugin is a component; call it through on_event"
+            )),
+        }
+    }
+
+    /// Instantiate the module in a fresh WASI store
+    fn instantiate(&self) -> Result<(Store<WasiState>, Instance)> {
+        let module = self.module()?;
         let mut linker = Linker::new(&self.engine);
         
         // wasmtime v39+ uses p1 module for WASIp1 compatibility
         p1::add_to_linker_sync(&mut linker, |s: &mut WasiState| s)?;
         
-        let wasi_ctx = WasiCtxBuilder::new()
-            .inherit_stdio()
-            .build_p1();
+        let mut builder = WasiCtxBuilder::new();
+        if self.deterministic {
+            Self::forbid_nondeterministic_calls(&mut linker, module)?;
+        } else {
+            builder.inherit_stdio();
+        }
+        let wasi_ctx = builder.build_p1();
         
         let mut store = Store::new(&self.engine, wasi_ctx);
-        let instance = linker.instantiate(&mut store, &self.module)?;
+        let instance = linker.instantiate(&mut store, module)?;
+        
+        Ok((store, instance))
+    }
+
+    /// Shadow the WASI calls the module imports that would make runs
+    /// differ with ones that trap
+    fn forbid_nondeterministic_calls(linker: &mut Linker<WasiState>, module: &Module) -> Result<()> {
+        linker.allow_shadowing(true);
+        for import in module.imports() {
+            if import.module() != "wasi_snapshot_preview1" || !NONDETERMINISTIC_WASI_CALLS.contains(&import.name()) {
+                continue;
+            }
+            if let ExternType::Func(ty) = import.ty() {
+                let name = import.name().to_string();
+                linker.func_new(import.module(), import.name(), ty, move |_, _, _| {
+                    Err(anyhow::anyhow!("WASI call {} is not allowed in deterministic mode", name))
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Run the plugin's event hook in a fresh instance
+    ///
+    /// Components are called through the `on-event` world. Core modules
+    /// need an `on_event(i32, i64) -> i32` export, nonzero meaning keep.
+    pub fn on_event(&self, source_id: u32, seq_no: u64) -> Result<bool> {
+        let component = match &self.artifact {
+            Artifact::Component(component) => component,
+            Artifact::Module(_) => {
+                let (mut store, instance) = self.instantiate()?;
+                let func = instance.get_typed_func::<(i32, i64), i32>(&mut store, "on_event")?;
+                return Ok(func.call(&mut store, (source_id as i32, seq_no as i64))? != 0);
+            }
+        };
+        
+        let mut linker = ComponentLinker::new(&self.engine);
+        let mut builder = WasiCtxBuilder::new();
+        if !self.deterministic {
+            wasmtime_wasi::p2::add_to_linker_sync(&mut linker)?;
+            builder.inherit_stdio();
+        }
+        
+        let state = ComponentState {
+            ctx: builder.build(),
+            table: ResourceTable::new(),
+        };
+        let mut store = Store::new(&self.engine, state);
+        let plugin = bindings::OnEvent::instantiate(&mut store, component, &linker)?;
+        plugin.call_on_event(&mut store, source_id, seq_no)
+    }
+
+    /// Execute the WASM module's exported function
+    ///
+    /// Results are typed from the function's declared signature.
+    pub fn execute(&self, function_name: &str, args: &[VmValue]) -> Result<Vec<VmValue>> {
+        let (mut store, instance) = self.instantiate()?;
         
         // Try to get the function
         let func = instance.get_func(&mut store, function_name)
             .ok_or_else(|| anyhow::anyhow!("Function {} not found", function_name))?;
         
-        // For simplicity, assume function signature matches
-        // In production, we'd validate this
-        let mut results = vec![wasmtime::Val::I64(0)];
+        let ty = func.ty(&store);
+        if ty.params().len() != args.len() {
+            anyhow::bail!(
+                "Function {} expects {} arguments, got {}",
+                function_name, ty.params().len(), args.len()
+            );
+        }
         
-        let params: Vec<wasmtime::Val> = args.iter()
-            .map(|&v| wasmtime::Val::I64(v))
-            .collect();
+        let mut results = ty.results()
+            .map(|t| VmValue::zero_for(&t))
+            .collect::<Result<Vec<_>>>()?;
+        
+        let params: Vec<Val> = args.iter().map(|&v| v.into()).collect();
         
         func.call(&mut store, &params, &mut results)?;
         
-        Ok(results.iter().map(|v| {
-            if let wasmtime::Val::I64(i) = v {
-                *i
-            } else {
-                0
-            }
-        }).collect())
+        results.iter().map(VmValue::try_from).collect()
+    }
+
+    /// Execute a function taking and returning integers
+    ///
+    /// `i32` results are sign-extended; a float result is an error.
+    pub fn execute_i64(&self, function_name: &str, args: &[i64]) -> Result<Vec<i64>> {
+        let args: Vec<VmValue> = args.iter().map(|&v| VmValue::I64(v)).collect();
+        
+        self.execute(function_name, &args)?
+            .iter()
+            .map(|v| v.as_i64().ok_or_else(|| anyhow::anyhow!("Function {} returned non-integer {:?}", function_name, v)))
+            .collect()
+    }
+
+    /// Create a long-lived instance whose memory persists across calls
+    pub fn instance(&self) -> Result<VmInstance> {
+        let (store, instance) = self.instantiate()?;
+        Ok(VmInstance { store, instance })
     }
 
     /// Get module metadata
     pub fn get_exports(&self) -> Vec<String> {
-        self.module.exports()
-            .map(|e| e.name().to_string())
-            .collect()
+        match &self.artifact {
+            Artifact::Module(module) => module.exports()
+                .map(|e| e.name().to_string())
+                .collect(),
+            Artifact::Component(component) => component.component_type()
+                .exports(&self.engine)
+                .map(|(name, _)| name.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// An instantiated module with its own store and linear memory
+pub struct VmInstance {
+    store: Store<WasiState>,
+    instance: Instance,
+}
+
+impl VmInstance {
+    /// Call a guest function that takes and returns a byte buffer
+    ///
+    /// The guest must export `memory`, `alloc(len: i32) -> i32` and
+    /// `dealloc(ptr: i32, len: i32)`. The input is copied into a buffer from
+    /// `alloc`, `func(ptr, len)` is invoked, and the output it describes is
+    /// copied out. The output location is returned either as two `i32`
+    /// results `(ptr, len)` or as one `i64` with `ptr` in the high 32 bits
+    /// and `len` in the low 32. Both buffers are released with `dealloc`.
+    pub fn call_bytes(&mut self, func: &str, input: &[u8]) -> Result<Vec<u8>> {
+        self.exchange_bytes(func, input, None)
+    }
+
+    /// Like `call_bytes`, but enforces the context's output size limit
+    /// before anything is copied out of guest memory
+    pub fn call_bytes_in(&mut self, ctx: &ExecutionContext, func: &str, input: &[u8]) -> Result<Vec<u8>> {
+        self.exchange_bytes(func, input, Some(ctx))
+    }
+
+    fn exchange_bytes(&mut self, func: &str, input: &[u8], ctx: Option<&ExecutionContext>) -> Result<Vec<u8>> {
+        let memory = self.instance.get_memory(&mut self.store, "memory")
+            .ok_or_else(|| anyhow::anyhow!("Guest does not export `memory`"))?;
+        let alloc = self.instance.get_typed_func::<i32, i32>(&mut self.store, "alloc")
+            .map_err(|e| anyhow::anyhow!("Guest `alloc(i32) -> i32` export missing or mistyped: {}", e))?;
+        let dealloc = self.instance.get_typed_func::<(i32, i32), ()>(&mut self.store, "dealloc")
+            .map_err(|e| anyhow::anyhow!("Guest `dealloc(i32, i32)` export missing or mistyped: {}", e))?;
+        let target = self.instance.get_func(&mut self.store, func)
+            .ok_or_else(|| anyhow::anyhow!("Function {} not found", func))?;
+        
+        let input_len = i32::try_from(input.len())
+            .map_err(|_| anyhow::anyhow!("Input of {} bytes exceeds guest address space", input.len()))?;
+        let input_ptr = alloc.call(&mut self.store, input_len)?;
+        
+        let exchanged = (|| {
+            memory.write(&mut self.store, input_ptr as u32 as usize, input)?;
+            
+            let results = match target.typed::<(i32, i32), (i32, i32)>(&self.store) {
+                Ok(f) => f.call(&mut self.store, (input_ptr, input_len)),
+                Err(_) => {
+                    let packed = target.typed::<(i32, i32), i64>(&self.store)
+                        .map_err(|_| anyhow::anyhow!(
+                            "Function {} must have type (i32, i32) -> (i32, i32) or (i32, i32) -> i64", func
+                        ))?;
+                    packed.call(&mut self.store, (input_ptr, input_len))
+                        .map(|p| ((p >> 32) as i32, p as i32))
+                }
+            };
+            let (out_ptr, out_len) = results?;
+            
+            if let Some(ctx) = ctx {
+                ctx.check_output_size(out_len as u32 as usize)?;
+            }
+            
+            // Check the guest's buffer before sizing a host allocation by it
+            let start = out_ptr as u32 as usize;
+            let len = out_len as u32 as usize;
+            if start.checked_add(len).is_none_or(|end| end > memory.data_size(&self.store)) {
+                anyhow::bail!("Function {} returned out-of-bounds buffer ({}, {})", func, out_ptr, out_len);
+            }
+            let mut output = vec![0u8; len];
+            memory.read(&self.store, start, &mut output)?;
+            Ok((output, out_ptr, out_len))
+        })();
+        
+        let (output, out_ptr, out_len) = match exchanged {
+            Ok(exchanged) => exchanged,
+            Err(e) => {
+                // Best effort: the guest may be left unusable by a trap
+                let _ = dealloc.call(&mut self.store, (input_ptr, input_len));
+                return Err(e);
+            }
+        };
+        
+        dealloc.call(&mut self.store, (input_ptr, input_len))?;
+        if out_ptr != input_ptr {
+            dealloc.call(&mut self.store, (out_ptr, out_len))?;
+        }
+        
+        Ok(output)
     }
 }
 
@@ -88,4 +417,255 @@ mod tests {
         let exports = vm.get_exports();
         assert!(exports.contains(&"test".to_string()));
     }
+
+    #[test]
+    #[allow(clippy::approx_constant)]
+    fn test_vm_float_results() {
+        let wasm = wat::parse_str(r#"
+            (module
+                (func (export "pi") (result f64)
+                    f64.const 3.14
+                )
+                (func (export "scale") (param f32 i32) (result f32 i32)
+                    local.get 0
+                    f32.const 2.5
+                    f32.mul
+                    local.get 1
+                )
+            )
+        "#).unwrap();
+        
+        let vm = VM::from_bytes(&wasm).unwrap();
+        assert_eq!(vm.execute("pi", &[]).unwrap(), vec![VmValue::F64(3.14)]);
+        assert_eq!(
+            vm.execute("scale", &[VmValue::F32(2.0), VmValue::I32(-7)]).unwrap(),
+            vec![VmValue::F32(5.0), VmValue::I32(-7)]
+        );
+        
+        assert!(vm.execute("scale", &[VmValue::F32(2.0)]).is_err(), "Arity is checked");
+        assert!(vm.execute_i64("pi", &[]).is_err(), "Float results aren't integers");
+    }
+
+    #[test]
+    fn test_vm_execute_i64() {
+        let wasm = wat::parse_str(r#"
+            (module
+                (func (export "add") (param i64 i64) (result i64)
+                    local.get 0
+                    local.get 1
+                    i64.add
+                )
+            )
+        "#).unwrap();
+        
+        let vm = VM::from_bytes(&wasm).unwrap();
+        assert_eq!(vm.execute_i64("add", &[40, 2]).unwrap(), vec![42]);
+    }
+
+    #[test]
+    fn test_vm_component_on_event() {
+        // Keeps events with an even sequence number
+        let wasm = wat::parse_str(r#"
+            (component
+                (core module $m
+                    (func (export "on-event") (param i32 i64) (result i32)
+                        local.get 1
+                        i64.const 2
+                        i64.rem_u
+                        i64.eqz
+                    )
+                )
+                (core instance $i (instantiate $m))
+                (func $on-event (param "source-id" u32) (param "seq-no" u64) (result bool)
+                    (canon lift (core func $i "on-event"))
+                )
+                (export "on-event" (func $on-event))
+            )
+        "#).unwrap();
+        assert!(is_component(&wasm));
+        
+        let vm = VM::from_bytes(&wasm).unwrap();
+        assert!(vm.is_component());
+        assert_eq!(vm.get_exports(), vec!["on-event".to_string()]);
+        assert!(vm.on_event(1, 42).unwrap());
+        assert!(!vm.on_event(1, 43).unwrap());
+        
+        assert!(vm.execute("on-event", &[]).is_err(), "Components have no core exports");
+        assert!(VM::from_component_bytes(&wat::parse_str("(module)").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_vm_module_on_event() {
+        let wasm = wat::parse_str(r#"
+            (module
+                (func (export "on_event") (param i32 i64) (result i32)
+                    local.get 0
+                    i32.const 7
+                    i32.ne
+                )
+            )
+        "#).unwrap();
+        assert!(!is_component(&wasm));
+        
+        let vm = VM::from_bytes(&wasm).unwrap();
+        assert!(!vm.is_component());
+        assert!(vm.on_event(1, 0).unwrap());
+        assert!(!vm.on_event(7, 0).unwrap());
+    }
+
+    #[test]
+    fn test_vm_deterministic_forbids_clock() {
+        let wasm = wat::parse_str(r#"
+            (module
+                (import "wasi_snapshot_preview1" "clock_time_get"
+                    (func $clock_time_get (param i32 i64 i32) (result i32)))
+                (memory (export "memory") 1)
+                (func (export "now") (result i32)
+                    i32.const 0
+                    i64.const 1
+                    i32.const 0
+                    call $clock_time_get
+                )
+            )
+        "#).unwrap();
+        
+        let vm = VM::deterministic(&wasm).unwrap();
+        assert!(vm.is_deterministic());
+        let err = vm.execute("now", &[]).unwrap_err();
+        assert!(format!("{:?}", err).contains("not allowed in deterministic mode"), "unexpected error: {:?}", err);
+    }
+
+    #[test]
+    fn test_vm_deterministic_runs_match() {
+        // sqrt(-1) is a NaN whose bit pattern is left to the platform
+        // unless NaNs are canonicalized
+        let wasm = wat::parse_str(r#"
+            (module
+                (func (export "nan_bits") (param f32) (result i32)
+                    local.get 0
+                    f32.sqrt
+                    i32.reinterpret_f32
+                )
+            )
+        "#).unwrap();
+        
+        let vm = VM::deterministic(&wasm).unwrap();
+        let first = vm.execute("nan_bits", &[VmValue::F32(-1.0)]).unwrap();
+        let second = vm.execute("nan_bits", &[VmValue::F32(-1.0)]).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first, vec![VmValue::I32(f32::NAN.to_bits() as i32)]);
+        
+        assert!(!VM::from_bytes(&wasm).unwrap().is_deterministic());
+    }
+
+    const ECHO_WAT: &str = r#"
+        (module
+            (memory (export "memory") 1)
+            (global $next (mut i32) (i32.const 1024))
+            (func (export "alloc") (param $len i32) (result i32)
+                (local $ptr i32)
+                global.get $next
+                local.set $ptr
+                global.get $next
+                local.get $len
+                i32.add
+                global.set $next
+                local.get $ptr
+            )
+            (global $freed (export "freed") (mut i32) (i32.const 0))
+            (func (export "dealloc") (param i32 i32)
+                global.get $freed
+                i32.const 1
+                i32.add
+                global.set $freed
+            )
+            ;; Returns the input buffer unchanged as (ptr, len)
+            (func (export "echo") (param i32 i32) (result i32 i32)
+                local.get 0
+                local.get 1
+            )
+            ;; Same, packed as (ptr << 32) | len
+            (func (export "echo_packed") (param i32 i32) (result i64)
+                local.get 0
+                i64.extend_i32_u
+                i64.const 32
+                i64.shl
+                local.get 1
+                i64.extend_i32_u
+                i64.or
+            )
+            (func (export "bad_sig") (param i32) (result i32)
+                local.get 0
+            )
+            ;; Claims a 4 GiB buffer
+            (func (export "huge") (param i32 i32) (result i32 i32)
+                local.get 0
+                i32.const -1
+            )
+        )
+    "#;
+
+    #[test]
+    fn test_vm_call_bytes_echo() {
+        let wasm = wat::parse_str(ECHO_WAT).unwrap();
+        let vm = VM::from_bytes(&wasm).unwrap();
+        let mut instance = vm.instance().unwrap();
+        
+        assert_eq!(instance.call_bytes("echo", b"hello zenith").unwrap(), b"hello zenith");
+        assert_eq!(instance.call_bytes("echo_packed", &[0, 1, 2, 255]).unwrap(), vec![0, 1, 2, 255]);
+        assert!(instance.call_bytes("echo", &[]).unwrap().is_empty());
+        
+        let err = instance.call_bytes("bad_sig", b"x").unwrap_err();
+        assert!(err.to_string().contains("must have type"));
+    }
+
+    #[test]
+    fn test_vm_call_bytes_output_limit() {
+        use crate::sandbox::{Sandbox, SandboxLimits};
+        
+        let wasm = wat::parse_str(ECHO_WAT).unwrap();
+        let mut instance = VM::from_bytes(&wasm).unwrap().instance().unwrap();
+        let ctx = Sandbox::new(SandboxLimits { max_output_bytes: 8, ..Default::default() }).create_context();
+        
+        assert_eq!(instance.call_bytes_in(&ctx, "echo", b"12345678").unwrap(), b"12345678");
+        
+        let err = instance.call_bytes_in(&ctx, "echo", b"123456789").unwrap_err();
+        assert!(err.to_string().contains("exceeds limit"));
+    }
+
+    #[test]
+    fn test_vm_call_bytes_out_of_bounds() {
+        let wasm = wat::parse_str(ECHO_WAT).unwrap();
+        let mut instance = VM::from_bytes(&wasm).unwrap().instance().unwrap();
+        let freed = instance.instance.get_global(&mut instance.store, "freed").unwrap();
+        
+        let err = instance.call_bytes("huge", b"data").unwrap_err();
+        assert!(err.to_string().contains("out-of-bounds"), "unexpected error: {}", err);
+        // The input buffer is released even though the call failed
+        assert_eq!(freed.get(&mut instance.store).i32(), Some(1));
+        
+        assert!(instance.call_bytes("bad_sig", b"x").is_err());
+        assert_eq!(freed.get(&mut instance.store).i32(), Some(2));
+    }
+
+    #[test]
+    fn test_vm_call_bytes_missing_exports() {
+        let wasm = wat::parse_str(r#"
+            (module
+                (memory (export "memory") 1)
+                (func (export "echo") (param i32 i32) (result i32 i32)
+                    local.get 0
+                    local.get 1
+                )
+            )
+        "#).unwrap();
+        
+        let vm = VM::from_bytes(&wasm).unwrap();
+        let err = vm.instance().unwrap().call_bytes("echo", b"data").unwrap_err();
+        assert!(err.to_string().contains("alloc"), "unexpected error: {}", err);
+        
+        let wasm = wat::parse_str(r#"(module (func (export "echo")))"#).unwrap();
+        let err = VM::from_bytes(&wasm).unwrap().instance().unwrap().call_bytes("echo", b"").unwrap_err();
+        assert!(err.to_string().contains("memory"));
+    }
 }
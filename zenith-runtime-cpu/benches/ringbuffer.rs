@@ -96,6 +96,8 @@ fn bench_memory_pool(c: &mut Criterion) {
                     initial_slabs: 64,
                     max_slabs: 256,
                     alignment: 64,
+                    auto_trim_ratio: None,
+                    numa_node: None,
                 };
                 let pool = MemoryPool::new(config).unwrap();
                 
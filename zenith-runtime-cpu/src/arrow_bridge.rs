@@ -0,0 +1,111 @@
+//! Arrow Bridge
+//!
+//! Turns Arrow `RecordBatch` columns into contiguous `f32` buffers that
+//! `SimdOps` and the preprocessing stages can work on directly.
+
+use arrow::array::{Array, AsArray, RecordBatch};
+use arrow::datatypes::{ArrowPrimitiveType, DataType, Float32Type, Float64Type, Int32Type};
+
+use crate::{Error, Result};
+
+/// Extract `column` as `f32`, filling nulls with `0.0`.
+///
+/// See [`record_batch_to_f32_with_fill`].
+pub fn record_batch_to_f32(batch: &RecordBatch, column: &str) -> Result<Vec<f32>> {
+    record_batch_to_f32_with_fill(batch, column, 0.0)
+}
+
+/// Extract `column` as a contiguous `f32` vector, one value per row.
+///
+/// Float32 is copied as is; Float64 and Int32 are cast (Float64 may lose
+/// precision, Int32 values above 2^24 are rounded). Nulls become
+/// `null_fill`. Other column types are rejected.
+pub fn record_batch_to_f32_with_fill(batch: &RecordBatch, column: &str, null_fill: f32) -> Result<Vec<f32>> {
+    let array = batch
+        .column_by_name(column)
+        .ok_or_else(|| Error::Schema(format!("no column named '{}'", column)))?;
+
+    match array.data_type() {
+        DataType::Float32 => Ok(collect::<Float32Type>(array.as_ref(), null_fill, |v| v)),
+        DataType::Float64 => Ok(collect::<Float64Type>(array.as_ref(), null_fill, |v| v as f32)),
+        DataType::Int32 => Ok(collect::<Int32Type>(array.as_ref(), null_fill, |v| v as f32)),
+        other => Err(Error::Schema(format!(
+            "column '{}' has type {}, expected Float32, Float64 or Int32",
+            column, other
+        ))),
+    }
+}
+
+fn collect<T: ArrowPrimitiveType>(
+    array: &dyn Array,
+    null_fill: f32,
+    cast: impl Fn(T::Native) -> f32,
+) -> Vec<f32> {
+    let values = array.as_primitive::<T>();
+    if values.null_count() == 0 {
+        return values.values().iter().map(|&v| cast(v)).collect();
+    }
+    values.iter().map(|v| v.map_or(null_fill, &cast)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Float32Array, Float64Array, Int32Array, StringArray};
+    use arrow::datatypes::{Field, Schema};
+    use std::sync::Arc;
+
+    fn test_batch() -> RecordBatch {
+        let schema = Schema::new(vec![
+            Field::new("f32", DataType::Float32, false),
+            Field::new("f64", DataType::Float64, true),
+            Field::new("i32", DataType::Int32, true),
+            Field::new("label", DataType::Utf8, false),
+        ]);
+        RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Float32Array::from(vec![0.5, 1.5, 2.5])),
+                Arc::new(Float64Array::from(vec![Some(1.25), None, Some(-3.0)])),
+                Arc::new(Int32Array::from(vec![Some(7), Some(-2), None])),
+                Arc::new(StringArray::from(vec!["a", "b", "c"])),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_extract_numeric_columns() {
+        let batch = test_batch();
+
+        assert_eq!(record_batch_to_f32(&batch, "f32").unwrap(), vec![0.5, 1.5, 2.5]);
+        assert_eq!(record_batch_to_f32(&batch, "f64").unwrap(), vec![1.25, 0.0, -3.0]);
+        assert_eq!(record_batch_to_f32(&batch, "i32").unwrap(), vec![7.0, -2.0, 0.0]);
+    }
+
+    #[test]
+    fn test_extract_null_fill() {
+        let batch = test_batch();
+
+        let filled = record_batch_to_f32_with_fill(&batch, "f64", f32::NAN).unwrap();
+        assert_eq!(filled[0], 1.25);
+        assert!(filled[1].is_nan());
+        assert_eq!(record_batch_to_f32_with_fill(&batch, "i32", -1.0).unwrap(), vec![7.0, -2.0, -1.0]);
+    }
+
+    #[test]
+    fn test_extract_sliced_batch() {
+        let batch = test_batch().slice(1, 2);
+        assert_eq!(record_batch_to_f32(&batch, "f32").unwrap(), vec![1.5, 2.5]);
+        assert_eq!(record_batch_to_f32(&batch, "i32").unwrap(), vec![-2.0, 0.0]);
+    }
+
+    #[test]
+    fn test_extract_errors() {
+        let batch = test_batch();
+
+        assert!(matches!(record_batch_to_f32(&batch, "missing"), Err(Error::Schema(_))));
+        let err = record_batch_to_f32(&batch, "label").unwrap_err();
+        assert!(err.to_string().contains("Utf8"), "{}", err);
+    }
+}
@@ -54,6 +54,7 @@
 #![warn(rustdoc::missing_crate_level_docs)]
 
 pub mod allocator;
+pub mod arrow_bridge;
 pub mod buffer;
 pub mod circuit_breaker;
 pub mod config;
@@ -120,6 +121,10 @@ pub enum Error {
     #[error("io_uring error: {0}")]
     IoUring(String),
     
+    /// Schema mismatch (missing column, unsupported column type)
+    #[error("Schema error: {0}")]
+    Schema(String),
+    
     /// Feature not yet implemented
     #[error("Not implemented: {0}")]
     NotImplemented(String),
@@ -9,8 +9,11 @@ use axum::{
     routing::get,
     response::IntoResponse,
     extract::State,
+    Json,
 };
+use tokio::sync::oneshot;
 use crate::TelemetryCollector;
+use crate::telemetry::TelemetrySnapshot;
 
 /// Metrics server configuration
 pub struct MetricsServerConfig {
@@ -32,22 +35,33 @@ struct MetricsState {
 }
 
 /// Start Prometheus metrics server
+///
+/// Runs until `shutdown` fires (or its sender is dropped), then stops
+/// accepting connections, drains in-flight requests and returns.
 pub async fn start_metrics_server(
     collector: Arc<TelemetryCollector>,
     config: MetricsServerConfig,
+    shutdown: oneshot::Receiver<()>,
 ) -> crate::Result<()> {
     let state = Arc::new(MetricsState { collector });
     
     let app = Router::new()
         .route("/metrics", get(metrics_handler))
+        .route("/metrics.json", get(metrics_json_handler))
         .route("/health", get(health_handler))
         .with_state(state);
     
     tracing::info!("Starting metrics server on {}", config.listen_addr);
     
     let listener = tokio::net::TcpListener::bind(config.listen_addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            // A dropped sender also counts as a shutdown request
+            let _ = shutdown.await;
+        })
+        .await?;
     
+    tracing::info!("Metrics server on {} stopped", config.listen_addr);
     Ok(())
 }
 
@@ -55,10 +69,19 @@ pub async fn start_metrics_server(
 async fn metrics_handler(
     State(state): State<Arc<MetricsState>>,
 ) -> impl IntoResponse {
-    let snapshot = state.collector.snapshot();
-    
-    // Format metrics in Prometheus format
-    format!(
+    render_prometheus(&state.collector.snapshot())
+}
+
+/// JSON metrics endpoint (same fields as the snapshot struct)
+async fn metrics_json_handler(
+    State(state): State<Arc<MetricsState>>,
+) -> Json<TelemetrySnapshot> {
+    Json(state.collector.snapshot())
+}
+
+/// Format a snapshot in the Prometheus text exposition format
+fn render_prometheus(snapshot: &TelemetrySnapshot) -> String {
+    let mut output = format!(
         r#"# HELP zenith_cpu_uptime_seconds Engine uptime in seconds
 # TYPE zenith_cpu_uptime_seconds gauge
 zenith_cpu_uptime_seconds {}
@@ -104,7 +127,24 @@ zenith_cpu_deallocations_total {}
         snapshot.max_latency_us,
         snapshot.allocations,
         snapshot.deallocations,
-    )
+    );
+    
+    output.push_str("\n# HELP zenith_cpu_latency_microseconds Event latency distribution in microseconds\n");
+    output.push_str("# TYPE zenith_cpu_latency_microseconds histogram\n");
+    for (bound, count) in &snapshot.latency_buckets {
+        output.push_str(&format!(
+            "zenith_cpu_latency_microseconds_bucket{{le=\"{}\"}} {}\n",
+            bound, count
+        ));
+    }
+    output.push_str(&format!(
+        "zenith_cpu_latency_microseconds_bucket{{le=\"+Inf\"}} {}\n",
+        snapshot.latency_count
+    ));
+    output.push_str(&format!("zenith_cpu_latency_microseconds_sum {}\n", snapshot.latency_sum_us));
+    output.push_str(&format!("zenith_cpu_latency_microseconds_count {}\n", snapshot.latency_count));
+    
+    output
 }
 
 /// Health check endpoint
@@ -121,4 +161,70 @@ mod tests {
         let config = MetricsServerConfig::default();
         assert_eq!(config.listen_addr.port(), 9090);
     }
+    
+    #[test]
+    fn test_prometheus_histogram_output() {
+        let collector = TelemetryCollector::new(1000);
+        for latency in [3, 40, 40, 700] {
+            collector.record_latency(latency);
+        }
+        
+        let output = render_prometheus(&collector.snapshot());
+        
+        assert!(output.contains("# TYPE zenith_cpu_latency_microseconds histogram"));
+        assert!(output.contains("zenith_cpu_latency_microseconds_bucket{le=\"5\"} 1\n"));
+        assert!(output.contains("zenith_cpu_latency_microseconds_bucket{le=\"50\"} 3\n"));
+        assert!(output.contains("zenith_cpu_latency_microseconds_bucket{le=\"1000\"} 4\n"));
+        assert!(output.contains("zenith_cpu_latency_microseconds_bucket{le=\"+Inf\"} 4\n"));
+        assert!(output.contains("zenith_cpu_latency_microseconds_sum 783\n"));
+        assert!(output.contains("zenith_cpu_latency_microseconds_count 4\n"));
+        
+        // Legacy gauges are still exported
+        assert!(output.contains("zenith_cpu_latency_avg_microseconds "));
+        assert!(output.contains("zenith_cpu_latency_max_microseconds 700"));
+    }
+    
+    #[tokio::test]
+    async fn test_metrics_server_graceful_shutdown() {
+        let collector = Arc::new(TelemetryCollector::new(1000));
+        let config = MetricsServerConfig {
+            listen_addr: "127.0.0.1:0".parse().unwrap(),
+        };
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        
+        let server = tokio::spawn(start_metrics_server(collector, config, shutdown_rx));
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        shutdown_tx.send(()).unwrap();
+        
+        let result = tokio::time::timeout(std::time::Duration::from_secs(5), server)
+            .await
+            .expect("metrics server should stop after shutdown signal")
+            .unwrap();
+        assert!(result.is_ok());
+    }
+    
+    #[tokio::test]
+    async fn test_metrics_json_round_trip() {
+        let collector = Arc::new(TelemetryCollector::new(1000));
+        collector.record_event(512);
+        collector.record_latency(42);
+        let state = Arc::new(MetricsState { collector: collector.clone() });
+        
+        let response = metrics_json_handler(State(state)).await.into_response();
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+        
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let decoded: TelemetrySnapshot = serde_json::from_slice(&body).unwrap();
+        
+        let expected = collector.snapshot();
+        assert_eq!(decoded.events_processed, 1);
+        assert_eq!(decoded.bytes_processed, 512);
+        assert_eq!(decoded.max_latency_us, 42);
+        assert_eq!(decoded.latency_buckets, expected.latency_buckets);
+        // uptime and rates depend on wall-clock time, compare everything else
+        assert_eq!(
+            TelemetrySnapshot { uptime_ms: 0, events_per_second: 0, throughput_mbps: 0, ..decoded },
+            TelemetrySnapshot { uptime_ms: 0, events_per_second: 0, throughput_mbps: 0, ..expected },
+        );
+    }
 }
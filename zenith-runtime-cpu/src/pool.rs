@@ -3,11 +3,15 @@
 //! High-performance memory pool with slab allocation.
 
 use std::alloc::{alloc, dealloc, Layout};
+use std::ops::{Deref, DerefMut};
 use std::ptr::NonNull;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use parking_lot::Mutex;
 
 use crate::Result;
+#[cfg(feature = "numa_cpp")]
+use crate::numa_ffi::NumaAllocator;
 
 /// Memory pool configuration
 #[derive(Debug, Clone)]
@@ -20,6 +24,13 @@ pub struct PoolConfig {
     pub max_slabs: usize,
     /// Alignment requirement
     pub alignment: usize,
+    /// Auto-trim back to `initial_slabs` free slabs when the free fraction
+    /// of the pool exceeds this ratio after a deallocation (`None` disables)
+    pub auto_trim_ratio: Option<f64>,
+    /// NUMA node to place slabs on. Needs the `numa_cpp` feature; without
+    /// it, or if the node can't be used, slabs come from the default
+    /// allocator.
+    pub numa_node: Option<usize>,
 }
 
 impl Default for PoolConfig {
@@ -29,19 +40,40 @@ impl Default for PoolConfig {
             initial_slabs: 16,
             max_slabs: 1024,
             alignment: 64, // Cache line aligned
+            auto_trim_ratio: None,
+            numa_node: None,
         }
     }
 }
 
+/// Where a slab's memory came from, so it is freed the same way
+enum Backing {
+    Global(Layout),
+    #[cfg(feature = "numa_cpp")]
+    Numa(NumaAllocator, usize),
+}
+
 /// A slab of memory
 struct Slab {
     ptr: NonNull<u8>,
-    layout: Layout,
+    backing: Backing,
     in_use: bool,
+    /// Generation of the buffer currently handed out from this slab
+    generation: u64,
 }
 
 impl Slab {
-    fn new(size: usize, align: usize) -> Option<Self> {
+    fn new(size: usize, align: usize, numa_node: Option<usize>) -> Option<Self> {
+        #[cfg(feature = "numa_cpp")]
+        if let Some(node) = numa_node {
+            if let Some(slab) = Self::new_on_node(size, align, node) {
+                return Some(slab);
+            }
+            tracing::warn!("NUMA allocation on node {} failed, using default allocator", node);
+        }
+        #[cfg(not(feature = "numa_cpp"))]
+        let _ = numa_node;
+        
         let layout = Layout::from_size_align(size, align).ok()?;
         
         let ptr = unsafe { alloc(layout) };
@@ -49,8 +81,26 @@ impl Slab {
         
         Some(Self {
             ptr,
-            layout,
+            backing: Backing::Global(layout),
             in_use: false,
+            generation: 0,
+        })
+    }
+    
+    #[cfg(feature = "numa_cpp")]
+    fn new_on_node(size: usize, align: usize, node: usize) -> Option<Self> {
+        // libnuma hands out whole pages, which covers any smaller alignment
+        if !align.is_power_of_two() || align > 4096 {
+            return None;
+        }
+        let allocator = NumaAllocator::new(i32::try_from(node).ok()?).ok()?;
+        let ptr = unsafe { allocator.alloc(size) }.ok()?;
+        
+        Some(Self {
+            ptr: NonNull::new(ptr)?,
+            backing: Backing::Numa(allocator, size),
+            in_use: false,
+            generation: 0,
         })
     }
     
@@ -59,31 +109,47 @@ impl Slab {
     }
 }
 
+// Safety: a Slab exclusively owns its allocation; access to it is
+// serialized by the pool's mutex
+unsafe impl Send for Slab {}
+
 impl Drop for Slab {
     fn drop(&mut self) {
-        unsafe {
-            dealloc(self.ptr.as_ptr(), self.layout);
+        match &self.backing {
+            Backing::Global(layout) => unsafe { dealloc(self.ptr.as_ptr(), *layout) },
+            #[cfg(feature = "numa_cpp")]
+            Backing::Numa(allocator, size) => unsafe { allocator.free(self.ptr.as_ptr(), *size) },
         }
     }
 }
 
 /// Thread-safe memory pool
+///
+/// Slabs live in fixed slots so a `PoolBuffer`'s index stays valid when
+/// other slabs are trimmed; a trimmed slot is `None` until reused.
 pub struct MemoryPool {
     config: PoolConfig,
-    slabs: Mutex<Vec<Slab>>,
+    slabs: Mutex<Vec<Option<Slab>>>,
     allocated: AtomicUsize,
     high_water_mark: AtomicUsize,
+    released: AtomicUsize,
+    /// Source of buffer generations; unique per allocation across all slots
+    next_generation: AtomicU64,
+    stale_returns: AtomicUsize,
+    /// Node slabs are bound to, if `config.numa_node` could be honored
+    numa_node: Option<usize>,
 }
 
 impl MemoryPool {
     /// Create a new memory pool
     pub fn new(config: PoolConfig) -> Result<Self> {
+        let numa_node = Self::resolve_numa_node(config.numa_node);
         let mut slabs = Vec::with_capacity(config.max_slabs);
         
         // Pre-allocate initial slabs
         for _ in 0..config.initial_slabs {
-            if let Some(slab) = Slab::new(config.slab_size, config.alignment) {
-                slabs.push(slab);
+            if let Some(slab) = Slab::new(config.slab_size, config.alignment, numa_node) {
+                slabs.push(Some(slab));
             }
         }
         
@@ -92,17 +158,44 @@ impl MemoryPool {
             slabs: Mutex::new(slabs),
             allocated: AtomicUsize::new(0),
             high_water_mark: AtomicUsize::new(0),
+            released: AtomicUsize::new(0),
+            next_generation: AtomicU64::new(1),
+            stale_returns: AtomicUsize::new(0),
+            numa_node,
         })
     }
     
+    /// The requested node if slabs can be bound to it, otherwise `None`
+    fn resolve_numa_node(requested: Option<usize>) -> Option<usize> {
+        let node = requested?;
+        
+        #[cfg(feature = "numa_cpp")]
+        match i32::try_from(node).map(NumaAllocator::new) {
+            Ok(Ok(_)) => return Some(node),
+            Ok(Err(e)) => tracing::warn!("Cannot bind pool to NUMA node {}: {:?}", node, e),
+            Err(_) => tracing::warn!("NUMA node {} out of range", node),
+        }
+        #[cfg(not(feature = "numa_cpp"))]
+        tracing::debug!("numa_cpp feature disabled, ignoring pool NUMA node {}", node);
+        
+        None
+    }
+    
+    /// NUMA node slabs are allocated on, if binding is in effect
+    pub fn numa_node(&self) -> Option<usize> {
+        self.numa_node
+    }
+    
     /// Allocate a buffer from the pool
     pub fn allocate(&self) -> Option<PoolBuffer> {
         let mut slabs = self.slabs.lock();
         
         // Find a free slab
         for (idx, slab) in slabs.iter_mut().enumerate() {
+            let Some(slab) = slab else { continue };
             if !slab.in_use {
                 slab.in_use = true;
+                slab.generation = self.next_generation.fetch_add(1, Ordering::Relaxed);
                 self.allocated.fetch_add(1, Ordering::Relaxed);
                 
                 // Update high water mark
@@ -121,17 +214,28 @@ impl MemoryPool {
                     ptr: slab.as_ptr(),
                     size: self.config.slab_size,
                     pool_idx: idx,
+                    generation: slab.generation,
                 });
             }
         }
         
-        // No free slab, try to allocate new one
-        if slabs.len() < self.config.max_slabs {
-            if let Some(mut slab) = Slab::new(self.config.slab_size, self.config.alignment) {
+        // No free slab, try to allocate new one (reusing a trimmed slot if any)
+        if Self::live_slabs(&slabs) < self.config.max_slabs {
+            if let Some(mut slab) = Slab::new(self.config.slab_size, self.config.alignment, self.numa_node) {
                 slab.in_use = true;
+                slab.generation = self.next_generation.fetch_add(1, Ordering::Relaxed);
                 let ptr = slab.as_ptr();
-                let idx = slabs.len();
-                slabs.push(slab);
+                let generation = slab.generation;
+                let idx = match slabs.iter().position(Option::is_none) {
+                    Some(idx) => {
+                        slabs[idx] = Some(slab);
+                        idx
+                    }
+                    None => {
+                        slabs.push(Some(slab));
+                        slabs.len() - 1
+                    }
+                };
                 
                 self.allocated.fetch_add(1, Ordering::Relaxed);
                 
@@ -139,6 +243,7 @@ impl MemoryPool {
                     ptr,
                     size: self.config.slab_size,
                     pool_idx: idx,
+                    generation,
                 });
             }
         }
@@ -146,14 +251,83 @@ impl MemoryPool {
         None
     }
     
+    /// Allocate a buffer that returns itself to the pool when dropped
+    pub fn allocate_guarded(self: &Arc<Self>) -> Option<PooledGuard> {
+        let buffer = self.allocate()?;
+        Some(PooledGuard {
+            pool: Arc::clone(self),
+            buffer: Some(buffer),
+        })
+    }
+    
     /// Return a buffer to the pool
+    ///
+    /// A buffer that was already returned, or whose slab has since been
+    /// handed to someone else or trimmed, is ignored and logged.
     pub fn deallocate(&self, buffer: PoolBuffer) {
         let mut slabs = self.slabs.lock();
         
-        if buffer.pool_idx < slabs.len() {
-            slabs[buffer.pool_idx].in_use = false;
-            self.allocated.fetch_sub(1, Ordering::Relaxed);
+        match slabs.get_mut(buffer.pool_idx) {
+            Some(Some(slab)) if slab.in_use
+                && slab.generation == buffer.generation
+                && slab.as_ptr() == buffer.ptr =>
+            {
+                slab.in_use = false;
+                self.allocated.fetch_sub(1, Ordering::Relaxed);
+            }
+            _ => {
+                self.stale_returns.fetch_add(1, Ordering::Relaxed);
+                tracing::warn!(
+                    "Ignoring stale return of pool buffer (slot {}, generation {})",
+                    buffer.pool_idx, buffer.generation
+                );
+                return;
+            }
+        }
+        
+        if let Some(ratio) = self.config.auto_trim_ratio {
+            let live = Self::live_slabs(&slabs);
+            let free = live - self.allocated.load(Ordering::Relaxed).min(live);
+            if live > 0 && free as f64 > ratio * live as f64 {
+                self.trim_locked(&mut slabs, self.config.initial_slabs);
+            }
+        }
+    }
+    
+    /// Release free slabs beyond `keep`, returning how many were dropped.
+    ///
+    /// Slabs backing outstanding buffers are never touched.
+    pub fn trim(&self, keep: usize) -> usize {
+        let mut slabs = self.slabs.lock();
+        self.trim_locked(&mut slabs, keep)
+    }
+    
+    fn trim_locked(&self, slabs: &mut Vec<Option<Slab>>, keep: usize) -> usize {
+        let mut free_seen = 0;
+        let mut dropped = 0;
+        
+        // Keep the lowest-indexed free slabs so allocation stays compact
+        for slot in slabs.iter_mut() {
+            if matches!(slot, Some(slab) if !slab.in_use) {
+                free_seen += 1;
+                if free_seen > keep {
+                    *slot = None;
+                    dropped += 1;
+                }
+            }
+        }
+        
+        // Trailing empty slots can go; no outstanding buffer refers to them
+        while matches!(slabs.last(), Some(None)) {
+            slabs.pop();
         }
+        
+        self.released.fetch_add(dropped, Ordering::Relaxed);
+        dropped
+    }
+    
+    fn live_slabs(slabs: &[Option<Slab>]) -> usize {
+        slabs.iter().filter(|s| s.is_some()).count()
     }
     
     /// Get current allocation count
@@ -168,18 +342,21 @@ impl MemoryPool {
     
     /// Get total capacity
     pub fn capacity(&self) -> usize {
-        self.slabs.lock().len()
+        Self::live_slabs(&self.slabs.lock())
     }
     
     /// Get statistics
     pub fn stats(&self) -> PoolStats {
-        let slabs = self.slabs.lock();
+        let live = Self::live_slabs(&self.slabs.lock());
         PoolStats {
-            total_slabs: slabs.len(),
+            total_slabs: live,
             allocated_slabs: self.allocated.load(Ordering::Relaxed),
             slab_size: self.config.slab_size,
-            total_memory: slabs.len() * self.config.slab_size,
+            total_memory: live * self.config.slab_size,
             high_water_mark: self.high_water_mark.load(Ordering::Relaxed),
+            released_slabs: self.released.load(Ordering::Relaxed),
+            stale_returns: self.stale_returns.load(Ordering::Relaxed),
+            numa_node: self.numa_node,
         }
     }
 }
@@ -189,6 +366,7 @@ pub struct PoolBuffer {
     ptr: *mut u8,
     size: usize,
     pool_idx: usize,
+    generation: u64,
 }
 
 impl PoolBuffer {
@@ -216,6 +394,42 @@ impl PoolBuffer {
 // Safety: PoolBuffer is safe to send between threads
 unsafe impl Send for PoolBuffer {}
 
+/// A `PoolBuffer` that is returned to its pool on drop
+pub struct PooledGuard {
+    pool: Arc<MemoryPool>,
+    /// Always `Some` until dropped or detached
+    buffer: Option<PoolBuffer>,
+}
+
+impl PooledGuard {
+    /// Detach the buffer; the caller must then `deallocate` it manually
+    pub fn into_inner(mut self) -> PoolBuffer {
+        self.buffer.take().expect("PooledGuard buffer already taken")
+    }
+}
+
+impl Deref for PooledGuard {
+    type Target = PoolBuffer;
+    
+    fn deref(&self) -> &PoolBuffer {
+        self.buffer.as_ref().expect("PooledGuard buffer already taken")
+    }
+}
+
+impl DerefMut for PooledGuard {
+    fn deref_mut(&mut self) -> &mut PoolBuffer {
+        self.buffer.as_mut().expect("PooledGuard buffer already taken")
+    }
+}
+
+impl Drop for PooledGuard {
+    fn drop(&mut self) {
+        if let Some(buffer) = self.buffer.take() {
+            self.pool.deallocate(buffer);
+        }
+    }
+}
+
 /// Pool statistics
 #[derive(Debug, Clone)]
 pub struct PoolStats {
@@ -229,6 +443,12 @@ pub struct PoolStats {
     pub total_memory: usize,
     /// Maximum concurrent allocations
     pub high_water_mark: usize,
+    /// Slabs released back to the allocator by trimming
+    pub released_slabs: usize,
+    /// Deallocations ignored because the buffer was already returned
+    pub stale_returns: usize,
+    /// NUMA node slabs are bound to (`None` for the default allocator)
+    pub numa_node: Option<usize>,
 }
 
 #[cfg(test)]
@@ -242,6 +462,8 @@ mod tests {
             initial_slabs: 4,
             max_slabs: 16,
             alignment: 64,
+            auto_trim_ratio: None,
+            numa_node: None,
         };
         
         let pool = MemoryPool::new(config).unwrap();
@@ -256,6 +478,8 @@ mod tests {
             initial_slabs: 4,
             max_slabs: 16,
             alignment: 64,
+            auto_trim_ratio: None,
+            numa_node: None,
         };
         
         let pool = MemoryPool::new(config).unwrap();
@@ -274,6 +498,119 @@ mod tests {
         assert_eq!(pool.allocated_count(), 0);
     }
     
+    /// A second handle to the same slab, as a buggy caller might keep
+    fn alias(buf: &PoolBuffer) -> PoolBuffer {
+        PoolBuffer {
+            ptr: buf.ptr,
+            size: buf.size,
+            pool_idx: buf.pool_idx,
+            generation: buf.generation,
+        }
+    }
+    
+    #[test]
+    fn test_pool_double_free_ignored() {
+        let pool = MemoryPool::new(PoolConfig {
+            slab_size: 1024,
+            initial_slabs: 1,
+            max_slabs: 1,
+            ..Default::default()
+        }).unwrap();
+        
+        let buf = pool.allocate().unwrap();
+        let first_copy = alias(&buf);
+        let second_copy = alias(&buf);
+        pool.deallocate(buf);
+        assert_eq!(pool.allocated_count(), 0);
+        
+        pool.deallocate(first_copy);
+        assert_eq!(pool.allocated_count(), 0);
+        assert_eq!(pool.stats().stale_returns, 1);
+        
+        // Once the slab is reused, an old handle must not free the new owner's buffer
+        let owner = pool.allocate().unwrap();
+        assert_eq!(owner.as_ptr(), second_copy.as_ptr());
+        pool.deallocate(second_copy);
+        assert_eq!(pool.allocated_count(), 1);
+        assert!(pool.allocate().is_none(), "Slab must still belong to its owner");
+        assert_eq!(pool.stats().stale_returns, 2);
+        
+        pool.deallocate(owner);
+        assert_eq!(pool.allocated_count(), 0);
+    }
+    
+    #[test]
+    fn test_pool_guard_returns_buffer() {
+        let pool = Arc::new(MemoryPool::new(PoolConfig {
+            slab_size: 1024,
+            initial_slabs: 2,
+            max_slabs: 2,
+            ..Default::default()
+        }).unwrap());
+        
+        {
+            let mut guard = pool.allocate_guarded().unwrap();
+            guard.as_mut_slice()[0] = 7;
+            assert_eq!(guard.as_slice()[0], 7);
+            assert_eq!(pool.allocated_count(), 1);
+        }
+        assert_eq!(pool.allocated_count(), 0);
+        
+        // Guards can move to other threads
+        let pool_clone = Arc::clone(&pool);
+        std::thread::spawn(move || drop(pool_clone.allocate_guarded().unwrap()))
+            .join()
+            .unwrap();
+        assert_eq!(pool.allocated_count(), 0);
+        
+        // Early return paths release too
+        let fill = |pool: &Arc<MemoryPool>| -> Option<()> {
+            let _a = pool.allocate_guarded()?;
+            let _b = pool.allocate_guarded()?;
+            let _c = pool.allocate_guarded()?; // pool exhausted
+            Some(())
+        };
+        assert!(fill(&pool).is_none());
+        assert_eq!(pool.allocated_count(), 0);
+        
+        // A detached buffer stays allocated until deallocated
+        let buffer = pool.allocate_guarded().unwrap().into_inner();
+        assert_eq!(pool.allocated_count(), 1);
+        pool.deallocate(buffer);
+        assert_eq!(pool.allocated_count(), 0);
+    }
+    
+    #[test]
+    fn test_pool_numa_node() {
+        let pool = MemoryPool::new(PoolConfig {
+            slab_size: 4096,
+            initial_slabs: 2,
+            max_slabs: 4,
+            numa_node: Some(0),
+            ..Default::default()
+        }).unwrap();
+        
+        // Node 0 exists wherever libnuma works; otherwise we fall back
+        if cfg!(feature = "numa_cpp") && pool.numa_node().is_some() {
+            assert_eq!(pool.stats().numa_node, Some(0));
+        } else {
+            assert_eq!(pool.stats().numa_node, None);
+        }
+        
+        let mut buf = pool.allocate().unwrap();
+        buf.as_mut_slice().fill(0xAB);
+        assert!(buf.as_slice().iter().all(|&b| b == 0xAB));
+        pool.deallocate(buf);
+        
+        // A node that doesn't exist always falls back
+        let pool = MemoryPool::new(PoolConfig {
+            numa_node: Some(4096),
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(pool.stats().numa_node, None);
+        assert_eq!(pool.capacity(), PoolConfig::default().initial_slabs);
+    }
+    
     #[test]
     fn test_pool_write_read() {
         let config = PoolConfig::default();
@@ -298,6 +635,8 @@ mod tests {
             initial_slabs: 4,
             max_slabs: 16,
             alignment: 64,
+            auto_trim_ratio: None,
+            numa_node: None,
         };
         
         let pool = MemoryPool::new(config).unwrap();
@@ -310,4 +649,71 @@ mod tests {
         assert_eq!(stats.high_water_mark, 2);
         assert_eq!(stats.slab_size, 1024);
     }
+    
+    #[test]
+    fn test_pool_trim() {
+        let config = PoolConfig {
+            slab_size: 1024,
+            initial_slabs: 2,
+            max_slabs: 16,
+            alignment: 64,
+            auto_trim_ratio: None,
+            numa_node: None,
+        };
+        
+        let pool = MemoryPool::new(config).unwrap();
+        
+        // Burst grows the pool to 10 slabs
+        let mut bufs: Vec<_> = (0..10).map(|_| pool.allocate().unwrap()).collect();
+        assert_eq!(pool.capacity(), 10);
+        
+        // Keep the middle buffer outstanding, free everything else
+        let mut held = bufs.remove(5);
+        held.as_mut_slice()[0] = 0xAB;
+        for buf in bufs {
+            pool.deallocate(buf);
+        }
+        
+        assert_eq!(pool.trim(2), 7);
+        assert_eq!(pool.capacity(), 3);
+        assert_eq!(pool.allocated_count(), 1);
+        assert_eq!(pool.stats().total_memory, 3 * 1024);
+        assert_eq!(pool.stats().released_slabs, 7);
+        
+        // The outstanding buffer's slab survived and still deallocates cleanly
+        assert_eq!(held.as_slice()[0], 0xAB);
+        pool.deallocate(held);
+        assert_eq!(pool.allocated_count(), 0);
+        
+        // Trimmed slots are reused when the pool grows again
+        let again: Vec<_> = (0..5).map(|_| pool.allocate().unwrap()).collect();
+        assert_eq!(pool.capacity(), 5);
+        for buf in again {
+            pool.deallocate(buf);
+        }
+    }
+    
+    #[test]
+    fn test_pool_auto_trim() {
+        let config = PoolConfig {
+            slab_size: 1024,
+            initial_slabs: 2,
+            max_slabs: 16,
+            alignment: 64,
+            auto_trim_ratio: Some(0.75),
+            numa_node: None,
+        };
+        
+        let pool = MemoryPool::new(config).unwrap();
+        let bufs: Vec<_> = (0..8).map(|_| pool.allocate().unwrap()).collect();
+        assert_eq!(pool.capacity(), 8);
+        
+        for buf in bufs {
+            pool.deallocate(buf);
+        }
+        
+        // Once more than 75% of slabs sat idle the pool shrank back to its initial size
+        assert!(pool.capacity() <= 3, "capacity {} not trimmed", pool.capacity());
+        assert_eq!(pool.allocated_count(), 0);
+    }
 }
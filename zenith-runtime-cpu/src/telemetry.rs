@@ -3,8 +3,15 @@
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
+use serde::{Deserialize, Serialize};
 use tracing::debug;
 
+/// Upper bounds (inclusive, microseconds) of the latency histogram buckets.
+/// Samples above the last bound only land in the implicit `+Inf` bucket.
+pub const LATENCY_BUCKETS_US: [u64; 14] = [
+    5, 10, 25, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 25_000, 50_000, 100_000,
+];
+
 /// Telemetry collector for runtime metrics
 pub struct TelemetryCollector {
     running: Arc<AtomicBool>,
@@ -21,6 +28,8 @@ pub struct TelemetryCollector {
     latency_sum: AtomicU64,
     latency_count: AtomicU64,
     latency_max: AtomicU64,
+    // Per-bucket (non-cumulative) counts, indexed like LATENCY_BUCKETS_US
+    latency_buckets: [AtomicU64; LATENCY_BUCKETS_US.len()],
 }
 
 impl TelemetryCollector {
@@ -37,6 +46,7 @@ impl TelemetryCollector {
             latency_sum: AtomicU64::new(0),
             latency_count: AtomicU64::new(0),
             latency_max: AtomicU64::new(0),
+            latency_buckets: std::array::from_fn(|_| AtomicU64::new(0)),
         }
     }
     
@@ -63,6 +73,10 @@ impl TelemetryCollector {
         self.latency_sum.fetch_add(latency_us, Ordering::Relaxed);
         self.latency_count.fetch_add(1, Ordering::Relaxed);
         
+        if let Some(idx) = LATENCY_BUCKETS_US.iter().position(|&bound| latency_us <= bound) {
+            self.latency_buckets[idx].fetch_add(1, Ordering::Relaxed);
+        }
+        
         // Update max latency (compare-and-swap loop)
         loop {
             let current_max = self.latency_max.load(Ordering::Relaxed);
@@ -98,6 +112,16 @@ impl TelemetryCollector {
         let latency_count = self.latency_count.load(Ordering::Relaxed);
         let latency_sum = self.latency_sum.load(Ordering::Relaxed);
         
+        let mut cumulative = 0;
+        let latency_buckets = LATENCY_BUCKETS_US
+            .iter()
+            .zip(self.latency_buckets.iter())
+            .map(|(&bound, count)| {
+                cumulative += count.load(Ordering::Relaxed);
+                (bound, cumulative)
+            })
+            .collect();
+        
         TelemetrySnapshot {
             uptime_ms,
             events_processed: events,
@@ -120,6 +144,9 @@ impl TelemetryCollector {
             max_latency_us: self.latency_max.load(Ordering::Relaxed),
             allocations: self.allocations.load(Ordering::Relaxed),
             deallocations: self.deallocations.load(Ordering::Relaxed),
+            latency_buckets,
+            latency_sum_us: latency_sum,
+            latency_count,
         }
     }
     
@@ -130,13 +157,16 @@ impl TelemetryCollector {
         self.latency_sum.store(0, Ordering::Relaxed);
         self.latency_count.store(0, Ordering::Relaxed);
         self.latency_max.store(0, Ordering::Relaxed);
+        for bucket in &self.latency_buckets {
+            bucket.store(0, Ordering::Relaxed);
+        }
         self.allocations.store(0, Ordering::Relaxed);
         self.deallocations.store(0, Ordering::Relaxed);
     }
 }
 
 /// Snapshot of telemetry metrics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TelemetrySnapshot {
     /// Uptime in milliseconds
     pub uptime_ms: u64,
@@ -156,6 +186,12 @@ pub struct TelemetrySnapshot {
     pub allocations: u64,
     /// Total deallocations
     pub deallocations: u64,
+    /// Cumulative latency histogram as `(upper_bound_us, count)` pairs
+    pub latency_buckets: Vec<(u64, u64)>,
+    /// Sum of all recorded latencies in microseconds
+    pub latency_sum_us: u64,
+    /// Number of recorded latencies (the `+Inf` bucket count)
+    pub latency_count: u64,
 }
 
 impl std::fmt::Display for TelemetrySnapshot {
@@ -192,4 +228,32 @@ mod tests {
         assert_eq!(snapshot.avg_latency_us, 75);
         assert_eq!(snapshot.max_latency_us, 100);
     }
+    
+    #[test]
+    fn test_latency_histogram_buckets() {
+        let collector = TelemetryCollector::new(1000);
+        
+        // 3 samples <= 5us, 2 in (50, 100], 1 in (1ms, 2.5ms], 1 above all bounds
+        for latency in [1, 5, 3, 60, 100, 2_000, 500_000] {
+            collector.record_latency(latency);
+        }
+        
+        let snapshot = collector.snapshot();
+        let bucket = |bound: u64| {
+            snapshot.latency_buckets.iter().find(|(b, _)| *b == bound).unwrap().1
+        };
+        
+        assert_eq!(snapshot.latency_buckets.len(), LATENCY_BUCKETS_US.len());
+        assert_eq!(bucket(5), 3);
+        assert_eq!(bucket(50), 3);
+        assert_eq!(bucket(100), 5);
+        assert_eq!(bucket(1_000), 5);
+        assert_eq!(bucket(2_500), 6);
+        assert_eq!(bucket(100_000), 6);
+        assert_eq!(snapshot.latency_count, 7);
+        assert_eq!(snapshot.latency_sum_us, 1 + 5 + 3 + 60 + 100 + 2_000 + 500_000);
+        
+        collector.reset();
+        assert!(collector.snapshot().latency_buckets.iter().all(|(_, c)| *c == 0));
+    }
 }
@@ -46,14 +46,43 @@ impl ExecutionProvider {
     }
 }
 
+/// Upper bound accepted for intra-/inter-op thread counts
+pub const MAX_SESSION_THREADS: usize = 1024;
+
+/// ONNX Runtime graph optimization level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphOptimizationLevel {
+    /// No graph optimizations
+    Disable,
+    /// Constant folding and redundant node elimination
+    Basic,
+    /// Basic plus complex node fusions
+    Extended,
+    /// All optimizations including layout transforms
+    All,
+}
+
+impl GraphOptimizationLevel {
+    /// Map the numeric level (0-3) used by `OnnxConfig::optimization_level`
+    pub fn from_level(level: u32) -> Option<Self> {
+        match level {
+            0 => Some(Self::Disable),
+            1 => Some(Self::Basic),
+            2 => Some(Self::Extended),
+            3 => Some(Self::All),
+            _ => None,
+        }
+    }
+}
+
 /// ONNX session configuration
 #[derive(Debug, Clone)]
 pub struct OnnxConfig {
     /// Execution providers in priority order
     pub providers: Vec<ExecutionProvider>,
-    /// Number of intra-op threads
+    /// Number of intra-op threads (0 = ONNX Runtime default)
     pub intra_op_threads: usize,
-    /// Number of inter-op threads
+    /// Number of inter-op threads (0 = ONNX Runtime default)
     pub inter_op_threads: usize,
     /// Enable memory arena
     pub enable_mem_arena: bool,
@@ -79,6 +108,43 @@ impl Default for OnnxConfig {
     }
 }
 
+impl OnnxConfig {
+    /// Graph optimization level, or `None` if `optimization_level` is out of range
+    pub fn graph_optimization_level(&self) -> Option<GraphOptimizationLevel> {
+        GraphOptimizationLevel::from_level(self.optimization_level)
+    }
+    
+    /// Check thread counts and optimization level are within range
+    pub fn validate(&self) -> Result<(), OnnxError> {
+        for (name, threads) in [("intra_op_threads", self.intra_op_threads), ("inter_op_threads", self.inter_op_threads)] {
+            if threads > MAX_SESSION_THREADS {
+                return Err(OnnxError::InvalidConfig(format!(
+                    "{} = {} exceeds maximum of {}", name, threads, MAX_SESSION_THREADS
+                )));
+            }
+        }
+        
+        if self.graph_optimization_level().is_none() {
+            return Err(OnnxError::InvalidConfig(format!(
+                "optimization_level = {} must be in 0..=3", self.optimization_level
+            )));
+        }
+        
+        Ok(())
+    }
+}
+
+/// Session options resolved from `OnnxConfig` and applied at session creation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionOptions {
+    /// Intra-op thread count, `None` leaves the runtime default
+    pub intra_op_threads: Option<usize>,
+    /// Inter-op thread count, `None` leaves the runtime default
+    pub inter_op_threads: Option<usize>,
+    /// Graph optimization level
+    pub graph_optimization_level: GraphOptimizationLevel,
+}
+
 /// Tensor shape information
 #[derive(Debug, Clone)]
 pub struct TensorInfo {
@@ -119,6 +185,7 @@ impl TensorType {
 pub struct OnnxSession {
     model_path: String,
     config: OnnxConfig,
+    options: SessionOptions,
     input_info: Vec<TensorInfo>,
     output_info: Vec<TensorInfo>,
     loaded: bool,
@@ -132,6 +199,22 @@ impl OnnxSession {
             return Err(OnnxError::ModelNotFound(model_path.to_string()));
         }
         
+        config.validate()?;
+        let non_default = |threads: usize| (threads > 0).then_some(threads);
+        let options = SessionOptions {
+            intra_op_threads: non_default(config.intra_op_threads),
+            inter_op_threads: non_default(config.inter_op_threads),
+            graph_optimization_level: config.graph_optimization_level().unwrap_or(GraphOptimizationLevel::All),
+        };
+        
+        let cores = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        if config.intra_op_threads + config.inter_op_threads > cores {
+            tracing::debug!(
+                "ONNX session requests {} intra + {} inter-op threads on {} cores; expect oversubscription",
+                config.intra_op_threads, config.inter_op_threads, cores
+            );
+        }
+        
         // Parse model metadata (placeholder - real impl would use onnxruntime-rs)
         let input_info = vec![TensorInfo {
             name: "input".to_string(),
@@ -148,6 +231,7 @@ impl OnnxSession {
         Ok(Self {
             model_path: model_path.to_string(),
             config,
+            options,
             input_info,
             output_info,
             loaded: true,
@@ -182,6 +266,11 @@ impl OnnxSession {
         Ok(vec![vec![0.0f32; output_size]])
     }
     
+    /// Session options applied when the session was created
+    pub fn session_options(&self) -> &SessionOptions {
+        &self.options
+    }
+    
     /// Get model path
     pub fn model_path(&self) -> &str {
         &self.model_path
@@ -201,9 +290,15 @@ impl OnnxSession {
 /// ONNX error types
 #[derive(Debug)]
 pub enum OnnxError {
+    /// Model file does not exist
     ModelNotFound(String),
+    /// Session was not loaded
     SessionNotLoaded,
+    /// Inputs rejected by the session
     InvalidInput(String),
+    /// Configuration out of range
+    InvalidConfig(String),
+    /// Error raised by the runtime
     RuntimeError(String),
 }
 
@@ -213,6 +308,7 @@ impl std::fmt::Display for OnnxError {
             Self::ModelNotFound(p) => write!(f, "Model not found: {}", p),
             Self::SessionNotLoaded => write!(f, "Session not loaded"),
             Self::InvalidInput(msg) => write!(f, "Invalid input: {}", msg),
+            Self::InvalidConfig(msg) => write!(f, "Invalid config: {}", msg),
             Self::RuntimeError(msg) => write!(f, "Runtime error: {}", msg),
         }
     }
@@ -320,6 +416,32 @@ mod tests {
         assert!(!config.providers.is_empty());
     }
     
+    #[test]
+    fn test_onnx_session_thread_options() {
+        let model = tempfile::NamedTempFile::new().unwrap();
+        let path = model.path().to_str().unwrap();
+        
+        let config = OnnxConfig {
+            intra_op_threads: 2,
+            inter_op_threads: 0,
+            optimization_level: 1,
+            ..Default::default()
+        };
+        let session = OnnxSession::new(path, config).unwrap();
+        assert_eq!(session.session_options(), &SessionOptions {
+            intra_op_threads: Some(2),
+            inter_op_threads: None,
+            graph_optimization_level: GraphOptimizationLevel::Basic,
+        });
+        
+        let too_many = OnnxConfig { inter_op_threads: MAX_SESSION_THREADS + 1, ..Default::default() };
+        assert!(matches!(OnnxSession::new(path, too_many), Err(OnnxError::InvalidConfig(_))));
+        
+        let bad_level = OnnxConfig { optimization_level: 7, ..Default::default() };
+        let err = OnnxSession::new(path, bad_level).err().unwrap();
+        assert!(err.to_string().contains("optimization_level"));
+    }
+    
     #[test]
     fn test_tensor_type() {
         assert_eq!(TensorType::Float32.size(), 4);
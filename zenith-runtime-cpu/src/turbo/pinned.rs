@@ -0,0 +1,117 @@
+//! Page-locked Host Memory
+//!
+//! Pinned host allocations for GPU-direct transfers. Backed by
+//! `cudaHostAlloc` when the `cuda` feature is enabled; without it,
+//! allocation always reports unavailable and callers fall back to
+//! regular pageable memory.
+
+use std::ptr::NonNull;
+
+// FFI bindings to the CUDA runtime
+#[cfg(feature = "cuda")]
+mod ffi {
+    use std::ffi::c_void;
+
+    /// `cudaHostAllocDefault`
+    pub const CUDA_HOST_ALLOC_DEFAULT: u32 = 0;
+
+    #[link(name = "cudart")]
+    extern "C" {
+        pub fn cudaHostAlloc(ptr: *mut *mut c_void, size: usize, flags: u32) -> i32;
+        pub fn cudaFreeHost(ptr: *mut c_void) -> i32;
+    }
+}
+
+/// A page-locked host allocation, freed on drop
+pub struct PinnedMemory {
+    ptr: NonNull<u8>,
+    len: usize,
+}
+
+// SAFETY: PinnedMemory uniquely owns its allocation, like a Box<[u8]>
+unsafe impl Send for PinnedMemory {}
+unsafe impl Sync for PinnedMemory {}
+
+impl PinnedMemory {
+    /// Allocate `len` bytes of page-locked memory.
+    ///
+    /// Returns `None` if `len` is zero, the CUDA runtime refuses the
+    /// allocation, or the crate was built without the `cuda` feature.
+    pub fn alloc(len: usize) -> Option<Self> {
+        if len == 0 {
+            return None;
+        }
+        Self::alloc_impl(len)
+    }
+
+    #[cfg(feature = "cuda")]
+    fn alloc_impl(len: usize) -> Option<Self> {
+        let mut raw: *mut std::ffi::c_void = std::ptr::null_mut();
+        // SAFETY: cudaHostAlloc writes a valid pointer to `raw` on success
+        let status = unsafe { ffi::cudaHostAlloc(&mut raw, len, ffi::CUDA_HOST_ALLOC_DEFAULT) };
+        if status != 0 {
+            tracing::warn!("cudaHostAlloc({} bytes) failed with error {}", len, status);
+            return None;
+        }
+        NonNull::new(raw as *mut u8).map(|ptr| Self { ptr, len })
+    }
+
+    #[cfg(not(feature = "cuda"))]
+    fn alloc_impl(_len: usize) -> Option<Self> {
+        None
+    }
+
+    /// Allocation size in bytes
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the allocation is empty (never true for a live allocation)
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// View the pinned region
+    pub fn as_slice(&self) -> &[u8] {
+        // SAFETY: ptr is valid for len bytes for the lifetime of self
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// Mutable view of the pinned region
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        // SAFETY: ptr is valid for len bytes and uniquely borrowed
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl Drop for PinnedMemory {
+    fn drop(&mut self) {
+        #[cfg(feature = "cuda")]
+        // SAFETY: ptr came from cudaHostAlloc and is freed exactly once
+        unsafe {
+            ffi::cudaFreeHost(self.ptr.as_ptr() as *mut std::ffi::c_void);
+        }
+    }
+}
+
+impl std::fmt::Debug for PinnedMemory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PinnedMemory").field("len", &self.len).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_length_is_never_pinned() {
+        assert!(PinnedMemory::alloc(0).is_none());
+    }
+
+    #[cfg(not(feature = "cuda"))]
+    #[test]
+    fn test_alloc_unavailable_without_cuda() {
+        assert!(PinnedMemory::alloc(4096).is_none());
+    }
+}
@@ -0,0 +1,180 @@
+//! Preprocessing Pipeline
+//!
+//! Ordered preprocessing stages run by `TurboEngine::process` before a
+//! tensor is handed to inference.
+
+use super::{DataType, SimdOps};
+use crate::{Error, Result};
+
+/// An f32 tensor flowing through the preprocessing pipeline
+#[derive(Debug, Clone, PartialEq)]
+pub struct TensorView {
+    data: Vec<f32>,
+    shape: Vec<usize>,
+    dtype: DataType,
+}
+
+impl TensorView {
+    /// Create a Float32 tensor; `data` must hold exactly `shape` elements
+    pub fn new(data: Vec<f32>, shape: Vec<usize>) -> Result<Self> {
+        let expected: usize = shape.iter().product();
+        if data.len() != expected {
+            return Err(Error::Buffer(format!(
+                "tensor of shape {:?} needs {} elements, got {}",
+                shape, expected, data.len()
+            )));
+        }
+        Ok(Self { data, shape, dtype: DataType::Float32 })
+    }
+
+    /// Tensor values, row-major
+    pub fn data(&self) -> &[f32] {
+        &self.data
+    }
+
+    /// Mutable tensor values, row-major
+    pub fn data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Tensor dimensions
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// Type the values represent; quantized tensors keep f32 storage
+    pub fn dtype(&self) -> DataType {
+        self.dtype
+    }
+
+    /// Leading (batch) dimension, or 1 for a scalar
+    pub fn batch_size(&self) -> usize {
+        self.shape.first().copied().unwrap_or(1)
+    }
+
+    /// Size of the values in bytes at `dtype`
+    pub fn size_bytes(&self) -> usize {
+        self.data.len() * self.dtype.size()
+    }
+
+    /// Consume the tensor, returning its values
+    pub fn into_data(self) -> Vec<f32> {
+        self.data
+    }
+}
+
+/// A single preprocessing step
+pub trait Stage: Send + Sync {
+    /// Name reported in per-stage statistics
+    fn name(&self) -> &str;
+
+    /// Transform `input`, returning the tensor for the next stage
+    fn apply(&self, input: TensorView) -> Result<TensorView>;
+}
+
+/// Per-stage counters reported in `TurboStats::stages`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StageStats {
+    /// Stage name
+    pub name: String,
+    /// Tensors the stage has transformed
+    pub invocations: u64,
+    /// Failed invocations
+    pub errors: u64,
+    /// Time spent in the stage (nanoseconds)
+    pub total_ns: u64,
+}
+
+/// `(x - mean) / std` over every element
+pub struct NormalizeStage {
+    mean: f32,
+    std: f32,
+    ops: SimdOps,
+}
+
+impl NormalizeStage {
+    /// Create a normalize stage; `std` must be positive
+    pub fn new(mean: f32, std: f32) -> Result<Self> {
+        if !mean.is_finite() || !std.is_finite() || std <= 0.0 {
+            return Err(Error::Config(format!(
+                "normalize needs a finite mean and positive std, got mean={} std={}",
+                mean, std
+            )));
+        }
+        Ok(Self { mean, std, ops: SimdOps::new() })
+    }
+}
+
+impl Stage for NormalizeStage {
+    fn name(&self) -> &str {
+        "normalize"
+    }
+
+    fn apply(&self, mut input: TensorView) -> Result<TensorView> {
+        if input.dtype != DataType::Float32 {
+            return Err(Error::Config(format!("normalize expects Float32, got {:?}", input.dtype)));
+        }
+        self.ops.normalize_inplace(&mut input.data, self.mean, self.std);
+        Ok(input)
+    }
+}
+
+/// Affine quantization to uint8 levels: `round(x / scale) + zero_point`
+pub struct QuantizeStage {
+    scale: f32,
+    zero_point: u8,
+    ops: SimdOps,
+}
+
+impl QuantizeStage {
+    /// Create a quantize stage; `scale` must be positive
+    pub fn new(scale: f32, zero_point: u8) -> Result<Self> {
+        if !scale.is_finite() || scale <= 0.0 {
+            return Err(Error::Config(format!("quantize needs a positive scale, got {}", scale)));
+        }
+        Ok(Self { scale, zero_point, ops: SimdOps::new() })
+    }
+}
+
+impl Stage for QuantizeStage {
+    fn name(&self) -> &str {
+        "quantize"
+    }
+
+    fn apply(&self, mut input: TensorView) -> Result<TensorView> {
+        if input.dtype != DataType::Float32 {
+            return Err(Error::Config(format!("quantize expects Float32, got {:?}", input.dtype)));
+        }
+        self.ops.quantize_inplace(&mut input.data, self.scale, self.zero_point);
+        input.dtype = DataType::UInt8;
+        Ok(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tensor_shape_must_match() {
+        assert!(TensorView::new(vec![0.0; 6], vec![2, 3]).is_ok());
+        assert!(matches!(TensorView::new(vec![0.0; 5], vec![2, 3]), Err(Error::Buffer(_))));
+    }
+
+    #[test]
+    fn test_builtin_stages() {
+        assert!(NormalizeStage::new(0.0, 0.0).is_err());
+        assert!(QuantizeStage::new(-1.0, 0).is_err());
+
+        let tensor = TensorView::new(vec![1.0, 3.0], vec![2]).unwrap();
+        let normalized = NormalizeStage::new(1.0, 2.0).unwrap().apply(tensor).unwrap();
+        assert_eq!(normalized.data(), &[0.0, 1.0]);
+
+        let quantized = QuantizeStage::new(0.5, 10).unwrap().apply(normalized).unwrap();
+        assert_eq!(quantized.data(), &[10.0, 12.0]);
+        assert_eq!(quantized.dtype(), DataType::UInt8);
+
+        // Quantized values can't be quantized again
+        assert!(QuantizeStage::new(1.0, 0).unwrap().apply(quantized).is_err());
+    }
+}
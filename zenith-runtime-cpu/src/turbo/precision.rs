@@ -2,7 +2,11 @@
 //!
 //! Support for FP16/BF16 training and inference acceleration.
 
+use std::cmp::Ordering as CmpOrdering;
+use std::ops::{Add, Div, Mul, Sub};
 use std::sync::atomic::{AtomicU64, Ordering};
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
 
 /// Half precision (FP16) representation
 #[derive(Debug, Clone, Copy, Default)]
@@ -97,6 +101,10 @@ impl Float16 {
 impl BFloat16 {
     /// Convert from f32 to bf16 (just truncate lower 16 bits)
     pub fn from_f32(value: f32) -> Self {
+        if value.is_nan() {
+            // Truncating could clear every mantissa bit and turn NaN into Inf
+            return Self(0x7FC0);
+        }
         let bits = value.to_bits();
         Self((bits >> 16) as u16)
     }
@@ -117,6 +125,54 @@ impl BFloat16 {
     }
 }
 
+/// Arithmetic and comparisons for the reduced-precision types. Operations
+/// are computed in f32 and rounded back, so NaN and infinity follow IEEE 754
+/// (NaN compares unequal to everything, `+0 == -0`).
+macro_rules! impl_reduced_float_ops {
+    ($ty:ident) => {
+        impl From<f32> for $ty {
+            fn from(value: f32) -> Self {
+                Self::from_f32(value)
+            }
+        }
+        
+        impl From<$ty> for f32 {
+            fn from(value: $ty) -> Self {
+                value.to_f32()
+            }
+        }
+        
+        impl PartialEq for $ty {
+            fn eq(&self, other: &Self) -> bool {
+                self.to_f32() == other.to_f32()
+            }
+        }
+        
+        impl PartialOrd for $ty {
+            fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+                self.to_f32().partial_cmp(&other.to_f32())
+            }
+        }
+        
+        impl_reduced_float_ops!(@binop $ty, Add, add, +);
+        impl_reduced_float_ops!(@binop $ty, Sub, sub, -);
+        impl_reduced_float_ops!(@binop $ty, Mul, mul, *);
+        impl_reduced_float_ops!(@binop $ty, Div, div, /);
+    };
+    (@binop $ty:ident, $trait:ident, $method:ident, $op:tt) => {
+        impl $trait for $ty {
+            type Output = Self;
+            
+            fn $method(self, rhs: Self) -> Self {
+                Self::from_f32(self.to_f32() $op rhs.to_f32())
+            }
+        }
+    };
+}
+
+impl_reduced_float_ops!(Float16);
+impl_reduced_float_ops!(BFloat16);
+
 /// Mixed precision configuration
 #[derive(Debug, Clone)]
 pub struct MixedPrecisionConfig {
@@ -235,12 +291,49 @@ impl LossScaler {
 /// Mixed precision converter for batch processing
 pub struct PrecisionConverter {
     config: MixedPrecisionConfig,
+    /// Randomness source for stochastic rounding
+    rng: Box<dyn RngCore + Send>,
 }
 
 impl PrecisionConverter {
     /// Create new converter
     pub fn new(config: MixedPrecisionConfig) -> Self {
-        Self { config }
+        Self::with_rng(config, StdRng::from_os_rng())
+    }
+    
+    /// Create a converter whose stochastic rounding is reproducible from `seed`
+    pub fn with_seed(config: MixedPrecisionConfig, seed: u64) -> Self {
+        Self::with_rng(config, StdRng::seed_from_u64(seed))
+    }
+    
+    /// Create a converter drawing stochastic rounding bits from `rng`
+    pub fn with_rng<R: RngCore + Send + 'static>(config: MixedPrecisionConfig, rng: R) -> Self {
+        Self { config, rng: Box::new(rng) }
+    }
+    
+    /// Convert f32 to bf16 with stochastic rounding.
+    ///
+    /// Rounds up with probability equal to the fraction of a bf16 ulp carried
+    /// by the discarded low 16 bits, so the rounding is unbiased in
+    /// expectation and small gradient updates survive accumulation.
+    pub fn f32_to_bf16_stochastic(&mut self, x: f32) -> BFloat16 {
+        if !x.is_finite() {
+            return BFloat16::from_f32(x);
+        }
+        
+        let noise = self.rng.next_u32() & 0xFFFF;
+        // Carry into the upper half iff noise + discarded bits >= 2^16;
+        // values near f32::MAX may round up to infinity, as in hardware
+        let bits = x.to_bits().wrapping_add(noise);
+        BFloat16::from_bits((bits >> 16) as u16)
+    }
+    
+    /// Convert f32 slice to bf16 with stochastic rounding
+    pub fn f32_to_bf16_stochastic_slice(&mut self, input: &[f32], output: &mut [u16]) {
+        assert_eq!(input.len(), output.len());
+        for (out, &val) in output.iter_mut().zip(input) {
+            *out = self.f32_to_bf16_stochastic(val).to_bits();
+        }
     }
     
     /// Convert f32 slice to bf16
@@ -313,6 +406,57 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_reduced_precision_arithmetic() {
+        let pairs = [(1.5f32, 2.25f32), (3.1, -0.7), (100.0, 0.01), (-42.5, 17.0), (0.3, 0.2)];
+        
+        for &(x, y) in &pairs {
+            // FP16 keeps 10 mantissa bits, BF16 keeps 7; the result loses at most one ulp
+            let (a, b) = (Float16::from_f32(x), Float16::from_f32(y));
+            let (fa, fb) = (a.to_f32(), b.to_f32());
+            for (got, want) in [(a + b, fa + fb), (a - b, fa - fb), (a * b, fa * fb), (a / b, fa / fb)] {
+                let err = (got.to_f32() - want).abs() / want.abs().max(1e-6);
+                assert!(err <= 2f32.powi(-10), "FP16 {} vs {}", got.to_f32(), want);
+            }
+            
+            let (a, b) = (BFloat16::from(x), BFloat16::from(y));
+            let (fa, fb): (f32, f32) = (a.into(), b.into());
+            for (got, want) in [(a + b, fa + fb), (a - b, fa - fb), (a * b, fa * fb), (a / b, fa / fb)] {
+                let err = (got.to_f32() - want).abs() / want.abs().max(1e-6);
+                assert!(err <= 2f32.powi(-7), "BF16 {} vs {}", got.to_f32(), want);
+            }
+        }
+        
+        assert!(Float16::from_f32(1.0) < Float16::from_f32(2.0));
+        assert!(BFloat16::from_f32(-3.0) < BFloat16::from_f32(-2.0));
+        assert_eq!(Float16::from_f32(0.0), Float16::from_f32(-0.0));
+        assert_eq!(BFloat16::from_f32(2.0) * BFloat16::from_f32(4.0), BFloat16::from_f32(8.0));
+    }
+    
+    #[test]
+    fn test_reduced_precision_nan_inf() {
+        let nan16 = Float16::from_f32(f32::NAN);
+        let nanbf = BFloat16::from_f32(f32::NAN);
+        
+        assert!(nan16.to_f32().is_nan());
+        assert!(nanbf.to_f32().is_nan());
+        assert!(BFloat16::from_f32(f32::from_bits(0x7F80_0001)).to_f32().is_nan(), "NaN payload must not truncate to Inf");
+        
+        assert_ne!(nan16, nan16);
+        assert_ne!(nanbf, nanbf);
+        assert_eq!(nan16.partial_cmp(&Float16::from_f32(1.0)), None);
+        let one = BFloat16::from_f32(1.0);
+        assert!(!nanbf.lt(&one) && !nanbf.ge(&one), "NaN is unordered");
+        
+        let inf = Float16::from_f32(f32::INFINITY);
+        assert_eq!((inf + Float16::from_f32(1.0)).to_f32(), f32::INFINITY);
+        assert!((inf - inf).to_f32().is_nan());
+        assert_eq!((Float16::from_f32(1.0) / Float16::from_f32(0.0)).to_f32(), f32::INFINITY);
+        assert_eq!((Float16::from_f32(60000.0) * Float16::from_f32(2.0)).to_f32(), f32::INFINITY, "FP16 overflow saturates to Inf");
+        assert!((BFloat16::from_f32(0.0) / BFloat16::from_f32(0.0)).to_f32().is_nan());
+        assert!(BFloat16::from_f32(f32::NEG_INFINITY) < BFloat16::from_f32(f32::MIN));
+    }
+    
     #[test]
     fn test_loss_scaler() {
         let config = MixedPrecisionConfig::default();
@@ -332,6 +476,57 @@ mod tests {
         assert!(scaler.scale() > initial_scale * 0.5);
     }
     
+    #[test]
+    fn test_bf16_stochastic_rounding_unbiased() {
+        let mut converter = PrecisionConverter::with_seed(MixedPrecisionConfig::default(), 42);
+        
+        // Exactly halfway between the bf16 neighbours 1.0 and 1.0078125
+        let halfway = f32::from_bits(0x3F80_8000);
+        let down = BFloat16::from_bits(0x3F80);
+        let up = BFloat16::from_bits(0x3F81);
+        
+        let trials = 10_000;
+        let mut ups: i32 = 0;
+        for _ in 0..trials {
+            let rounded = converter.f32_to_bf16_stochastic(halfway);
+            assert!(rounded.to_bits() == down.to_bits() || rounded.to_bits() == up.to_bits());
+            if rounded.to_bits() == up.to_bits() {
+                ups += 1;
+            }
+        }
+        
+        // Binomial(10000, 0.5): sigma = 50, allow 5 sigma
+        assert!((ups - trials / 2).abs() < 250, "{} of {} rounded up", ups, trials);
+        
+        // Exactly representable values never move; non-finite values pass through
+        assert_eq!(converter.f32_to_bf16_stochastic(1.0).to_bits(), 0x3F80);
+        assert_eq!(converter.f32_to_bf16_stochastic(f32::INFINITY).to_f32(), f32::INFINITY);
+        assert!(converter.f32_to_bf16_stochastic(f32::NAN).to_f32().is_nan());
+    }
+    
+    #[test]
+    fn test_bf16_stochastic_rounding_seeded() {
+        let input: Vec<f32> = (0..64).map(|i| 1.0 + i as f32 * 1e-4).collect();
+        let mut a = vec![0u16; 64];
+        let mut b = vec![0u16; 64];
+        
+        PrecisionConverter::with_seed(MixedPrecisionConfig::default(), 7)
+            .f32_to_bf16_stochastic_slice(&input, &mut a);
+        PrecisionConverter::with_seed(MixedPrecisionConfig::default(), 7)
+            .f32_to_bf16_stochastic_slice(&input, &mut b);
+        
+        assert_eq!(a, b, "Same seed must give the same rounding");
+        
+        // Mean of stochastically rounded values tracks the true mean
+        let mut converter = PrecisionConverter::with_seed(MixedPrecisionConfig::default(), 9);
+        let small = 1.0 + 1e-3; // ~1/8 of a bf16 ulp above 1.0
+        let mean: f64 = (0..20_000)
+            .map(|_| converter.f32_to_bf16_stochastic(small).to_f32() as f64)
+            .sum::<f64>() / 20_000.0;
+        assert!((mean - small as f64).abs() < 2e-4, "biased mean {}", mean);
+        assert_eq!(BFloat16::from_f32(small).to_f32(), 1.0, "Truncation loses the update entirely");
+    }
+    
     #[test]
     fn test_precision_converter() {
         let config = MixedPrecisionConfig::default();
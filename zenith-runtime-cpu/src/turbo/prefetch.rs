@@ -3,10 +3,14 @@
 //! Zero-latency data loading with async prefetching.
 
 use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::Arc;
 use parking_lot::{Mutex, Condvar};
 use std::thread::{self, JoinHandle};
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use super::pinned::PinnedMemory;
 
 /// Prefetch buffer containing prepared batch data
 pub struct PrefetchBuffer {
@@ -18,25 +22,85 @@ pub struct PrefetchBuffer {
     pub offsets: Vec<usize>,
     /// Is this buffer ready for consumption
     pub ready: bool,
+    /// Page-locked staging region for GPU-direct transfer
+    pinned: Option<PinnedMemory>,
+    pinned_requested: bool,
+    /// CRC32 of `data` taken at submit when checksums are enabled
+    checksum: Option<u32>,
+    /// Set when `data` no longer matched `checksum` on delivery
+    corrupted: bool,
 }
 
 impl PrefetchBuffer {
     /// Create empty buffer with capacity
     pub fn new(capacity: usize) -> Self {
+        Self::with_pinned(capacity, false)
+    }
+    
+    /// Create empty buffer, optionally backed by a page-locked staging region.
+    ///
+    /// Pinned memory needs the `cuda` feature; when it is unavailable the
+    /// buffer falls back to pageable memory and `is_pinned()` reports false.
+    pub fn with_pinned(capacity: usize, pinned: bool) -> Self {
+        let region = if pinned { PinnedMemory::alloc(capacity) } else { None };
+        if pinned && region.is_none() {
+            tracing::debug!("Pinned memory unavailable, using pageable prefetch buffer");
+        }
+        
         Self {
             data: Vec::with_capacity(capacity),
             num_samples: 0,
             offsets: Vec::new(),
             ready: false,
+            pinned: region,
+            pinned_requested: pinned,
+            checksum: None,
+            corrupted: false,
         }
     }
     
+    /// Whether pinned memory was requested for this buffer
+    pub fn pinned_requested(&self) -> bool {
+        self.pinned_requested
+    }
+    
+    /// Whether this buffer actually owns page-locked memory
+    pub fn is_pinned(&self) -> bool {
+        self.pinned.is_some()
+    }
+    
+    /// Copy `data` into the pinned region and return it for a DMA transfer.
+    ///
+    /// Returns `None` if the buffer is not pinned or `data` has outgrown it.
+    pub fn stage_pinned(&mut self) -> Option<&[u8]> {
+        let region = self.pinned.as_mut()?;
+        let len = self.data.len();
+        if len > region.len() {
+            return None;
+        }
+        region.as_mut_slice()[..len].copy_from_slice(&self.data);
+        Some(&region.as_slice()[..len])
+    }
+    
+    /// CRC32 of `data` recorded at submit, if checksums are enabled
+    pub fn checksum(&self) -> Option<u32> {
+        self.checksum
+    }
+    
+    /// Whether `data` changed between submit and delivery. Always false
+    /// unless `PrefetchConfig::verify_checksums` is set.
+    pub fn is_corrupted(&self) -> bool {
+        self.corrupted
+    }
+    
     /// Reset buffer for reuse
     pub fn reset(&mut self) {
         self.data.clear();
         self.num_samples = 0;
         self.offsets.clear();
         self.ready = false;
+        self.checksum = None;
+        self.corrupted = false;
     }
 }
 
@@ -47,10 +111,23 @@ pub struct PrefetchConfig {
     pub num_buffers: usize,
     /// Size of each buffer in bytes
     pub buffer_size: usize,
-    /// Number of worker threads
-    pub num_workers: usize,
+    /// Threads running the loader
+    pub num_loader_workers: usize,
+    /// Threads running the transform passed to `start_with_transform`;
+    /// at least one is started. Unused by `start`.
+    pub num_transform_workers: usize,
     /// Enable pinned memory for GPU
     pub pinned_memory: bool,
+    /// Ready buffers `next()` samples from at random; 0 keeps FIFO order.
+    /// Capped at `num_buffers - 1` so workers always have a free buffer.
+    pub shuffle_buffer_size: usize,
+    /// Seed for the shuffle RNG; `None` seeds from the OS
+    pub shuffle_seed: Option<u64>,
+    /// CRC32 each buffer's data when it is submitted and check it again on
+    /// delivery, flagging buffers that changed in between. Meant for
+    /// chasing corruption with `pinned_memory`; costs a pass over the data
+    /// twice per buffer.
+    pub verify_checksums: bool,
 }
 
 impl Default for PrefetchConfig {
@@ -58,20 +135,62 @@ impl Default for PrefetchConfig {
         Self {
             num_buffers: 4,
             buffer_size: 64 * 1024 * 1024, // 64MB
-            num_workers: 2,
+            num_loader_workers: 2,
+            num_transform_workers: 2,
             pinned_memory: false,
+            shuffle_buffer_size: 0,
+            shuffle_seed: None,
+            verify_checksums: false,
         }
     }
 }
 
+/// Callback run when every worker has reached the end of an epoch
+pub type EpochEndCallback = dyn Fn(usize) + Send + Sync;
+
+/// CPU preprocessing applied to loaded buffers by the transform stage
+pub type TransformFn = dyn Fn(&mut PrefetchBuffer) + Send + Sync;
+
 /// Thread-safe prefetch queue
 pub struct PrefetchQueue {
     ready_buffers: Mutex<VecDeque<PrefetchBuffer>>,
     free_buffers: Mutex<VecDeque<PrefetchBuffer>>,
     not_empty: Condvar,
     not_full: Condvar,
+    /// Loaded buffers waiting for the transform stage
+    loaded_buffers: Mutex<TransformQueue>,
+    loaded_not_empty: Condvar,
+    /// Signalled when the transform stage has nothing queued or in flight
+    transforms_drained: Condvar,
     shutdown: AtomicBool,
+    /// Set once all workers hit end-of-data; cleared by `reset_epoch`
+    end_of_data: AtomicBool,
+    epoch: Mutex<EpochState>,
+    epoch_changed: Condvar,
     stats: PrefetchStats,
+    verify_checksums: bool,
+}
+
+/// Intermediate queue between the loader and transform stages
+#[derive(Default)]
+struct TransformQueue {
+    buffers: VecDeque<PrefetchBuffer>,
+    /// Buffers taken by transform workers and not yet passed on
+    in_flight: usize,
+}
+
+impl TransformQueue {
+    fn is_idle(&self) -> bool {
+        self.buffers.is_empty() && self.in_flight == 0
+    }
+}
+
+/// Workers still loading the current epoch
+#[derive(Debug, Default)]
+struct EpochState {
+    epoch: usize,
+    active_workers: usize,
+    num_workers: usize,
 }
 
 /// Prefetch statistics
@@ -82,6 +201,37 @@ pub struct PrefetchStats {
     pub bytes_prefetched: AtomicUsize,
     pub queue_full_waits: AtomicUsize,
     pub queue_empty_waits: AtomicUsize,
+    /// Loader invocations that panicked (the worker recovers and keeps going)
+    pub worker_panics: AtomicUsize,
+    /// Epochs every worker has finished loading
+    pub epochs_completed: AtomicUsize,
+    /// Buffers the loader stage filled
+    pub buffers_loaded: AtomicUsize,
+    /// Buffers the transform stage passed on to the ready queue
+    pub buffers_transformed: AtomicUsize,
+    /// Times a transform worker waited for a loaded buffer
+    pub transform_queue_waits: AtomicUsize,
+    /// Transform invocations that panicked; the buffer is discarded
+    pub transform_panics: AtomicUsize,
+    /// Buffers whose checksum no longer matched on delivery
+    pub checksum_mismatches: AtomicUsize,
+}
+
+/// Snapshot of per-stage counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrefetchStageStats {
+    /// Buffers the loader stage filled
+    pub loaded: usize,
+    /// Loader invocations that panicked
+    pub loader_panics: usize,
+    /// Buffers the transform stage passed on to the ready queue
+    pub transformed: usize,
+    /// Transform invocations that panicked
+    pub transform_panics: usize,
+    /// Loaded buffers currently waiting for a transform worker
+    pub transform_queue_depth: usize,
+    /// Times a transform worker found no loaded buffer and had to wait
+    pub transform_queue_waits: usize,
 }
 
 impl PrefetchQueue {
@@ -89,7 +239,7 @@ impl PrefetchQueue {
     pub fn new(config: &PrefetchConfig) -> Self {
         let mut free_buffers = VecDeque::new();
         for _ in 0..config.num_buffers {
-            free_buffers.push_back(PrefetchBuffer::new(config.buffer_size));
+            free_buffers.push_back(PrefetchBuffer::with_pinned(config.buffer_size, config.pinned_memory));
         }
         
         Self {
@@ -97,8 +247,15 @@ impl PrefetchQueue {
             free_buffers: Mutex::new(free_buffers),
             not_empty: Condvar::new(),
             not_full: Condvar::new(),
+            loaded_buffers: Mutex::new(TransformQueue::default()),
+            loaded_not_empty: Condvar::new(),
+            transforms_drained: Condvar::new(),
             shutdown: AtomicBool::new(false),
+            end_of_data: AtomicBool::new(false),
+            epoch: Mutex::new(EpochState::default()),
+            epoch_changed: Condvar::new(),
             stats: PrefetchStats::default(),
+            verify_checksums: config.verify_checksums,
         }
     }
     
@@ -121,6 +278,9 @@ impl PrefetchQueue {
     /// Submit a filled buffer to the ready queue
     pub fn submit_buffer(&self, mut buffer: PrefetchBuffer) {
         buffer.ready = true;
+        if self.verify_checksums {
+            buffer.checksum = Some(crc32fast::hash(&buffer.data));
+        }
         
         let mut ready = self.ready_buffers.lock();
         self.stats.buffers_produced.fetch_add(1, Ordering::Relaxed);
@@ -130,26 +290,93 @@ impl PrefetchQueue {
         self.not_empty.notify_one();
     }
     
-    /// Get a ready buffer for consumption
+    /// Queue a loaded buffer for the transform stage
+    fn submit_loaded(&self, buffer: PrefetchBuffer) {
+        let mut loaded = self.loaded_buffers.lock();
+        loaded.buffers.push_back(buffer);
+        self.loaded_not_empty.notify_one();
+    }
+    
+    /// Take a loaded buffer to transform; `None` after shutdown. The caller
+    /// must hand it back through `finish_transform`.
+    fn take_loaded(&self) -> Option<PrefetchBuffer> {
+        let mut loaded = self.loaded_buffers.lock();
+        while loaded.buffers.is_empty() && !self.is_shutdown() {
+            self.stats.transform_queue_waits.fetch_add(1, Ordering::Relaxed);
+            self.loaded_not_empty.wait(&mut loaded);
+        }
+        if self.is_shutdown() {
+            return None;
+        }
+        
+        let buffer = loaded.buffers.pop_front();
+        loaded.in_flight += 1;
+        buffer
+    }
+    
+    /// Pass a transformed buffer on to the ready queue, or recycle it if
+    /// the transform panicked
+    fn finish_transform(&self, buffer: PrefetchBuffer, ok: bool) {
+        if ok {
+            self.stats.buffers_transformed.fetch_add(1, Ordering::Relaxed);
+            self.submit_buffer(buffer);
+        } else {
+            self.stats.transform_panics.fetch_add(1, Ordering::Relaxed);
+            self.return_buffer(buffer);
+        }
+        
+        let mut loaded = self.loaded_buffers.lock();
+        loaded.in_flight -= 1;
+        if loaded.is_idle() {
+            self.transforms_drained.notify_all();
+        }
+    }
+    
+    /// Block until every loaded buffer has been through the transform stage
+    fn wait_transforms_drained(&self) {
+        let mut loaded = self.loaded_buffers.lock();
+        while !loaded.is_idle() && !self.is_shutdown() {
+            self.transforms_drained.wait(&mut loaded);
+        }
+    }
+    
+    /// Get a ready buffer for consumption. Returns `None` after shutdown,
+    /// or once the current epoch's data has been fully consumed.
     pub fn get_ready_buffer(&self) -> Option<PrefetchBuffer> {
         let mut ready = self.ready_buffers.lock();
         
-        while ready.is_empty() && !self.shutdown.load(Ordering::Relaxed) {
+        while ready.is_empty() && !self.is_shutdown() && !self.is_end_of_data() {
             self.stats.queue_empty_waits.fetch_add(1, Ordering::Relaxed);
             self.not_empty.wait(&mut ready);
         }
         
-        if self.shutdown.load(Ordering::Relaxed) && ready.is_empty() {
+        if ready.is_empty() {
             return None;
         }
         
-        let buffer = ready.pop_front();
-        if buffer.is_some() {
+        let mut buffer = ready.pop_front();
+        drop(ready);
+        if let Some(buffer) = buffer.as_mut() {
             self.stats.buffers_consumed.fetch_add(1, Ordering::Relaxed);
+            self.verify_checksum(buffer);
         }
         buffer
     }
     
+    /// Flag and count a buffer whose data changed since it was submitted
+    fn verify_checksum(&self, buffer: &mut PrefetchBuffer) {
+        let Some(expected) = buffer.checksum else { return };
+        let actual = crc32fast::hash(&buffer.data);
+        if actual != expected {
+            buffer.corrupted = true;
+            self.stats.checksum_mismatches.fetch_add(1, Ordering::Relaxed);
+            tracing::error!(
+                "Prefetch buffer corrupted after submit: CRC32 {:08x}, expected {:08x} ({} bytes, pinned: {})",
+                actual, expected, buffer.data.len(), buffer.is_pinned()
+            );
+        }
+    }
+    
     /// Return a consumed buffer to the free pool
     pub fn return_buffer(&self, mut buffer: PrefetchBuffer) {
         buffer.reset();
@@ -164,6 +391,78 @@ impl PrefetchQueue {
         self.shutdown.store(true, Ordering::SeqCst);
         self.not_empty.notify_all();
         self.not_full.notify_all();
+        {
+            let _loaded = self.loaded_buffers.lock();
+            self.loaded_not_empty.notify_all();
+            self.transforms_drained.notify_all();
+        }
+        // Wake workers parked at an epoch boundary
+        let _epoch = self.epoch.lock();
+        self.epoch_changed.notify_all();
+    }
+    
+    /// Whether all workers have hit end-of-data for the current epoch
+    pub fn is_end_of_data(&self) -> bool {
+        self.end_of_data.load(Ordering::Acquire)
+    }
+    
+    /// Index of the epoch currently being loaded, starting at 0
+    pub fn epoch(&self) -> usize {
+        self.epoch.lock().epoch
+    }
+    
+    /// Number of epochs every worker has finished loading
+    pub fn epochs_completed(&self) -> usize {
+        self.stats.epochs_completed.load(Ordering::Relaxed)
+    }
+    
+    /// Set how many workers load each epoch
+    fn begin_workers(&self, num_workers: usize) {
+        let mut epoch = self.epoch.lock();
+        epoch.num_workers = num_workers;
+        epoch.active_workers = num_workers;
+    }
+    
+    /// Called by a worker whose loader reported end-of-data. The last worker
+    /// to finish runs `on_end` and flags end-of-data for the consumer. Blocks
+    /// until `reset_epoch` or shutdown; returns false on shutdown.
+    fn finish_epoch(&self, on_end: Option<&EpochEndCallback>) -> bool {
+        let mut epoch = self.epoch.lock();
+        let current = epoch.epoch;
+        epoch.active_workers = epoch.active_workers.saturating_sub(1);
+        
+        if epoch.active_workers == 0 {
+            // Buffers still being transformed belong to this epoch
+            self.wait_transforms_drained();
+            self.stats.epochs_completed.fetch_add(1, Ordering::Relaxed);
+            if let Some(on_end) = on_end {
+                on_end(current);
+            }
+            // Flag under the ready lock so a waiting consumer can't miss it
+            let _ready = self.ready_buffers.lock();
+            self.end_of_data.store(true, Ordering::Release);
+            self.not_empty.notify_all();
+        }
+        
+        while epoch.epoch == current && !self.is_shutdown() {
+            self.epoch_changed.wait(&mut epoch);
+        }
+        !self.is_shutdown()
+    }
+    
+    /// Start the next epoch if the current one has ended. Returns false
+    /// (and does nothing) while workers are still loading.
+    pub fn reset_epoch(&self) -> bool {
+        let mut epoch = self.epoch.lock();
+        if epoch.active_workers > 0 {
+            return false;
+        }
+        
+        epoch.epoch += 1;
+        epoch.active_workers = epoch.num_workers;
+        self.end_of_data.store(false, Ordering::Release);
+        self.epoch_changed.notify_all();
+        true
     }
     
     /// Check if shutdown
@@ -176,6 +475,33 @@ impl PrefetchQueue {
         self.ready_buffers.lock().len()
     }
     
+    /// Record a panic caught in a prefetch worker
+    pub fn record_worker_panic(&self) {
+        self.stats.worker_panics.fetch_add(1, Ordering::Relaxed);
+    }
+    
+    /// Number of loader panics caught so far
+    pub fn worker_panics(&self) -> usize {
+        self.stats.worker_panics.load(Ordering::Relaxed)
+    }
+    
+    /// Buffers delivered with `is_corrupted()` set
+    pub fn checksum_mismatches(&self) -> usize {
+        self.stats.checksum_mismatches.load(Ordering::Relaxed)
+    }
+    
+    /// Counters for the loader and transform stages
+    pub fn stage_stats(&self) -> PrefetchStageStats {
+        PrefetchStageStats {
+            loaded: self.stats.buffers_loaded.load(Ordering::Relaxed),
+            loader_panics: self.worker_panics(),
+            transformed: self.stats.buffers_transformed.load(Ordering::Relaxed),
+            transform_panics: self.stats.transform_panics.load(Ordering::Relaxed),
+            transform_queue_depth: self.loaded_buffers.lock().buffers.len(),
+            transform_queue_waits: self.stats.transform_queue_waits.load(Ordering::Relaxed),
+        }
+    }
+    
     /// Get statistics
     pub fn stats(&self) -> (usize, usize, usize) {
         (
@@ -186,12 +512,21 @@ impl PrefetchQueue {
     }
 }
 
+/// Consumer-side reservoir of ready buffers for shuffled delivery
+struct ShuffleBuffer {
+    reservoir: Vec<PrefetchBuffer>,
+    capacity: usize,
+    rng: StdRng,
+}
+
 /// Prefetch pipeline managing async data loading
 pub struct PrefetchPipeline {
     config: PrefetchConfig,
     queue: Arc<PrefetchQueue>,
     workers: Vec<JoinHandle<()>>,
     running: AtomicBool,
+    shuffle: Option<Mutex<ShuffleBuffer>>,
+    on_epoch_end: Option<Arc<EpochEndCallback>>,
 }
 
 impl PrefetchPipeline {
@@ -199,41 +534,100 @@ impl PrefetchPipeline {
     pub fn new(config: PrefetchConfig) -> Self {
         let queue = Arc::new(PrefetchQueue::new(&config));
         
+        // Buffers held in the reservoir can't be refilled, so leave at
+        // least one for the workers
+        let capacity = config.shuffle_buffer_size.min(config.num_buffers.saturating_sub(1));
+        if capacity < config.shuffle_buffer_size {
+            tracing::warn!(
+                "shuffle_buffer_size {} needs more than {} prefetch buffers, using {}",
+                config.shuffle_buffer_size, config.num_buffers, capacity
+            );
+        }
+        let shuffle = (capacity > 0).then(|| Mutex::new(ShuffleBuffer {
+            reservoir: Vec::with_capacity(capacity),
+            capacity,
+            rng: match config.shuffle_seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_os_rng(),
+            },
+        }));
+        
         Self {
             config,
             queue,
             workers: Vec::new(),
             running: AtomicBool::new(false),
+            shuffle,
+            on_epoch_end: None,
         }
     }
     
     /// Start prefetching with custom data loader function
     pub fn start<F>(&mut self, loader: F)
+    where
+        F: Fn(&mut PrefetchBuffer) -> bool + Send + Sync + 'static,
+    {
+        self.spawn_workers(Arc::new(loader), None);
+    }
+    
+    /// Start a two-stage pipeline: loader workers fill buffers (I/O) and
+    /// transform workers run `transform` on them (CPU preprocessing) before
+    /// they reach the ready queue, so the two overlap. A buffer whose
+    /// transform panics is discarded. Transform workers take buffers in
+    /// load order but may finish them out of order.
+    pub fn start_with_transform<F, T>(&mut self, loader: F, transform: T)
+    where
+        F: Fn(&mut PrefetchBuffer) -> bool + Send + Sync + 'static,
+        T: Fn(&mut PrefetchBuffer) + Send + Sync + 'static,
+    {
+        self.spawn_workers(Arc::new(loader), Some(Arc::new(transform)));
+    }
+    
+    fn spawn_workers<F>(&mut self, loader: Arc<F>, transform: Option<Arc<TransformFn>>)
     where
         F: Fn(&mut PrefetchBuffer) -> bool + Send + Sync + 'static,
     {
         self.running.store(true, Ordering::SeqCst);
         
-        let loader = Arc::new(loader);
+        let two_stage = transform.is_some();
+        self.queue.begin_workers(self.config.num_loader_workers);
         
-        for worker_id in 0..self.config.num_workers {
+        for worker_id in 0..self.config.num_loader_workers {
             let queue = Arc::clone(&self.queue);
             let loader = Arc::clone(&loader);
+            let on_epoch_end = self.on_epoch_end.clone();
             
             let handle = thread::spawn(move || {
                 tracing::debug!("Prefetch worker {} started", worker_id);
                 
                 while !queue.is_shutdown() {
                     if let Some(mut buffer) = queue.get_free_buffer() {
-                        // Load data into buffer
-                        let success = loader(&mut buffer);
+                        // Load data into buffer, isolating panics so a bad batch
+                        // doesn't silently take the worker down
+                        let result = panic::catch_unwind(AssertUnwindSafe(|| loader(&mut buffer)));
                         
-                        if success {
-                            queue.submit_buffer(buffer);
-                        } else {
-                            // End of data or error, return buffer and shutdown
-                            queue.return_buffer(buffer);
-                            break;
+                        match result {
+                            Ok(true) => {
+                                queue.stats.buffers_loaded.fetch_add(1, Ordering::Relaxed);
+                                if two_stage {
+                                    queue.submit_loaded(buffer);
+                                } else {
+                                    queue.submit_buffer(buffer);
+                                }
+                            }
+                            Ok(false) => {
+                                // End of data: wait for the next epoch or shutdown
+                                queue.return_buffer(buffer);
+                                if !queue.finish_epoch(on_epoch_end.as_deref()) {
+                                    break;
+                                }
+                            }
+                            Err(_) => {
+                                // Partially filled buffer is discarded; keep loading
+                                queue.record_worker_panic();
+                                tracing::error!("Prefetch worker {} loader panicked, recovering", worker_id);
+                                queue.return_buffer(buffer);
+                            }
                         }
                     }
                 }
@@ -243,11 +637,54 @@ impl PrefetchPipeline {
             
             self.workers.push(handle);
         }
+        
+        let Some(transform) = transform else { return };
+        for worker_id in 0..self.config.num_transform_workers.max(1) {
+            let queue = Arc::clone(&self.queue);
+            let transform = Arc::clone(&transform);
+            
+            let handle = thread::spawn(move || {
+                tracing::debug!("Prefetch transform worker {} started", worker_id);
+                
+                while let Some(mut buffer) = queue.take_loaded() {
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| transform(&mut buffer)));
+                    if result.is_err() {
+                        tracing::error!("Prefetch transform worker {} panicked, dropping buffer", worker_id);
+                    }
+                    queue.finish_transform(buffer, result.is_ok());
+                }
+                
+                tracing::debug!("Prefetch transform worker {} stopped", worker_id);
+            });
+            
+            self.workers.push(handle);
+        }
     }
     
-    /// Get next batch of data
+    /// Get next batch of data.
+    ///
+    /// With a shuffle buffer, tops the reservoir up from the ready queue and
+    /// returns a random entry; once the queue is shut down the remaining
+    /// entries are drained in random order.
     pub fn next(&self) -> Option<PrefetchBuffer> {
-        self.queue.get_ready_buffer()
+        let Some(shuffle) = &self.shuffle else {
+            return self.queue.get_ready_buffer();
+        };
+        
+        let mut guard = shuffle.lock();
+        let shuffle = &mut *guard;
+        while shuffle.reservoir.len() < shuffle.capacity {
+            match self.queue.get_ready_buffer() {
+                Some(buffer) => shuffle.reservoir.push(buffer),
+                None => break,
+            }
+        }
+        
+        if shuffle.reservoir.is_empty() {
+            return None;
+        }
+        let index = shuffle.rng.random_range(0..shuffle.reservoir.len());
+        Some(shuffle.reservoir.swap_remove(index))
     }
     
     /// Return consumed buffer
@@ -255,6 +692,35 @@ impl PrefetchPipeline {
         self.queue.return_buffer(buffer);
     }
     
+    /// Run `callback` with the epoch index each time every worker's loader
+    /// has returned `false`. Loader state for the next epoch can be rewound
+    /// here; the callback must not call `reset_epoch` itself. Set before
+    /// `start`.
+    pub fn on_epoch_end<F>(&mut self, callback: F)
+    where
+        F: Fn(usize) + Send + Sync + 'static,
+    {
+        self.on_epoch_end = Some(Arc::new(callback));
+    }
+    
+    /// Resume workers after the end of an epoch; they call the loader again
+    /// from wherever its state now points. `next()` returns `None` between
+    /// the end of an epoch and this call. Returns false, without effect,
+    /// while the current epoch is still loading.
+    pub fn reset_epoch(&self) -> bool {
+        self.queue.reset_epoch()
+    }
+    
+    /// Index of the epoch currently being loaded, starting at 0
+    pub fn epoch(&self) -> usize {
+        self.queue.epoch()
+    }
+    
+    /// Number of epochs every worker has finished loading
+    pub fn epochs_completed(&self) -> usize {
+        self.queue.epochs_completed()
+    }
+    
     /// Stop the pipeline
     pub fn stop(&mut self) {
         self.running.store(false, Ordering::SeqCst);
@@ -274,6 +740,27 @@ impl PrefetchPipeline {
     pub fn stats(&self) -> (usize, usize, usize) {
         self.queue.stats()
     }
+    
+    /// Number of loader panics caught across all workers
+    pub fn worker_panics(&self) -> usize {
+        self.queue.worker_panics()
+    }
+    
+    /// Buffers that failed checksum verification on delivery
+    pub fn checksum_mismatches(&self) -> usize {
+        self.queue.checksum_mismatches()
+    }
+    
+    /// Counters for the loader and transform stages
+    pub fn stage_stats(&self) -> PrefetchStageStats {
+        self.queue.stage_stats()
+    }
+    
+    /// Whether any loader or transform has panicked
+    pub fn is_degraded(&self) -> bool {
+        let stages = self.stage_stats();
+        stages.loader_panics > 0 || stages.transform_panics > 0
+    }
 }
 
 impl Drop for PrefetchPipeline {
@@ -302,6 +789,81 @@ mod tests {
         assert_eq!(buffer.num_samples, 0);
     }
     
+    #[test]
+    fn test_checksum_detects_corruption() {
+        let config = PrefetchConfig {
+            num_buffers: 2,
+            buffer_size: 64,
+            verify_checksums: true,
+            ..Default::default()
+        };
+        let queue = PrefetchQueue::new(&config);
+        
+        for payload in [b"clean sample", b"dirty sample"] {
+            let mut buffer = queue.get_free_buffer().unwrap();
+            buffer.data.extend_from_slice(payload);
+            queue.submit_buffer(buffer);
+        }
+        // Flip a byte in the second buffer while it sits in the ready queue
+        queue.ready_buffers.lock()[1].data[0] ^= 0xff;
+        
+        let clean = queue.get_ready_buffer().unwrap();
+        assert!(clean.checksum().is_some());
+        assert!(!clean.is_corrupted());
+        assert_eq!(queue.checksum_mismatches(), 0);
+        
+        let mut dirty = queue.get_ready_buffer().unwrap();
+        assert!(dirty.is_corrupted());
+        assert_eq!(queue.checksum_mismatches(), 1);
+        
+        dirty.reset();
+        assert!(dirty.checksum().is_none());
+        assert!(!dirty.is_corrupted());
+    }
+    
+    #[test]
+    fn test_checksums_off_by_default() {
+        let queue = PrefetchQueue::new(&PrefetchConfig::default());
+        let mut buffer = queue.get_free_buffer().unwrap();
+        buffer.data.extend_from_slice(b"unchecked");
+        queue.submit_buffer(buffer);
+        queue.ready_buffers.lock()[0].data[0] ^= 0xff;
+        
+        let buffer = queue.get_ready_buffer().unwrap();
+        assert!(buffer.checksum().is_none());
+        assert!(!buffer.is_corrupted());
+        assert_eq!(queue.checksum_mismatches(), 0);
+    }
+    
+    #[cfg(not(feature = "cuda"))]
+    #[test]
+    fn test_prefetch_pinned_fallback() {
+        let config = PrefetchConfig {
+            num_buffers: 1,
+            buffer_size: 1024,
+            pinned_memory: true,
+            ..Default::default()
+        };
+        
+        let queue = PrefetchQueue::new(&config);
+        let mut buffer = queue.get_free_buffer().unwrap();
+        
+        // Without CUDA the request is remembered but the buffer stays pageable
+        assert!(buffer.pinned_requested());
+        assert!(!buffer.is_pinned());
+        assert!(buffer.stage_pinned().is_none());
+        
+        buffer.data.extend_from_slice(b"still usable");
+        queue.submit_buffer(buffer);
+        let buffer = queue.get_ready_buffer().unwrap();
+        assert_eq!(buffer.data, b"still usable");
+        
+        queue.return_buffer(buffer);
+        assert!(queue.get_free_buffer().unwrap().pinned_requested());
+        
+        assert!(!PrefetchBuffer::new(64).pinned_requested());
+    }
+    
     #[test]
     fn test_prefetch_queue() {
         let config = PrefetchConfig {
@@ -337,7 +899,7 @@ mod tests {
         let config = PrefetchConfig {
             num_buffers: 4,
             buffer_size: 1024,
-            num_workers: 1,
+            num_loader_workers: 1,
             ..Default::default()
         };
         
@@ -371,4 +933,264 @@ mod tests {
         assert!(produced >= 3);
         assert!(consumed >= 3);
     }
+    
+    /// Produce `count` single-byte buffers `0..count` with one worker, then
+    /// stop the pipeline and return the order `next()` delivers them in.
+    fn delivery_order(count: u8, shuffle_buffer_size: usize, seed: u64) -> Vec<u8> {
+        let config = PrefetchConfig {
+            num_buffers: count as usize + 1,
+            buffer_size: 16,
+            num_loader_workers: 1,
+            shuffle_buffer_size,
+            shuffle_seed: Some(seed),
+            ..Default::default()
+        };
+        let mut pipeline = PrefetchPipeline::new(config);
+        
+        let counter = Arc::new(AtomicUsize::new(0));
+        let counter_clone = Arc::clone(&counter);
+        pipeline.start(move |buffer| {
+            let id = counter_clone.fetch_add(1, Ordering::SeqCst);
+            if id >= count as usize {
+                return false;
+            }
+            buffer.data.push(id as u8);
+            true
+        });
+        
+        while pipeline.stats().0 < count as usize {
+            thread::sleep(std::time::Duration::from_millis(1));
+        }
+        pipeline.stop();
+        
+        let mut order = Vec::new();
+        while let Some(buffer) = pipeline.next() {
+            order.push(buffer.data[0]);
+        }
+        order
+    }
+    
+    #[test]
+    fn test_prefetch_shuffle_buffer() {
+        let fifo: Vec<u8> = (0..10).collect();
+        assert_eq!(delivery_order(10, 0, 7), fifo);
+        
+        let shuffled = delivery_order(10, 4, 7);
+        assert_ne!(shuffled, fifo, "shuffle buffer should break FIFO order");
+        let mut sorted = shuffled.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, fifo, "every buffer is delivered exactly once");
+        
+        // Same seed, same order
+        assert_eq!(delivery_order(10, 4, 7), shuffled);
+    }
+    
+    #[test]
+    fn test_prefetch_shuffle_buffer_is_capped() {
+        let config = PrefetchConfig {
+            num_buffers: 3,
+            buffer_size: 16,
+            shuffle_buffer_size: 8,
+            ..Default::default()
+        };
+        let pipeline = PrefetchPipeline::new(config);
+        assert_eq!(pipeline.shuffle.as_ref().unwrap().lock().capacity, 2);
+    }
+    
+    #[test]
+    fn test_prefetch_epochs() {
+        const DATASET: usize = 5;
+        let config = PrefetchConfig {
+            num_buffers: 2,
+            buffer_size: 16,
+            num_loader_workers: 2,
+            ..Default::default()
+        };
+        let mut pipeline = PrefetchPipeline::new(config);
+        
+        let cursor = Arc::new(AtomicUsize::new(0));
+        let ended = Arc::new(Mutex::new(Vec::new()));
+        let (loader_cursor, rewind_cursor, ended_clone) = (cursor.clone(), cursor.clone(), ended.clone());
+        pipeline.on_epoch_end(move |epoch| {
+            ended_clone.lock().push(epoch);
+            rewind_cursor.store(0, Ordering::SeqCst);
+        });
+        pipeline.start(move |buffer| {
+            let sample = loader_cursor.fetch_add(1, Ordering::SeqCst);
+            if sample >= DATASET {
+                return false;
+            }
+            buffer.data.push(sample as u8);
+            buffer.num_samples = 1;
+            true
+        });
+        
+        let drain_epoch = || {
+            let mut samples = Vec::new();
+            while let Some(buffer) = pipeline.next() {
+                samples.push(buffer.data[0]);
+                pipeline.recycle(buffer);
+            }
+            samples.sort_unstable();
+            samples
+        };
+        
+        let all: Vec<u8> = (0..DATASET as u8).collect();
+        assert_eq!(drain_epoch(), all);
+        assert_eq!(pipeline.epochs_completed(), 1);
+        assert!(pipeline.next().is_none(), "end-of-data persists until reset");
+        
+        assert!(pipeline.reset_epoch());
+        assert_eq!(pipeline.epoch(), 1);
+        assert_eq!(drain_epoch(), all);
+        assert_eq!(pipeline.epochs_completed(), 2);
+        assert_eq!(*ended.lock(), vec![0, 1]);
+        
+        let (produced, consumed, _) = pipeline.stats();
+        assert_eq!((produced, consumed), (2 * DATASET, 2 * DATASET));
+        
+        pipeline.stop();
+    }
+    
+    #[test]
+    fn test_prefetch_reset_epoch_mid_epoch_is_noop() {
+        let mut pipeline = PrefetchPipeline::new(PrefetchConfig {
+            num_buffers: 2,
+            buffer_size: 16,
+            num_loader_workers: 1,
+            ..Default::default()
+        });
+        pipeline.start(|buffer| {
+            buffer.data.push(0);
+            true
+        });
+        
+        assert!(!pipeline.reset_epoch());
+        assert_eq!(pipeline.epoch(), 0);
+        pipeline.stop();
+    }
+    
+    #[test]
+    fn test_prefetch_transform_stage() {
+        const BATCHES: usize = 20;
+        let mut pipeline = PrefetchPipeline::new(PrefetchConfig {
+            num_buffers: 4,
+            buffer_size: 16,
+            num_loader_workers: 2,
+            num_transform_workers: 3,
+            ..Default::default()
+        });
+        
+        let loaded = Arc::new(AtomicUsize::new(0));
+        let loaded_clone = Arc::clone(&loaded);
+        pipeline.start_with_transform(
+            move |buffer| {
+                if loaded_clone.fetch_add(1, Ordering::SeqCst) >= BATCHES {
+                    return false;
+                }
+                buffer.data.resize(8, 0);
+                buffer.num_samples = 1;
+                true
+            },
+            |buffer| {
+                // Slow enough that loads finish while transforms are pending
+                thread::sleep(std::time::Duration::from_millis(2));
+                for byte in buffer.data.iter_mut() {
+                    *byte += 1;
+                }
+            },
+        );
+        
+        // End of data is only reported once the transforms have caught up
+        let mut consumed = 0;
+        while let Some(buffer) = pipeline.next() {
+            assert_eq!(buffer.data, vec![1; 8], "transform must run exactly once per buffer");
+            consumed += 1;
+            pipeline.recycle(buffer);
+        }
+        assert_eq!(consumed, BATCHES);
+        
+        let stages = pipeline.stage_stats();
+        assert_eq!((stages.loaded, stages.transformed), (BATCHES, BATCHES));
+        assert_eq!(stages.transform_queue_depth, 0);
+        assert!(!pipeline.is_degraded());
+        pipeline.stop();
+    }
+    
+    #[test]
+    fn test_prefetch_transform_panic_isolation() {
+        let mut pipeline = PrefetchPipeline::new(PrefetchConfig {
+            num_buffers: 2,
+            buffer_size: 16,
+            num_loader_workers: 1,
+            num_transform_workers: 1,
+            ..Default::default()
+        });
+        
+        let loaded = Arc::new(AtomicUsize::new(0));
+        let loaded_clone = Arc::clone(&loaded);
+        pipeline.start_with_transform(
+            move |buffer| {
+                let id = loaded_clone.fetch_add(1, Ordering::SeqCst);
+                if id >= 4 {
+                    return false;
+                }
+                buffer.data.push(id as u8);
+                true
+            },
+            |buffer| assert_ne!(buffer.data[0], 1, "bad sample"),
+        );
+        
+        let mut seen = Vec::new();
+        while let Some(buffer) = pipeline.next() {
+            seen.push(buffer.data[0]);
+            pipeline.recycle(buffer);
+        }
+        assert_eq!(seen, vec![0, 2, 3]);
+        assert_eq!(pipeline.stage_stats().transform_panics, 1);
+        assert!(pipeline.is_degraded());
+        pipeline.stop();
+    }
+    
+    #[test]
+    fn test_prefetch_worker_panic_isolation() {
+        let config = PrefetchConfig {
+            num_buffers: 2,
+            buffer_size: 1024,
+            num_loader_workers: 1,
+            ..Default::default()
+        };
+        
+        let mut pipeline = PrefetchPipeline::new(config);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let calls_clone = Arc::clone(&calls);
+        
+        pipeline.start(move |buffer| {
+            let call = calls_clone.fetch_add(1, Ordering::SeqCst) + 1;
+            if call == 3 {
+                panic!("loader failure on call 3");
+            }
+            if call > 6 {
+                return false;
+            }
+            
+            buffer.data.push(call as u8);
+            buffer.num_samples = 1;
+            true
+        });
+        
+        // Calls 1, 2, 4, 5, 6 produce buffers; the panicking call 3 must not stall the pipeline
+        let mut seen = Vec::new();
+        for _ in 0..5 {
+            let buffer = pipeline.next().expect("pipeline stalled after loader panic");
+            seen.push(buffer.data[0]);
+            pipeline.recycle(buffer);
+        }
+        
+        assert_eq!(seen, vec![1, 2, 4, 5, 6]);
+        assert_eq!(pipeline.worker_panics(), 1);
+        assert!(pipeline.is_degraded());
+        
+        pipeline.stop();
+    }
 }
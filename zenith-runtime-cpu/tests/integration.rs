@@ -74,6 +74,8 @@ fn integration_memory_pool_stress() {
         initial_slabs: 16,
         max_slabs: 256,
         alignment: 64,
+        auto_trim_ratio: None,
+        numa_node: None,
     };
     
     let pool = MemoryPool::new(config).unwrap();
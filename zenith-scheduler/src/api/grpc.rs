@@ -2,10 +2,13 @@
 
 use tonic::Status;
 use std::sync::Arc;
-use crate::scheduler::Scheduler;
+use crate::scheduler::{ProjectQuota, Scheduler};
 use crate::node::NodeRegistry;
-use crate::job::{Job, JobDescriptor, ResourceRequirements, LocalityPreferences, SchedulingPolicy};
+use crate::job::{Job, JobDescriptor, JobState, ResourceRequirements, LocalityPreferences, SchedulingPolicy};
+use crate::logs::LogLine;
 use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use tokio::sync::mpsc::Receiver;
 
 /// Job submission request
 #[derive(Debug, Clone)]
@@ -22,6 +25,10 @@ pub struct SubmitJobRequest {
     pub memory_mb: u64,
     pub priority: i32,
     pub gang_schedule: bool,
+    /// Only run on nodes carrying all of these labels
+    pub node_selector: HashMap<String, String>,
+    /// IDs of jobs that must complete before this one is queued
+    pub depends_on: Vec<String>,
 }
 
 /// Job submission response
@@ -31,6 +38,17 @@ pub struct SubmitJobResponse {
     pub status: String,
 }
 
+/// Dry-run scheduling result
+#[derive(Debug, Clone)]
+pub struct DryRunResponse {
+    /// Whether the job could be scheduled right now
+    pub fits: bool,
+    /// Node ID -> GPU device IDs the job would get
+    pub allocations: HashMap<String, Vec<String>>,
+    /// Why the job can't be scheduled, if it doesn't fit
+    pub reason: String,
+}
+
 /// Job status request
 #[derive(Debug, Clone)]
 pub struct GetJobStatusRequest {
@@ -44,6 +62,16 @@ pub struct GetJobStatusResponse {
     pub state: String,
     pub message: String,
     pub allocated_nodes: Vec<String>,
+    /// Current attempt, starting at 1; grows as the job is retried
+    pub attempt: u32,
+    /// When the job was submitted
+    pub submitted_at: DateTime<Utc>,
+    /// When the current attempt started running
+    pub started_at: Option<DateTime<Utc>>,
+    /// When the job reached a final state
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Time since submission, frozen once the job finishes
+    pub duration_seconds: f64,
 }
 
 /// Cancel job request
@@ -60,6 +88,26 @@ pub struct CancelJobResponse {
     pub message: String,
 }
 
+/// Result of cancelling every job in a project
+#[derive(Debug, Clone)]
+pub struct CancelBatchResponse {
+    /// Jobs this call cancelled
+    pub cancelled: usize,
+    /// Jobs that had already finished, including ones cancelled earlier
+    pub already_terminal: usize,
+}
+
+/// Job log streaming request
+#[derive(Debug, Clone)]
+pub struct StreamLogsRequest {
+    /// Job whose output to stream
+    pub job_id: String,
+    /// Keep streaming new output until the job finishes
+    pub follow: bool,
+    /// Most recent lines to send first (0 = none)
+    pub tail_lines: u32,
+}
+
 /// Cluster status response
 #[derive(Debug, Clone)]
 pub struct ClusterStatusResponse {
@@ -89,6 +137,50 @@ impl SchedulerService {
     /// Submit a job
     #[allow(clippy::result_large_err)]
     pub fn submit_job(&self, request: SubmitJobRequest) -> Result<SubmitJobResponse, Status> {
+        let job = Self::build_job(request)?;
+        
+        match self.scheduler.submit(job) {
+            Ok(job_id) => {
+                // Blocked behind dependencies, or already failed because of them
+                let status = self.scheduler.get_job(&job_id)
+                    .map_or("QUEUED", |job| wire_state(job.state))
+                    .to_string();
+                Ok(SubmitJobResponse { job_id, status })
+            }
+            Err(e @ crate::Error::QuotaExceeded(_)) => Err(Status::resource_exhausted(e.to_string())),
+            Err(e @ crate::Error::Dependency(_)) => Err(Status::invalid_argument(e.to_string())),
+            Err(e) => Err(Status::internal(e.to_string())),
+        }
+    }
+    
+    /// Report where a job would be placed, without submitting it
+    #[allow(clippy::result_large_err)]
+    pub fn schedule_dry_run(&self, request: SubmitJobRequest) -> Result<DryRunResponse, Status> {
+        let job = Self::build_job(request)?;
+        
+        match self.scheduler.dry_run(&job) {
+            Ok(decision) => Ok(DryRunResponse {
+                fits: true,
+                allocations: decision.allocations,
+                reason: String::new(),
+            }),
+            Err(e @ (crate::Error::QuotaExceeded(_) | crate::Error::Scheduling(_))) => Ok(DryRunResponse {
+                fits: false,
+                allocations: HashMap::new(),
+                reason: e.to_string(),
+            }),
+            Err(e) => Err(Status::internal(e.to_string())),
+        }
+    }
+    
+    #[allow(clippy::result_large_err)]
+    fn build_job(request: SubmitJobRequest) -> Result<Job, Status> {
+        let resources = ResourceRequirements::from_request(
+            request.gpu_count,
+            request.cpu_cores,
+            request.memory_mb,
+        ).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        
         let descriptor = JobDescriptor {
             name: request.name,
             user_id: request.user_id,
@@ -97,13 +189,11 @@ impl SchedulerService {
             arguments: request.arguments,
             environment: request.environment,
             working_directory: request.working_directory,
-            resources: ResourceRequirements {
-                gpu_count: request.gpu_count,
-                cpu_cores: request.cpu_cores,
-                cpu_memory: request.memory_mb * 1024 * 1024, // Convert MB to bytes
+            resources,
+            locality: LocalityPreferences {
+                node_selector: request.node_selector,
                 ..Default::default()
             },
-            locality: LocalityPreferences::default(),
             policy: SchedulingPolicy {
                 priority: request.priority,
                 gang_schedule: request.gang_schedule,
@@ -111,17 +201,18 @@ impl SchedulerService {
             },
             labels: HashMap::new(),
             annotations: HashMap::new(),
+            depends_on: request.depends_on,
         };
         
-        let job = Job::new(descriptor);
-        
-        match self.scheduler.submit(job) {
-            Ok(job_id) => Ok(SubmitJobResponse {
-                job_id,
-                status: "QUEUED".to_string(),
-            }),
-            Err(e) => Err(Status::internal(e.to_string())),
-        }
+        Ok(Job::new(descriptor))
+    }
+    
+    /// Set a project's resource quota (admin)
+    pub fn set_project_quota(&self, project_id: &str, max_gpus: u32, max_concurrent_jobs: usize) {
+        self.scheduler.set_project_quota(project_id, ProjectQuota {
+            max_gpus,
+            max_concurrent_jobs,
+        });
     }
     
     /// Get job status
@@ -132,6 +223,11 @@ impl SchedulerService {
                 job_id: job.id.to_string(),
                 state: format!("{:?}", job.state),
                 message: job.message.clone(),
+                attempt: job.attempt(),
+                submitted_at: job.submit_time,
+                started_at: job.start_time,
+                finished_at: job.end_time,
+                duration_seconds: job.elapsed_seconds(),
                 allocated_nodes: job.allocated_nodes,
             }),
             None => Err(Status::not_found(format!("Job not found: {}", request.job_id))),
@@ -150,6 +246,25 @@ impl SchedulerService {
         }
     }
     
+    /// Cancel all queued and running jobs in a project (admin)
+    #[allow(clippy::result_large_err)]
+    pub fn cancel_jobs_by_project(&self, project_id: &str, reason: &str) -> Result<CancelBatchResponse, Status> {
+        if project_id.trim().is_empty() {
+            return Err(Status::invalid_argument("project_id must not be empty"));
+        }
+        
+        let (cancelled, already_terminal) = self.scheduler.cancel_project(project_id, reason);
+        Ok(CancelBatchResponse { cancelled, already_terminal })
+    }
+    
+    /// Stream a job's output
+    #[allow(clippy::result_large_err)]
+    pub fn stream_job_logs(&self, request: StreamLogsRequest) -> Result<Receiver<LogLine>, Status> {
+        self.scheduler
+            .subscribe_job_logs(&request.job_id, request.tail_lines as usize, request.follow)
+            .map_err(|_| Status::not_found(format!("Job not found: {}", request.job_id)))
+    }
+    
     /// Get cluster status
     pub fn get_cluster_status(&self) -> ClusterStatusResponse {
         let summary = self.node_registry.summary();
@@ -165,6 +280,23 @@ impl SchedulerService {
     }
 }
 
+/// Status string sent to clients for a job state
+fn wire_state(state: JobState) -> &'static str {
+    match state {
+        JobState::Pending => "PENDING",
+        JobState::Blocked => "BLOCKED",
+        JobState::Queued => "QUEUED",
+        JobState::Scheduled => "SCHEDULED",
+        JobState::Running => "RUNNING",
+        JobState::Suspended => "SUSPENDED",
+        JobState::Completed => "COMPLETED",
+        JobState::Failed => "FAILED",
+        JobState::Cancelled => "CANCELLED",
+        JobState::Timeout => "TIMEOUT",
+        JobState::DependencyFailed => "DEPENDENCY_FAILED",
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -191,6 +323,8 @@ mod tests {
             memory_mb: 16384,
             priority: 50,
             gang_schedule: true,
+            node_selector: HashMap::new(),
+            depends_on: vec![],
         }
     }
     
@@ -229,6 +363,8 @@ mod tests {
             memory_mb: 8192,
             priority: 100,
             gang_schedule: false,
+            node_selector: HashMap::new(),
+            depends_on: vec![],
         };
         
         assert_eq!(request.environment.get("CUDA_VISIBLE_DEVICES"), Some(&"0,1".to_string()));
@@ -259,6 +395,11 @@ mod tests {
             state: "Running".to_string(),
             message: "Job is running".to_string(),
             allocated_nodes: vec!["node1".to_string(), "node2".to_string()],
+            attempt: 1,
+            submitted_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+            duration_seconds: 0.0,
         };
         assert_eq!(response.job_id, "job-789");
         assert_eq!(response.state, "Running");
@@ -336,6 +477,18 @@ mod tests {
         assert_eq!(response.status, "QUEUED");
     }
     
+    #[test]
+    fn test_submit_job_after_failed_dependency() {
+        let service = create_test_service();
+        let parent = service.submit_job(create_test_request()).unwrap().job_id;
+        service.scheduler.cancel(&parent, "test").unwrap();
+        
+        let mut request = create_test_request();
+        request.depends_on = vec![parent];
+        let response = service.submit_job(request).unwrap();
+        assert_eq!(response.status, "DEPENDENCY_FAILED");
+    }
+    
     #[test]
     fn test_submit_multiple_jobs() {
         let service = create_test_service();
@@ -352,6 +505,121 @@ mod tests {
         assert_eq!(status.queued_jobs, 5);
     }
     
+    #[test]
+    fn test_submit_job_rejects_bad_resources() {
+        let service = create_test_service();
+        
+        let mut request = create_test_request();
+        request.memory_mb = 0;
+        let status = service.submit_job(request).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        
+        // Used to overflow when converted to bytes
+        let mut request = create_test_request();
+        request.memory_mb = u64::MAX;
+        let status = service.submit_job(request).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert!(status.message().contains("memory_mb"));
+        
+        let mut request = create_test_request();
+        request.cpu_cores = 0;
+        let status = service.submit_job(request).unwrap_err();
+        assert!(status.message().contains("cpu_cores"));
+        
+        assert_eq!(service.get_cluster_status().queued_jobs, 0);
+    }
+    
+    #[test]
+    fn test_submit_job_over_quota() {
+        let service = create_test_service();
+        service.set_project_quota("project1", 4, 10);
+        
+        // create_test_request asks for 4 GPUs
+        assert!(service.submit_job(create_test_request()).is_ok());
+        
+        let status = service.submit_job(create_test_request()).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+        assert!(status.message().contains("Quota exceeded"));
+        assert_eq!(service.get_cluster_status().queued_jobs, 1);
+    }
+    
+    fn register_test_node(service: &SchedulerService, id: &str, gpu_count: usize) {
+        use crate::node::{GpuDevice, Node, NodeTopology};
+        
+        let gpus = (0..gpu_count).map(|i| GpuDevice {
+            device_id: format!("cuda:{}", i),
+            device_name: "NVIDIA A100".to_string(),
+            uuid: format!("GPU-{}-{}", id, i),
+            total_memory: 80 * 1024 * 1024 * 1024,
+            free_memory: 80 * 1024 * 1024 * 1024,
+            utilization: 0.0,
+            temperature: 40,
+            allocated: false,
+            allocated_job_id: None,
+        }).collect();
+        let topology = NodeTopology {
+            gpus,
+            cpu_cores: 64,
+            cpu_memory: 512 * 1024 * 1024 * 1024,
+            cpu_memory_free: 500 * 1024 * 1024 * 1024,
+            numa_nodes: 2,
+            nvlink_present: true,
+            nvswitch_present: false,
+            rdma_capable: true,
+        };
+        service.node_registry.register(Node::new(
+            id.to_string(),
+            format!("{}.local", id),
+            "192.168.1.1".to_string(),
+            topology,
+        )).unwrap();
+    }
+    
+    #[test]
+    fn test_schedule_dry_run_fits() {
+        let service = create_test_service();
+        register_test_node(&service, "node-1", 8);
+        
+        // create_test_request asks for 4 GPUs
+        let response = service.schedule_dry_run(create_test_request()).unwrap();
+        assert!(response.fits, "{}", response.reason);
+        assert_eq!(response.allocations.len(), 1);
+        assert_eq!(response.allocations["node-1"].len(), 4);
+        assert!(response.reason.is_empty());
+        
+        // Nothing was queued
+        assert_eq!(service.get_cluster_status().queued_jobs, 0);
+        assert!(service.scheduler.jobs_with_state(crate::job::JobState::Queued).is_empty());
+    }
+    
+    #[test]
+    fn test_schedule_dry_run_does_not_fit() {
+        let service = create_test_service();
+        
+        let response = service.schedule_dry_run(create_test_request()).unwrap();
+        assert!(!response.fits);
+        assert!(response.allocations.is_empty());
+        assert!(response.reason.contains("needs 4 GPUs but only 0 are free"), "{}", response.reason);
+        
+        register_test_node(&service, "node-1", 2);
+        let response = service.schedule_dry_run(create_test_request()).unwrap();
+        assert!(response.reason.contains("only 2 are free"), "{}", response.reason);
+        
+        // Quota rejections are reported the same way
+        register_test_node(&service, "node-2", 8);
+        service.set_project_quota("project1", 2, 10);
+        let response = service.schedule_dry_run(create_test_request()).unwrap();
+        assert!(!response.fits);
+        assert!(response.reason.contains("Quota exceeded"), "{}", response.reason);
+        
+        // Malformed requests are still invalid arguments
+        let mut request = create_test_request();
+        request.cpu_cores = 0;
+        let status = service.schedule_dry_run(request).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert_eq!(service.get_cluster_status().queued_jobs, 0);
+    }
+    
     #[test]
     fn test_get_job_status_not_found() {
         let service = create_test_service();
@@ -382,6 +650,38 @@ mod tests {
         
         let status = status_result.unwrap();
         assert_eq!(status.job_id, job_id);
+        assert_eq!(status.attempt, 1);
+    }
+    
+    #[test]
+    fn test_job_status_timestamps() {
+        let service = create_test_service();
+        let job_id = service.submit_job(create_test_request()).unwrap().job_id;
+        let status = || service.get_job_status(GetJobStatusRequest { job_id: job_id.clone() }).unwrap();
+        let pause = || std::thread::sleep(std::time::Duration::from_millis(20));
+        
+        let queued = status();
+        assert!(queued.started_at.is_none());
+        assert!(queued.finished_at.is_none());
+        
+        pause();
+        service.scheduler.mark_job_started(&job_id).unwrap();
+        let running = status();
+        assert_eq!(running.submitted_at, queued.submitted_at);
+        assert!(running.started_at.unwrap() > running.submitted_at);
+        assert!(running.finished_at.is_none());
+        assert!(running.duration_seconds > queued.duration_seconds);
+        
+        pause();
+        service.scheduler.mark_job_completed(&job_id, true, "done").unwrap();
+        let finished = status();
+        assert_eq!(finished.started_at, running.started_at);
+        assert!(finished.finished_at.unwrap() > running.started_at.unwrap());
+        assert!(finished.duration_seconds >= 0.04);
+        
+        // The clock stops once the job finishes
+        pause();
+        assert_eq!(status().duration_seconds, finished.duration_seconds);
     }
     
     #[test]
@@ -396,6 +696,93 @@ mod tests {
         assert!(result.is_err());
     }
     
+    #[tokio::test]
+    async fn test_stream_job_logs() {
+        use crate::logs::LogStream;
+        
+        let service = create_test_service();
+        let job_id = service.submit_job(create_test_request()).unwrap().job_id;
+        let scheduler = &service.scheduler;
+        let request = |follow, tail_lines| StreamLogsRequest { job_id: job_id.clone(), follow, tail_lines };
+        
+        for i in 0..5 {
+            scheduler.append_job_log(&job_id, LogStream::Stdout, &format!("line {}", i)).unwrap();
+        }
+        scheduler.append_job_log(&job_id, LogStream::Stderr, "warn\x1b[0m").unwrap();
+        
+        // Tail without follow: the last lines, then the stream ends
+        let mut rx = service.stream_job_logs(request(false, 2)).unwrap();
+        let tail: Vec<_> = std::iter::from_fn(|| rx.try_recv().ok()).collect();
+        assert_eq!(tail.iter().map(|l| l.text.as_str()).collect::<Vec<_>>(), vec!["line 4", "warn[0m"]);
+        assert_eq!(tail[1].stream, LogStream::Stderr);
+        assert!(rx.recv().await.is_none());
+        
+        // Follow: backfill, then live lines in order, until the job ends
+        let mut rx = service.stream_job_logs(request(true, 1)).unwrap();
+        scheduler.append_job_log(&job_id, LogStream::Stdout, "line 6").unwrap();
+        scheduler.append_job_log(&job_id, LogStream::Stdout, "line 7").unwrap();
+        service.cancel_job(CancelJobRequest { job_id: job_id.clone(), reason: "done".to_string() }).unwrap();
+        let mut texts = Vec::new();
+        while let Some(line) = rx.recv().await {
+            texts.push(line.text);
+        }
+        assert_eq!(texts, vec!["warn[0m", "line 6", "line 7"]);
+        
+        // Finished jobs only get the backfill
+        let mut rx = service.stream_job_logs(request(true, 100)).unwrap();
+        let mut count = 0;
+        while rx.recv().await.is_some() {
+            count += 1;
+        }
+        assert_eq!(count, 8);
+        
+        let status = service.stream_job_logs(StreamLogsRequest {
+            job_id: "non-existent-job".to_string(),
+            follow: true,
+            tail_lines: 10,
+        }).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+        assert!(scheduler.append_job_log("non-existent-job", LogStream::Stdout, "x").is_err());
+    }
+    
+    #[test]
+    fn test_cancel_jobs_by_project() {
+        let service = create_test_service();
+        let submit = |project_id: &str| {
+            let request = SubmitJobRequest {
+                project_id: project_id.to_string(),
+                ..create_test_request()
+            };
+            service.submit_job(request).unwrap().job_id
+        };
+        let doomed = [submit("project1"), submit("project1"), submit("project1")];
+        let kept = [submit("project2"), submit("project2")];
+        service.cancel_job(CancelJobRequest { job_id: doomed[0].clone(), reason: "early".to_string() }).unwrap();
+        
+        let response = service.cancel_jobs_by_project("project1", "project shut down").unwrap();
+        assert_eq!(response.cancelled, 2);
+        assert_eq!(response.already_terminal, 1);
+        for job_id in &doomed {
+            let status = service.get_job_status(GetJobStatusRequest { job_id: job_id.clone() }).unwrap();
+            assert_eq!(status.state, "Cancelled");
+        }
+        for job_id in &kept {
+            let status = service.get_job_status(GetJobStatusRequest { job_id: job_id.clone() }).unwrap();
+            assert_eq!(status.state, "Queued");
+        }
+        assert_eq!(service.get_cluster_status().queued_jobs, 2);
+        
+        // Repeating it changes nothing
+        let response = service.cancel_jobs_by_project("project1", "again").unwrap();
+        assert_eq!(response.cancelled, 0);
+        assert_eq!(response.already_terminal, 3);
+        
+        for project_id in ["", "  "] {
+            let status = service.cancel_jobs_by_project(project_id, "oops").unwrap_err();
+            assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        }
+    }
+    
     #[test]
     fn test_get_cluster_status_empty() {
         let service = create_test_service();
@@ -434,6 +821,11 @@ mod tests {
             state: "Running".to_string(),
             message: "OK".to_string(),
             allocated_nodes: vec![],
+            attempt: 1,
+            submitted_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+            duration_seconds: 0.0,
         };
         let cloned = status_resp.clone();
         assert_eq!(status_resp.state, cloned.state);
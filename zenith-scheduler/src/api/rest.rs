@@ -58,6 +58,12 @@ pub struct SubmitJobRequest {
     pub priority: i32,
     #[serde(default)]
     pub gang_schedule: bool,
+    /// Only run on nodes carrying all of these labels
+    #[serde(default)]
+    pub node_selector: HashMap<String, String>,
+    /// IDs of jobs that must complete before this one is queued
+    #[serde(default)]
+    pub depends_on: Vec<String>,
 }
 
 fn default_working_dir() -> String { "/app".to_string() }
@@ -118,6 +124,24 @@ async fn submit_job(
     State(state): State<Arc<AppState>>,
     Json(request): Json<SubmitJobRequest>,
 ) -> impl IntoResponse {
+    let resources = match ResourceRequirements::from_request(
+        request.gpu_count,
+        request.cpu_cores,
+        request.memory_mb,
+    ) {
+        Ok(resources) => resources,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(JobResponse {
+            job_id: "".to_string(),
+            name: "error".to_string(),
+            state: e.to_string(),
+            user_id: "".to_string(),
+            project_id: "".to_string(),
+            created_at: "".to_string(),
+            allocated_nodes: vec![],
+            gpu_count: 0,
+        })),
+    };
+    
     let descriptor = JobDescriptor {
         name: request.name,
         user_id: request.user_id,
@@ -126,13 +150,11 @@ async fn submit_job(
         arguments: request.arguments,
         environment: request.environment,
         working_directory: request.working_directory,
-        resources: ResourceRequirements {
-            gpu_count: request.gpu_count,
-            cpu_cores: request.cpu_cores,
-            cpu_memory: request.memory_mb * 1024 * 1024, // Convert MB to bytes
+        resources,
+        locality: LocalityPreferences {
+            node_selector: request.node_selector,
             ..Default::default()
         },
-        locality: LocalityPreferences::default(),
         policy: SchedulingPolicy {
             priority: request.priority,
             gang_schedule: request.gang_schedule,
@@ -140,6 +162,7 @@ async fn submit_job(
         },
         labels: HashMap::new(),
         annotations: HashMap::new(),
+        depends_on: request.depends_on,
     };
     
     let job = Job::new(descriptor);
@@ -300,6 +323,8 @@ mod tests {
             memory_mb: 8192,
             priority: 50,
             gang_schedule: false,
+            node_selector: HashMap::new(),
+            depends_on: vec![],
         }
     }
     
@@ -427,6 +452,7 @@ mod tests {
             policy: SchedulingPolicy::default(),
             labels: HashMap::new(),
             annotations: HashMap::new(),
+            depends_on: vec![],
         };
         
         let job = Job::new(descriptor);
@@ -455,6 +481,8 @@ mod tests {
             memory_mb: 1024, // 1GB in MB
             priority: 50,
             gang_schedule: false,
+            node_selector: HashMap::new(),
+            depends_on: vec![],
         };
         
         // Verify memory conversion: MB to bytes
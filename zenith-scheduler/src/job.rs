@@ -2,8 +2,15 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::Duration;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use crate::{Error, Result};
+
+/// Largest memory request a job may make, in MB (8TB)
+pub const MAX_MEMORY_MB: u64 = 8 * 1024 * 1024;
+/// Largest CPU core request a job may make
+pub const MAX_CPU_CORES: u32 = 1024;
 
 /// Job state
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -12,6 +19,8 @@ pub enum JobState {
     /// Job is pending submission
     #[default]
     Pending,
+    /// Job is waiting for the jobs it depends on to complete
+    Blocked,
     /// Job is queued waiting for resources
     Queued,
     /// Job has been scheduled to nodes
@@ -28,6 +37,8 @@ pub enum JobState {
     Cancelled,
     /// Job timed out
     Timeout,
+    /// A job this one depends on did not complete successfully
+    DependencyFailed,
 }
 
 
@@ -67,6 +78,34 @@ impl Default for ResourceRequirements {
     }
 }
 
+impl ResourceRequirements {
+    /// Build requirements from API request fields.
+    ///
+    /// Rejects zero or out-of-range CPU and memory requests; `memory_mb`
+    /// is converted to bytes with checked arithmetic.
+    pub fn from_request(gpu_count: u32, cpu_cores: u32, memory_mb: u64) -> Result<Self> {
+        if !(1..=MAX_CPU_CORES).contains(&cpu_cores) {
+            return Err(Error::Job(format!(
+                "cpu_cores out of range: {} not in [1, {}]", cpu_cores, MAX_CPU_CORES
+            )));
+        }
+        
+        let cpu_memory = Some(memory_mb)
+            .filter(|mb| (1..=MAX_MEMORY_MB).contains(mb))
+            .and_then(|mb| mb.checked_mul(1024 * 1024))
+            .ok_or_else(|| Error::Job(format!(
+                "memory_mb out of range: {} not in [1, {}]", memory_mb, MAX_MEMORY_MB
+            )))?;
+        
+        Ok(Self {
+            gpu_count,
+            cpu_cores,
+            cpu_memory,
+            ..Default::default()
+        })
+    }
+}
+
 /// Locality preferences
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LocalityPreferences {
@@ -80,6 +119,64 @@ pub struct LocalityPreferences {
     pub preferred_nodes: Vec<String>,
     /// Excluded node IDs
     pub excluded_nodes: Vec<String>,
+    /// Labels a node must carry, with these exact values, to run the job
+    #[serde(default)]
+    pub node_selector: HashMap<String, String>,
+    /// Further label requirements, all of which must hold
+    #[serde(default)]
+    pub node_selector_expressions: Vec<LabelRequirement>,
+}
+
+impl LocalityPreferences {
+    /// Whether a node with `labels` satisfies the node selector and every
+    /// selector expression
+    pub fn matches_labels(&self, labels: &HashMap<String, String>) -> bool {
+        self.node_selector.iter().all(|(key, value)| labels.get(key) == Some(value))
+            && self.node_selector_expressions.iter().all(|req| req.matches(labels))
+    }
+    
+    /// Whether the job restricts which nodes it can run on
+    pub fn has_node_selector(&self) -> bool {
+        !self.node_selector.is_empty() || !self.node_selector_expressions.is_empty()
+    }
+}
+
+/// How a `LabelRequirement` compares a node label
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LabelOperator {
+    /// Label is present with one of `values`
+    In,
+    /// Label is absent or has none of `values`
+    NotIn,
+    /// Label is present, whatever its value
+    Exists,
+    /// Label is absent
+    DoesNotExist,
+}
+
+/// One node-label condition, in the style of Kubernetes match expressions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LabelRequirement {
+    /// Label key
+    pub key: String,
+    /// Comparison to apply
+    pub operator: LabelOperator,
+    /// Values for `In`/`NotIn`; ignored otherwise
+    #[serde(default)]
+    pub values: Vec<String>,
+}
+
+impl LabelRequirement {
+    /// Whether a node with `labels` meets this requirement
+    pub fn matches(&self, labels: &HashMap<String, String>) -> bool {
+        let value = labels.get(&self.key);
+        match self.operator {
+            LabelOperator::In => value.is_some_and(|v| self.values.contains(v)),
+            LabelOperator::NotIn => value.is_none_or(|v| !self.values.contains(v)),
+            LabelOperator::Exists => value.is_some(),
+            LabelOperator::DoesNotExist => value.is_none(),
+        }
+    }
 }
 
 /// Scheduling policy
@@ -99,8 +196,8 @@ pub struct SchedulingPolicy {
     pub queue_name: String,
     /// Gang scheduling (all resources together)
     pub gang_schedule: bool,
-    /// Maximum retry attempts
-    pub max_retries: u32,
+    /// Re-queueing after retryable failures
+    pub retry: RetryPolicy,
 }
 
 impl Default for SchedulingPolicy {
@@ -113,11 +210,63 @@ impl Default for SchedulingPolicy {
             max_runtime_seconds: 0,       // unlimited
             queue_name: "default".to_string(),
             gang_schedule: true,
-            max_retries: 3,
+            retry: RetryPolicy::default(),
+        }
+    }
+}
+
+/// How a job that failed for a retryable reason is re-queued
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Total runs allowed, including the first (1 = never retry)
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubles for each retry after it
+    pub backoff_base: Duration,
+    /// Longest delay between attempts
+    pub backoff_max: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            backoff_base: Duration::from_secs(10),
+            backoff_max: Duration::from_secs(300),
         }
     }
 }
 
+impl RetryPolicy {
+    /// Delay before retry number `retry` (1-based)
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let doublings = retry.saturating_sub(1).min(31);
+        self.backoff_base
+            .checked_mul(1 << doublings)
+            .map_or(self.backoff_max, |delay| delay.min(self.backoff_max))
+    }
+}
+
+/// Why a scheduled or running job failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JobFailure {
+    /// A node the job was placed on died or became unreachable
+    NodeLost,
+    /// The job's request was rejected on the node
+    Validation,
+    /// The job exceeded its memory allowance and was killed by policy
+    OutOfMemory,
+    /// The job itself exited with an error
+    Application,
+}
+
+impl JobFailure {
+    /// Whether another attempt could succeed. Only failures outside the
+    /// job's control are retried; the rest would fail the same way again.
+    pub fn is_retryable(self) -> bool {
+        matches!(self, JobFailure::NodeLost)
+    }
+}
+
 /// Job descriptor - the core unit of work submission
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JobDescriptor {
@@ -145,6 +294,9 @@ pub struct JobDescriptor {
     pub labels: HashMap<String, String>,
     /// Annotations for metadata
     pub annotations: HashMap<String, String>,
+    /// IDs of jobs that must complete successfully before this one is queued
+    #[serde(default)]
+    pub depends_on: Vec<String>,
 }
 
 /// A job instance with state
@@ -168,8 +320,11 @@ pub struct Job {
     pub allocated_nodes: Vec<String>,
     /// Allocated GPU device IDs per node
     pub allocated_gpus: HashMap<String, Vec<String>>,
-    /// Retry count
+    /// Retries so far; the current attempt is `retry_count + 1`
     pub retry_count: u32,
+    /// Earliest time a retried job may be scheduled again
+    #[serde(default)]
+    pub retry_after: Option<DateTime<Utc>>,
     /// Last state change message
     pub message: String,
 }
@@ -188,6 +343,7 @@ impl Job {
             allocated_nodes: vec![],
             allocated_gpus: HashMap::new(),
             retry_count: 0,
+            retry_after: None,
             message: String::new(),
         }
     }
@@ -204,7 +360,8 @@ impl Job {
             JobState::Running => {
                 self.start_time = Some(Utc::now());
             }
-            JobState::Completed | JobState::Failed | JobState::Cancelled | JobState::Timeout => {
+            JobState::Completed | JobState::Failed | JobState::Cancelled | JobState::Timeout
+            | JobState::DependencyFailed => {
                 self.end_time = Some(Utc::now());
             }
             _ => {}
@@ -228,9 +385,26 @@ impl Job {
         }
     }
     
+    /// Seconds from submission until the job finished, or until now if
+    /// it hasn't
+    pub fn elapsed_seconds(&self) -> f64 {
+        let end = self.end_time.unwrap_or_else(Utc::now);
+        (end - self.submit_time).num_milliseconds().max(0) as f64 / 1000.0
+    }
+    
+    /// Current attempt number, starting at 1
+    pub fn attempt(&self) -> u32 {
+        self.retry_count.saturating_add(1)
+    }
+    
     /// Check if job can be retried
     pub fn can_retry(&self) -> bool {
-        self.retry_count < self.descriptor.policy.max_retries
+        self.attempt() < self.descriptor.policy.retry.max_attempts
+    }
+    
+    /// Whether the job is waiting out a retry backoff at `now`
+    pub fn in_backoff(&self, now: DateTime<Utc>) -> bool {
+        self.retry_after.is_some_and(|after| now < after)
     }
 }
 
@@ -252,9 +426,26 @@ mod tests {
             policy: SchedulingPolicy::default(),
             labels: HashMap::new(),
             annotations: HashMap::new(),
+            depends_on: vec![],
         }
     }
     
+    #[test]
+    fn test_resources_from_request() {
+        let res = ResourceRequirements::from_request(2, 8, 16384).unwrap();
+        assert_eq!(res.gpu_count, 2);
+        assert_eq!(res.cpu_cores, 8);
+        assert_eq!(res.cpu_memory, 16384 * 1024 * 1024);
+        
+        assert!(ResourceRequirements::from_request(0, 1, MAX_MEMORY_MB).is_ok());
+        assert!(ResourceRequirements::from_request(1, 1, 0).is_err());
+        assert!(ResourceRequirements::from_request(1, 1, MAX_MEMORY_MB + 1).is_err());
+        // Would have wrapped in the unchecked `memory_mb * 1024 * 1024`
+        assert!(ResourceRequirements::from_request(1, 1, u64::MAX / 1024).is_err());
+        assert!(ResourceRequirements::from_request(1, 0, 1024).is_err());
+        assert!(ResourceRequirements::from_request(1, MAX_CPU_CORES + 1, 1024).is_err());
+    }
+    
     #[test]
     fn test_job_creation() {
         let descriptor = create_test_descriptor();
@@ -276,4 +467,32 @@ mod tests {
         assert_eq!(job.state, JobState::Running);
         assert!(job.start_time.is_some());
     }
+    
+    #[test]
+    fn test_retry_backoff() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            backoff_base: Duration::from_secs(2),
+            backoff_max: Duration::from_secs(30),
+        };
+        let delays: Vec<u64> = (1..=6).map(|retry| policy.backoff(retry).as_secs()).collect();
+        assert_eq!(delays, vec![2, 4, 8, 16, 30, 30]);
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(30), "Large retries must not overflow");
+    }
+    
+    #[test]
+    fn test_can_retry_counts_first_attempt() {
+        let mut descriptor = create_test_descriptor();
+        descriptor.policy.retry.max_attempts = 2;
+        let mut job = Job::new(descriptor);
+        
+        assert_eq!(job.attempt(), 1);
+        assert!(job.can_retry());
+        job.retry_count = 1;
+        assert!(!job.can_retry());
+        
+        assert!(JobFailure::NodeLost.is_retryable());
+        assert!(!JobFailure::Validation.is_retryable());
+        assert!(!JobFailure::OutOfMemory.is_retryable());
+    }
 }
@@ -39,15 +39,17 @@ pub mod agent;
 pub mod api;
 pub mod config;
 pub mod job;
+pub mod logs;
 pub mod node;
 pub mod scheduler;
 pub mod state;
 
 // Re-exports
 pub use config::SchedulerConfig;
-pub use job::{Job, JobDescriptor, JobState};
+pub use job::{Job, JobDescriptor, JobFailure, JobState, RetryPolicy};
+pub use logs::{LogLine, LogStream};
 pub use node::{Node, NodeRegistry};
-pub use scheduler::Scheduler;
+pub use scheduler::{JobEvent, ProjectQuota, Scheduler};
 
 /// Crate version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -74,6 +76,14 @@ pub enum Error {
     #[error("Configuration error: {0}")]
     Config(String),
     
+    /// Project quota errors
+    #[error("Quota exceeded: {0}")]
+    QuotaExceeded(String),
+    
+    /// Unknown or cyclic job dependencies
+    #[error("Dependency error: {0}")]
+    Dependency(String),
+    
     /// I/O errors
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
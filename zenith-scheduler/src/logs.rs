@@ -0,0 +1,158 @@
+//! Job output buffering and streaming
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use tokio::sync::mpsc;
+
+/// Most recent lines kept per job for backfill
+pub const JOB_LOG_RETAINED_LINES: usize = 10_000;
+/// Buffered lines per follower, on top of the backfill, before new lines
+/// are dropped for it
+pub const JOB_LOG_CHANNEL_CAPACITY: usize = 1024;
+/// Longest line kept, in bytes; longer lines are truncated
+pub const MAX_LOG_LINE_LENGTH: usize = 10_000;
+
+/// Output stream a log line came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogStream {
+    /// Standard output
+    Stdout,
+    /// Standard error
+    Stderr,
+}
+
+/// A single line of job output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLine {
+    /// When the scheduler received the line
+    pub timestamp: DateTime<Utc>,
+    /// Stream the line was written to
+    pub stream: LogStream,
+    /// Sanitized line text
+    pub text: String,
+}
+
+/// Strip control characters (except tab and newline) and cap the length.
+///
+/// Same rules as `zenith_core::validation::sanitize_log_message`; the
+/// scheduler doesn't link the data plane core just for this.
+pub fn sanitize_log_message(message: &str) -> String {
+    let sanitized: String = message.chars()
+        .filter(|c| !c.is_control() || *c == '\n' || *c == '\t')
+        .collect();
+    if sanitized.len() <= MAX_LOG_LINE_LENGTH {
+        return sanitized;
+    }
+    let mut end = MAX_LOG_LINE_LENGTH;
+    while !sanitized.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}... [truncated]", &sanitized[..end])
+}
+
+/// Retained output and live followers of one job
+#[derive(Debug, Default)]
+pub(crate) struct JobLog {
+    lines: VecDeque<LogLine>,
+    followers: Vec<mpsc::Sender<LogLine>>,
+}
+
+impl JobLog {
+    /// Record a line and forward it to followers. Returns the number of
+    /// followers that were too far behind to receive it.
+    pub(crate) fn push(&mut self, line: LogLine) -> u64 {
+        let mut lagged = 0;
+        self.followers.retain(|tx| match tx.try_send(line.clone()) {
+            Ok(()) => true,
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                lagged += 1;
+                true
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => false,
+        });
+
+        if self.lines.len() == JOB_LOG_RETAINED_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+        lagged
+    }
+
+    /// Channel primed with the last `tail_lines` lines. With `follow`, new
+    /// lines are delivered until the job finishes; otherwise the channel
+    /// closes after the backfill.
+    pub(crate) fn subscribe(&mut self, tail_lines: usize, follow: bool) -> mpsc::Receiver<LogLine> {
+        let backfill = tail_lines.min(self.lines.len());
+        let (tx, rx) = mpsc::channel(backfill + JOB_LOG_CHANNEL_CAPACITY);
+        for line in self.lines.iter().skip(self.lines.len() - backfill) {
+            // Can't fail: the channel has room for the whole backfill
+            let _ = tx.try_send(line.clone());
+        }
+        if follow {
+            self.followers.push(tx);
+        }
+        rx
+    }
+
+    /// Disconnect followers so their streams end
+    pub(crate) fn close(&mut self) {
+        self.followers.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(text: &str) -> LogLine {
+        LogLine {
+            timestamp: Utc::now(),
+            stream: LogStream::Stdout,
+            text: text.to_string(),
+        }
+    }
+
+    fn drain(rx: &mut mpsc::Receiver<LogLine>) -> Vec<String> {
+        std::iter::from_fn(|| rx.try_recv().ok()).map(|l| l.text).collect()
+    }
+
+    #[test]
+    fn test_sanitize_log_message() {
+        assert_eq!(sanitize_log_message("ok\x1b[31m\tred\x00"), "ok[31m\tred");
+
+        let long = "é".repeat(MAX_LOG_LINE_LENGTH);
+        let sanitized = sanitize_log_message(&long);
+        assert!(sanitized.ends_with("... [truncated]"));
+        assert!(sanitized.len() <= MAX_LOG_LINE_LENGTH + "... [truncated]".len());
+    }
+
+    #[test]
+    fn test_retention_is_bounded() {
+        let mut log = JobLog::default();
+        for i in 0..JOB_LOG_RETAINED_LINES + 5 {
+            log.push(line(&i.to_string()));
+        }
+
+        let mut rx = log.subscribe(usize::MAX, false);
+        let lines = drain(&mut rx);
+        assert_eq!(lines.len(), JOB_LOG_RETAINED_LINES);
+        assert_eq!(lines[0], "5", "Oldest lines should be evicted first");
+    }
+
+    #[test]
+    fn test_lagging_follower_drops_lines() {
+        let mut log = JobLog::default();
+        let mut rx = log.subscribe(0, true);
+        let lagged: u64 = (0..JOB_LOG_CHANNEL_CAPACITY + 3)
+            .map(|i| log.push(line(&i.to_string())))
+            .sum();
+
+        assert_eq!(lagged, 3);
+        assert_eq!(drain(&mut rx).len(), JOB_LOG_CHANNEL_CAPACITY);
+
+        drop(rx);
+        log.push(line("after"));
+        assert!(log.followers.is_empty(), "Closed followers should be forgotten");
+    }
+}
@@ -1,13 +1,33 @@
 //! Gang Scheduler Implementation
 
-use crate::job::{Job, JobState};
+use crate::job::{Job, JobFailure, JobState};
+use crate::logs::{sanitize_log_message, JobLog, LogLine, LogStream};
 use crate::node::{Node, NodeRegistry};
 use crate::{Error, Result};
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use priority_queue::PriorityQueue;
-use tracing::{debug, info};
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+/// Buffered events per subscriber before new events are dropped for it
+pub const JOB_EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// A job state transition, as delivered to subscribers
+#[derive(Debug, Clone)]
+pub struct JobEvent {
+    /// Job ID
+    pub job_id: String,
+    /// State before the transition
+    pub old_state: JobState,
+    /// State after the transition
+    pub new_state: JobState,
+    /// When the transition happened
+    pub timestamp: DateTime<Utc>,
+}
 
 /// Scheduling decision for a job
 #[derive(Debug, Clone)]
@@ -20,6 +40,25 @@ pub struct SchedulingDecision {
     pub gang_allocated: bool,
 }
 
+/// Resource cap for a single project, covering its queued and running jobs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProjectQuota {
+    /// Maximum GPUs held or requested at once
+    pub max_gpus: u32,
+    /// Maximum queued + running jobs
+    pub max_concurrent_jobs: usize,
+}
+
+/// Where a job stands with respect to the jobs it depends on
+enum DependencyStatus {
+    /// Every dependency completed
+    Met,
+    /// Some dependency hasn't finished yet
+    Pending,
+    /// This dependency finished without completing
+    Failed(String),
+}
+
 /// Gang scheduler with topology awareness
 pub struct Scheduler {
     /// Node registry
@@ -30,6 +69,16 @@ pub struct Scheduler {
     jobs: RwLock<HashMap<String, Job>>,
     /// Scheduler configuration
     config: SchedulerConfig,
+    /// Job event subscribers
+    subscribers: Mutex<Vec<mpsc::Sender<JobEvent>>>,
+    /// Events dropped because a subscriber's channel was full
+    lagged_events: AtomicU64,
+    /// Per-project quotas; projects without an entry are unlimited
+    quotas: RwLock<HashMap<String, ProjectQuota>>,
+    /// Job output, keyed by job ID. Locked after `jobs` when both are held.
+    job_logs: Mutex<HashMap<String, JobLog>>,
+    /// Log lines dropped because a follower's channel was full
+    lagged_log_lines: AtomicU64,
 }
 
 /// Scheduler configuration
@@ -70,25 +119,301 @@ impl Scheduler {
             pending_queue: RwLock::new(PriorityQueue::new()),
             jobs: RwLock::new(HashMap::new()),
             config,
+            subscribers: Mutex::new(Vec::new()),
+            lagged_events: AtomicU64::new(0),
+            quotas: RwLock::new(HashMap::new()),
+            job_logs: Mutex::new(HashMap::new()),
+            lagged_log_lines: AtomicU64::new(0),
         }
     }
     
-    /// Submit a job
-    pub fn submit(&self, mut job: Job) -> Result<String> {
+    /// Set the quota for a project, replacing any existing one
+    pub fn set_project_quota(&self, project_id: &str, quota: ProjectQuota) {
+        info!(
+            "Project {} quota set: {} GPUs, {} concurrent jobs",
+            project_id, quota.max_gpus, quota.max_concurrent_jobs
+        );
+        self.quotas.write().insert(project_id.to_string(), quota);
+    }
+    
+    /// Remove a project's quota, leaving it unlimited
+    pub fn remove_project_quota(&self, project_id: &str) -> Option<ProjectQuota> {
+        self.quotas.write().remove(project_id)
+    }
+    
+    /// Get a project's quota
+    pub fn project_quota(&self, project_id: &str) -> Option<ProjectQuota> {
+        self.quotas.read().get(project_id).copied()
+    }
+    
+    /// GPUs and job count a project currently holds across blocked,
+    /// queued, scheduled and running jobs. Blocked jobs count so that
+    /// releasing them can never take the project over quota.
+    fn project_usage(jobs: &HashMap<String, Job>, project_id: &str) -> (u32, usize) {
+        jobs.values()
+            .filter(|j| j.descriptor.project_id == project_id)
+            .filter(|j| matches!(j.state, JobState::Blocked | JobState::Queued | JobState::Scheduled | JobState::Running))
+            .fold((0, 0), |(gpus, count), j| {
+                (gpus.saturating_add(j.descriptor.resources.gpu_count), count + 1)
+            })
+    }
+    
+    /// Reject a job that would push its project over quota
+    fn check_quota(&self, jobs: &HashMap<String, Job>, job: &Job) -> Result<()> {
+        let project_id = &job.descriptor.project_id;
+        let Some(quota) = self.project_quota(project_id) else {
+            return Ok(());
+        };
+        
+        let (used_gpus, active_jobs) = Self::project_usage(jobs, project_id);
+        let requested = job.descriptor.resources.gpu_count;
+        
+        if active_jobs >= quota.max_concurrent_jobs {
+            return Err(Error::QuotaExceeded(format!(
+                "project {} has {} active jobs, limit is {}",
+                project_id, active_jobs, quota.max_concurrent_jobs
+            )));
+        }
+        
+        if used_gpus.saturating_add(requested) > quota.max_gpus {
+            return Err(Error::QuotaExceeded(format!(
+                "project {} would use {} GPUs ({} in use + {} requested), limit is {}",
+                project_id,
+                used_gpus.saturating_add(requested),
+                used_gpus,
+                requested,
+                quota.max_gpus
+            )));
+        }
+        
+        Ok(())
+    }
+    
+    /// Subscribe to job state transitions.
+    ///
+    /// Each subscriber gets its own bounded channel. Events that arrive
+    /// while a subscriber's channel is full are dropped for that subscriber
+    /// and counted in [`Scheduler::lagged_events`].
+    pub fn subscribe_jobs(&self) -> mpsc::Receiver<JobEvent> {
+        let (tx, rx) = mpsc::channel(JOB_EVENT_CHANNEL_CAPACITY);
+        self.subscribers.lock().push(tx);
+        rx
+    }
+    
+    /// Number of job events dropped for slow subscribers
+    pub fn lagged_events(&self) -> u64 {
+        self.lagged_events.load(Ordering::Relaxed)
+    }
+    
+    /// Transition a job and notify subscribers
+    fn transition_job(&self, job: &mut Job, new_state: JobState, message: &str) {
+        let old_state = job.state;
+        job.transition(new_state, message);
+        if Self::is_finished(new_state) {
+            // End followed log streams; no more output is coming
+            if let Some(log) = self.job_logs.lock().get_mut(&job.id.to_string()) {
+                log.close();
+            }
+        }
+        self.emit(JobEvent {
+            job_id: job.id.to_string(),
+            old_state,
+            new_state,
+            timestamp: Utc::now(),
+        });
+    }
+    
+    fn emit(&self, event: JobEvent) {
+        let mut subscribers = self.subscribers.lock();
+        if subscribers.is_empty() {
+            return;
+        }
+        
+        subscribers.retain(|tx| match tx.try_send(event.clone()) {
+            Ok(()) => true,
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                let lagged = self.lagged_events.fetch_add(1, Ordering::Relaxed) + 1;
+                if lagged.is_power_of_two() {
+                    warn!("Job event subscriber lagging, {} events dropped so far", lagged);
+                }
+                true
+            }
+            // Receiver dropped, forget the subscriber
+            Err(mpsc::error::TrySendError::Closed(_)) => false,
+        });
+    }
+    
+    fn is_finished(state: JobState) -> bool {
+        matches!(
+            state,
+            JobState::Completed | JobState::Failed | JobState::Cancelled | JobState::Timeout
+                | JobState::DependencyFailed
+        )
+    }
+    
+    /// Reject dependencies on unknown jobs, and any that would close a cycle
+    fn check_dependencies(jobs: &HashMap<String, Job>, job: &Job) -> Result<()> {
         let job_id = job.id.to_string();
+        let depends_on = &job.descriptor.depends_on;
+        if let Some(unknown) = depends_on.iter().find(|dep| **dep != job_id && !jobs.contains_key(*dep)) {
+            return Err(Error::Dependency(format!("Job {} depends on unknown job {}", job_id, unknown)));
+        }
         
-        job.transition(JobState::Queued, "Submitted to scheduler");
+        // Reaching the job again from its own dependencies means a cycle
+        let mut stack: Vec<&str> = depends_on.iter().map(String::as_str).collect();
+        let mut seen = HashSet::new();
+        while let Some(dep) = stack.pop() {
+            if dep == job_id {
+                return Err(Error::Dependency(format!("Job {} has a dependency cycle", job_id)));
+            }
+            if seen.insert(dep) {
+                if let Some(dep_job) = jobs.get(dep) {
+                    stack.extend(dep_job.descriptor.depends_on.iter().map(String::as_str));
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    fn dependency_status(jobs: &HashMap<String, Job>, job: &Job) -> DependencyStatus {
+        let mut status = DependencyStatus::Met;
+        for dep in &job.descriptor.depends_on {
+            match jobs.get(dep).map(|dep_job| dep_job.state) {
+                Some(JobState::Completed) => {}
+                Some(state) if !Self::is_finished(state) => status = DependencyStatus::Pending,
+                _ => return DependencyStatus::Failed(dep.clone()),
+            }
+        }
+        status
+    }
+    
+    /// Settle blocked jobs whose dependencies have all finished: queue
+    /// them if every dependency completed, fail them otherwise. Returns
+    /// the jobs to put on `pending_queue`.
+    fn settle_blocked_jobs(&self, jobs: &mut HashMap<String, Job>) -> Vec<(String, i32)> {
+        let mut ready = vec![];
+        // A failure cascades down a chain one level per pass
+        loop {
+            let settled: Vec<(String, DependencyStatus)> = jobs.values()
+                .filter(|job| job.state == JobState::Blocked)
+                .filter_map(|job| match Self::dependency_status(jobs, job) {
+                    DependencyStatus::Pending => None,
+                    status => Some((job.id.to_string(), status)),
+                })
+                .collect();
+            if settled.is_empty() {
+                return ready;
+            }
+            
+            for (job_id, status) in settled {
+                let Some(job) = jobs.get_mut(&job_id) else { continue };
+                match status {
+                    DependencyStatus::Met => {
+                        self.transition_job(job, JobState::Queued, "Dependencies completed");
+                        ready.push((job_id, job.descriptor.policy.priority));
+                    }
+                    DependencyStatus::Failed(dep) => {
+                        let message = format!("Dependency {} did not complete", dep);
+                        self.transition_job(job, JobState::DependencyFailed, &message);
+                        info!("Job {} will not run: {}", job_id, message);
+                    }
+                    DependencyStatus::Pending => {}
+                }
+            }
+        }
+    }
+    
+    /// Put jobs on `pending_queue`. Call without holding `jobs`;
+    /// schedule_cycle takes the queue first.
+    fn enqueue(&self, ready: Vec<(String, i32)>) {
+        if ready.is_empty() {
+            return;
+        }
+        let mut queue = self.pending_queue.write();
+        for (job_id, priority) in ready {
+            queue.push(job_id, priority);
+        }
+    }
+    
+    /// Record a line of job output, sanitized, and forward it to followers
+    pub fn append_job_log(&self, job_id: &str, stream: LogStream, text: &str) -> Result<()> {
+        let jobs = self.jobs.read();
+        if !jobs.contains_key(job_id) {
+            return Err(Error::Job(format!("Job not found: {}", job_id)));
+        }
         
+        let line = LogLine {
+            timestamp: Utc::now(),
+            stream,
+            text: sanitize_log_message(text),
+        };
+        let lagged = self.job_logs.lock().entry(job_id.to_string()).or_default().push(line);
+        if lagged > 0 {
+            let total = self.lagged_log_lines.fetch_add(lagged, Ordering::Relaxed) + lagged;
+            debug!("Job {} log follower lagging, {} lines dropped so far", job_id, total);
+        }
+        Ok(())
+    }
+    
+    /// Stream a job's output, starting with up to `tail_lines` of the most
+    /// recent retained lines.
+    ///
+    /// With `follow`, new lines keep arriving until the job finishes; the
+    /// stream ends right after the backfill otherwise, or if the job has
+    /// already finished. Lines that arrive while a follower's channel is
+    /// full are dropped for it and counted in
+    /// [`Scheduler::lagged_log_lines`].
+    pub fn subscribe_job_logs(&self, job_id: &str, tail_lines: usize, follow: bool) -> Result<mpsc::Receiver<LogLine>> {
+        // Held so the job can't finish between the check and registering
+        let jobs = self.jobs.read();
+        let job = jobs.get(job_id)
+            .ok_or_else(|| Error::Job(format!("Job not found: {}", job_id)))?;
+        let follow = follow && !Self::is_finished(job.state);
+        
+        Ok(self.job_logs.lock().entry(job_id.to_string()).or_default().subscribe(tail_lines, follow))
+    }
+    
+    /// Number of log lines dropped for slow followers
+    pub fn lagged_log_lines(&self) -> u64 {
+        self.lagged_log_lines.load(Ordering::Relaxed)
+    }
+    
+    /// Submit a job
+    ///
+    /// A job with `depends_on` stays `Blocked` until all of its
+    /// dependencies complete, or ends as `DependencyFailed` if any of them
+    /// doesn't. Dependencies must name jobs already submitted, and may not
+    /// form a cycle.
+    pub fn submit(&self, mut job: Job) -> Result<String> {
+        let job_id = job.id.to_string();
         let priority = job.descriptor.policy.priority;
         
-        {
+        let queued = {
+            // Hold the write lock across the check so concurrent submits
+            // can't both squeeze under the quota
             let mut jobs = self.jobs.write();
+            self.check_quota(&jobs, &job)?;
+            Self::check_dependencies(&jobs, &job)?;
+            
+            match Self::dependency_status(&jobs, &job) {
+                DependencyStatus::Met => {
+                    self.transition_job(&mut job, JobState::Queued, "Submitted to scheduler");
+                }
+                DependencyStatus::Pending => {
+                    self.transition_job(&mut job, JobState::Blocked, "Waiting for dependencies");
+                }
+                DependencyStatus::Failed(dep) => {
+                    let message = format!("Dependency {} did not complete", dep);
+                    self.transition_job(&mut job, JobState::DependencyFailed, &message);
+                }
+            }
+            let queued = job.state == JobState::Queued;
             jobs.insert(job_id.clone(), job);
-        }
+            queued
+        };
         
-        {
-            let mut queue = self.pending_queue.write();
-            queue.push(job_id.clone(), priority);
+        if queued {
+            self.enqueue(vec![(job_id.clone(), priority)]);
         }
         
         info!("Job {} submitted with priority {}", job_id, priority);
@@ -101,15 +426,15 @@ impl Scheduler {
         
         if let Some(job) = jobs.get_mut(job_id) {
             match job.state {
-                JobState::Pending | JobState::Queued | JobState::Scheduled => {
-                    job.transition(JobState::Cancelled, reason);
+                JobState::Pending | JobState::Blocked | JobState::Queued | JobState::Scheduled => {
+                    self.transition_job(job, JobState::Cancelled, reason);
                     
                     // Remove from queue
                     let mut queue = self.pending_queue.write();
                     queue.remove(job_id);
                 }
                 JobState::Running => {
-                    job.transition(JobState::Cancelled, reason);
+                    self.transition_job(job, JobState::Cancelled, reason);
                     
                     // Release resources
                     for node_id in &job.allocated_nodes {
@@ -126,12 +451,47 @@ impl Scheduler {
             }
             
             info!("Job {} cancelled: {}", job_id, reason);
+            let ready = self.settle_blocked_jobs(&mut jobs);
+            drop(jobs);
+            self.enqueue(ready);
             Ok(())
         } else {
             Err(Error::Job(format!("Job not found: {}", job_id)))
         }
     }
     
+    /// Cancel every unfinished job in a project. Returns how many were
+    /// cancelled and how many had already finished, so calling it again
+    /// just reports the same jobs as finished.
+    pub fn cancel_project(&self, project_id: &str, reason: &str) -> (usize, usize) {
+        let mut jobs = self.jobs.write();
+        let mut cancelled = vec![];
+        let mut already_finished = 0;
+        
+        for job in jobs.values_mut().filter(|job| job.descriptor.project_id == project_id) {
+            if Self::is_finished(job.state) {
+                already_finished += 1;
+                continue;
+            }
+            self.transition_job(job, JobState::Cancelled, reason);
+            cancelled.push(job.id.to_string());
+        }
+        
+        info!("Cancelled {} jobs in project {}: {}", cancelled.len(), project_id, reason);
+        let ready = self.settle_blocked_jobs(&mut jobs);
+        // `schedule_cycle` locks the queue before the jobs, so release the
+        // jobs before touching the queue
+        drop(jobs);
+        if !cancelled.is_empty() {
+            let mut queue = self.pending_queue.write();
+            for job_id in &cancelled {
+                queue.remove(job_id);
+            }
+        }
+        self.enqueue(ready);
+        (cancelled.len(), already_finished)
+    }
+    
     /// Run one scheduling cycle
     pub fn schedule_cycle(&self) -> Vec<SchedulingDecision> {
         let mut decisions = vec![];
@@ -139,6 +499,7 @@ impl Scheduler {
         let mut jobs = self.jobs.write();
         
         let mut to_remove = vec![];
+        let now = Utc::now();
         
         // Process jobs in priority order
         for (processed, (job_id, _priority)) in queue.iter().enumerate() {
@@ -147,9 +508,12 @@ impl Scheduler {
             }
             
             if let Some(job) = jobs.get_mut(job_id) {
+                if job.in_backoff(now) {
+                    continue;
+                }
                 if let Some(decision) = self.try_schedule_job(job) {
                     // Apply allocation
-                    job.transition(JobState::Scheduled, "Resources allocated");
+                    self.transition_job(job, JobState::Scheduled, "Resources allocated");
                     job.allocated_nodes = decision.allocations.keys().cloned().collect();
                     job.allocated_gpus = decision.allocations.clone();
                     
@@ -174,6 +538,39 @@ impl Scheduler {
         decisions
     }
     
+    /// Where `job` would be placed if it were scheduled now, without
+    /// queueing it or touching any scheduler state.
+    ///
+    /// Fails with `QuotaExceeded` if the job would be rejected on submit,
+    /// or `Scheduling` describing why it doesn't fit the cluster as it is.
+    /// Jobs already queued are not taken into account.
+    pub fn dry_run(&self, job: &Job) -> Result<SchedulingDecision> {
+        self.check_quota(&self.jobs.read(), job)?;
+        if let Some(decision) = self.try_schedule_job(job) {
+            return Ok(decision);
+        }
+        
+        let required = job.descriptor.resources.gpu_count as usize;
+        let locality = &job.descriptor.locality;
+        let reason = if locality.has_node_selector()
+            && !self.nodes.healthy_nodes().iter().any(|node| locality.matches_labels(&node.labels))
+        {
+            "no healthy node matches the node selector".to_string()
+        } else if required == 0 {
+            "no healthy nodes".to_string()
+        } else {
+            let free: usize = self.eligible_nodes(job, self.nodes.nodes_with_available_gpus(1)).iter()
+                .map(|node| node.available_gpus())
+                .sum();
+            if free < required {
+                format!("needs {} GPUs but only {} are free", required, free)
+            } else {
+                format!("{} GPUs are free but cannot be allocated together", free)
+            }
+        };
+        Err(Error::Scheduling(format!("Job {} does not fit: {}", job.descriptor.name, reason)))
+    }
+    
     /// Try to schedule a single job
     fn try_schedule_job(&self, job: &Job) -> Option<SchedulingDecision> {
         let required_gpus = job.descriptor.resources.gpu_count as usize;
@@ -184,7 +581,7 @@ impl Scheduler {
         }
         
         // Get candidate nodes
-        let candidates = self.nodes.nodes_with_available_gpus(1);
+        let candidates = self.eligible_nodes(job, self.nodes.nodes_with_available_gpus(1));
         
         if candidates.is_empty() {
             debug!("No nodes with available GPUs for job {}", job.id);
@@ -200,6 +597,14 @@ impl Scheduler {
         self.spread_schedule(job, &candidates, required_gpus)
     }
     
+    /// Drop nodes whose labels don't satisfy the job's node selector
+    fn eligible_nodes(&self, job: &Job, nodes: Vec<Node>) -> Vec<Node> {
+        let locality = &job.descriptor.locality;
+        nodes.into_iter()
+            .filter(|node| locality.matches_labels(&node.labels))
+            .collect()
+    }
+    
     /// Gang scheduling: all or nothing allocation
     fn gang_schedule(
         &self,
@@ -289,7 +694,7 @@ impl Scheduler {
     
     /// Schedule CPU-only job
     fn schedule_cpu_job(&self, job: &Job) -> Option<SchedulingDecision> {
-        let nodes = self.nodes.healthy_nodes();
+        let nodes = self.eligible_nodes(job, self.nodes.healthy_nodes());
         
         nodes.first().map(|node| SchedulingDecision {
                 job_id: job.id.to_string(),
@@ -326,6 +731,7 @@ impl Scheduler {
     pub fn cleanup_zombie_jobs(&self) -> usize {
         let mut cleaned = 0;
         let now = chrono::Utc::now();
+        let mut requeued = vec![];
         let mut jobs = self.jobs.write();
         
         for job in jobs.values_mut() {
@@ -338,7 +744,8 @@ impl Scheduler {
                 if let Some(start_time) = job.start_time {
                     let elapsed = (now - start_time).num_seconds() as u64;
                     if elapsed > self.config.job_timeout_secs {
-                        job.transition(
+                        self.transition_job(
+                            job,
                             JobState::Timeout,
                             &format!("Job exceeded timeout of {} seconds", self.config.job_timeout_secs)
                         );
@@ -359,14 +766,16 @@ impl Scheduler {
             }
             
             if any_dead {
-                job.transition(
-                    JobState::Failed,
-                    "Allocated node(s) became unhealthy"
-                );
-                info!("Job {} failed due to unhealthy node", job.id);
+                self.handle_failure(job, JobFailure::NodeLost, "Allocated node(s) became unhealthy");
+                if job.state == JobState::Queued {
+                    requeued.push((job.id.to_string(), job.descriptor.policy.priority));
+                }
                 cleaned += 1;
             }
         }
+        requeued.extend(self.settle_blocked_jobs(&mut jobs));
+        drop(jobs);
+        self.enqueue(requeued);
         
         if cleaned > 0 {
             info!("Cleaned up {} zombie jobs", cleaned);
@@ -381,7 +790,7 @@ impl Scheduler {
         
         if let Some(job) = jobs.get_mut(job_id) {
             // Note: transition() already sets start_time for JobState::Running
-            job.transition(JobState::Running, "Job started on node");
+            self.transition_job(job, JobState::Running, "Job started on node");
             info!("Job {} marked as running", job_id);
             Ok(())
         } else {
@@ -395,14 +804,74 @@ impl Scheduler {
         
         if let Some(job) = jobs.get_mut(job_id) {
             let new_state = if success { JobState::Completed } else { JobState::Failed };
-            job.transition(new_state, message);
+            self.transition_job(job, new_state, message);
             info!("Job {} marked as {:?}: {}", job_id, new_state, message);
+            let ready = self.settle_blocked_jobs(&mut jobs);
+            drop(jobs);
+            self.enqueue(ready);
             Ok(())
         } else {
             Err(Error::Job(format!("Job not found: {}", job_id)))
         }
     }
     
+    /// Report a scheduled or running job as failed.
+    ///
+    /// Retryable failures re-queue the job, after its policy's backoff,
+    /// until it runs out of attempts; everything else fails it for good.
+    /// Returns the job's new state.
+    pub fn fail_job(&self, job_id: &str, failure: JobFailure, message: &str) -> Result<JobState> {
+        let (state, ready) = {
+            let mut jobs = self.jobs.write();
+            let job = jobs.get_mut(job_id)
+                .ok_or_else(|| Error::Job(format!("Job not found: {}", job_id)))?;
+            if !matches!(job.state, JobState::Scheduled | JobState::Running) {
+                return Err(Error::Job(format!(
+                    "Cannot fail job in state {:?}", job.state
+                )));
+            }
+            self.handle_failure(job, failure, message);
+            let state = job.state;
+            let mut ready = vec![];
+            if state == JobState::Queued {
+                ready.push((job_id.to_string(), job.descriptor.policy.priority));
+            }
+            ready.extend(self.settle_blocked_jobs(&mut jobs));
+            (state, ready)
+        };
+        
+        self.enqueue(ready);
+        Ok(state)
+    }
+    
+    /// Re-queue (state only) or fail a job. The caller must put re-queued
+    /// jobs back on `pending_queue`.
+    fn handle_failure(&self, job: &mut Job, failure: JobFailure, message: &str) {
+        if !failure.is_retryable() || !job.can_retry() {
+            let attempts = job.attempt();
+            self.transition_job(job, JobState::Failed, message);
+            info!("Job {} failed after {} attempt(s) ({:?}): {}", job.id, attempts, failure, message);
+            return;
+        }
+        
+        job.retry_count += 1;
+        let retry = job.descriptor.policy.retry;
+        let delay = retry.backoff(job.retry_count);
+        job.retry_after = Some(
+            chrono::Duration::from_std(delay).ok()
+                .and_then(|delay| Utc::now().checked_add_signed(delay))
+                .unwrap_or(DateTime::<Utc>::MAX_UTC),
+        );
+        job.allocated_nodes.clear();
+        job.allocated_gpus.clear();
+        let note = format!(
+            "Retrying as attempt {}/{} in {:?} after {:?}: {}",
+            job.attempt(), retry.max_attempts, delay, failure, message
+        );
+        self.transition_job(job, JobState::Queued, &note);
+        warn!("Job {}: {}", job.id, note);
+    }
+    
     /// Get configuration
     pub fn config(&self) -> &SchedulerConfig {
         &self.config
@@ -412,8 +881,9 @@ impl Scheduler {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::job::JobDescriptor;
+    use crate::job::{JobDescriptor, RetryPolicy};
     use crate::node::{GpuDevice, NodeTopology};
+    use std::time::Duration;
     
     fn create_test_node(id: &str, gpu_count: usize) -> Node {
         let gpus: Vec<GpuDevice> = (0..gpu_count)
@@ -472,6 +942,7 @@ mod tests {
             policy: Default::default(),
             labels: HashMap::new(),
             annotations: HashMap::new(),
+            depends_on: vec![],
         };
         
         let job = Job::new(descriptor);
@@ -510,6 +981,7 @@ mod tests {
             policy: Default::default(),
             labels: HashMap::new(),
             annotations: HashMap::new(),
+            depends_on: vec![],
         };
         
         let job = Job::new(descriptor);
@@ -531,7 +1003,7 @@ mod tests {
     #[test]
     fn test_scheduler_cpu_job() {
         let registry = Arc::new(NodeRegistry::new(60));
-        registry.register(create_test_node("node-1", 0)).unwrap();
+        registry.register(create_test_node("node-1", 4)).unwrap();
         
         let scheduler = Scheduler::new(registry, SchedulerConfig::default());
         
@@ -553,6 +1025,7 @@ mod tests {
             policy: Default::default(),
             labels: HashMap::new(),
             annotations: HashMap::new(),
+            depends_on: vec![],
         };
         
         let job = Job::new(descriptor);
@@ -597,6 +1070,7 @@ mod tests {
             },
             labels: HashMap::new(),
             annotations: HashMap::new(),
+            depends_on: vec![],
         };
         
         let job = Job::new(descriptor);
@@ -642,6 +1116,7 @@ mod tests {
             },
             labels: HashMap::new(),
             annotations: HashMap::new(),
+            depends_on: vec![],
         });
         scheduler.submit(low_job).unwrap();
         
@@ -665,6 +1140,7 @@ mod tests {
             },
             labels: HashMap::new(),
             annotations: HashMap::new(),
+            depends_on: vec![],
         });
         scheduler.submit(high_job).unwrap();
         
@@ -707,6 +1183,7 @@ mod tests {
             policy: Default::default(),
             labels: HashMap::new(),
             annotations: HashMap::new(),
+            depends_on: vec![],
         });
         
         let job_id = scheduler.submit(job).unwrap();
@@ -757,6 +1234,7 @@ mod tests {
             policy: Default::default(),
             labels: HashMap::new(),
             annotations: HashMap::new(),
+            depends_on: vec![],
         });
         
         scheduler.submit(job).unwrap();
@@ -799,6 +1277,7 @@ mod tests {
                 policy: Default::default(),
                 labels: HashMap::new(),
                 annotations: HashMap::new(),
+                depends_on: vec![],
             });
             scheduler.submit(job).unwrap();
         }
@@ -887,6 +1366,7 @@ mod tests {
             policy: Default::default(),
             labels: HashMap::new(),
             annotations: HashMap::new(),
+            depends_on: vec![],
         });
         
         let job_id = scheduler.submit(job).unwrap();
@@ -943,6 +1423,7 @@ mod tests {
             },
             labels: HashMap::new(),
             annotations: HashMap::new(),
+            depends_on: vec![],
         });
         
         scheduler.submit(job).unwrap();
@@ -986,6 +1467,7 @@ mod tests {
             },
             labels: HashMap::new(),
             annotations: HashMap::new(),
+            depends_on: vec![],
         });
         
         scheduler.submit(job).unwrap();
@@ -1030,6 +1512,7 @@ mod tests {
             },
             labels: HashMap::new(),
             annotations: HashMap::new(),
+            depends_on: vec![],
         });
         
         scheduler.submit(job).unwrap();
@@ -1074,6 +1557,7 @@ mod tests {
             policy: Default::default(),
             labels: HashMap::new(),
             annotations: HashMap::new(),
+            depends_on: vec![],
         });
         
         scheduler.submit(job).unwrap();
@@ -1116,6 +1600,7 @@ mod tests {
             policy: Default::default(),
             labels: HashMap::new(),
             annotations: HashMap::new(),
+            depends_on: vec![],
         });
         
         let job_id = scheduler.submit(job).unwrap();
@@ -1145,7 +1630,7 @@ mod tests {
             "Job must transition to Timeout state");
     }
     
-    /// Test cleanup with unhealthy node
+    /// Test cleanup with unhealthy node, for a job without retries left
     /// Kills mutations: !is_node_healthy, any_dead check
     #[test]
     fn test_cleanup_zombie_jobs_unhealthy_node() {
@@ -1171,9 +1656,13 @@ mod tests {
                 ..Default::default()
             },
             locality: Default::default(),
-            policy: Default::default(),
+            policy: crate::job::SchedulingPolicy {
+                retry: RetryPolicy { max_attempts: 1, ..Default::default() },
+                ..Default::default()
+            },
             labels: HashMap::new(),
             annotations: HashMap::new(),
+            depends_on: vec![],
         });
         
         let job_id = scheduler.submit(job).unwrap();
@@ -1225,6 +1714,7 @@ mod tests {
                 policy: Default::default(),
                 labels: HashMap::new(),
                 annotations: HashMap::new(),
+                depends_on: vec![],
             });
             job_ids.push(scheduler.submit(job).unwrap());
         }
@@ -1281,6 +1771,7 @@ mod tests {
             policy: Default::default(),
             labels: HashMap::new(),
             annotations: HashMap::new(),
+            depends_on: vec![],
         });
         scheduler.submit(job1).unwrap();
         
@@ -1301,6 +1792,7 @@ mod tests {
             policy: Default::default(),
             labels: HashMap::new(),
             annotations: HashMap::new(),
+            depends_on: vec![],
         });
         scheduler.submit(job2).unwrap();
         scheduler.schedule_cycle();
@@ -1310,4 +1802,380 @@ mod tests {
         assert_eq!(cleaned, 0,
             "cleanup_zombie_jobs must skip non-Running jobs");
     }
+    
+    fn event_test_job(name: &str) -> Job {
+        Job::new(JobDescriptor {
+            name: name.to_string(),
+            user_id: "user1".to_string(),
+            project_id: "project1".to_string(),
+            command: "echo".to_string(),
+            arguments: vec![],
+            environment: HashMap::new(),
+            working_directory: "/app".to_string(),
+            resources: Default::default(),
+            locality: Default::default(),
+            policy: Default::default(),
+            labels: HashMap::new(),
+            annotations: HashMap::new(),
+            depends_on: vec![],
+        })
+    }
+    
+    #[test]
+    fn test_subscribe_jobs_receives_queued_event() {
+        let registry = Arc::new(NodeRegistry::new(60));
+        let scheduler = Scheduler::new(registry, SchedulerConfig::default());
+        let mut events = scheduler.subscribe_jobs();
+        
+        let job_id = scheduler.submit(event_test_job("event-job")).unwrap();
+        
+        let event = events.try_recv().expect("QUEUED event should be delivered");
+        assert_eq!(event.job_id, job_id);
+        assert_eq!(event.old_state, JobState::Pending);
+        assert_eq!(event.new_state, JobState::Queued);
+        
+        scheduler.cancel(&job_id, "done").unwrap();
+        let event = events.try_recv().unwrap();
+        assert_eq!(event.old_state, JobState::Queued);
+        assert_eq!(event.new_state, JobState::Cancelled);
+        assert!(events.try_recv().is_err());
+    }
+    
+    #[test]
+    fn test_slow_subscriber_is_counted_not_blocking() {
+        let registry = Arc::new(NodeRegistry::new(60));
+        let scheduler = Scheduler::new(registry, SchedulerConfig::default());
+        let slow = scheduler.subscribe_jobs();
+        
+        for i in 0..JOB_EVENT_CHANNEL_CAPACITY + 3 {
+            scheduler.submit(event_test_job(&format!("job-{}", i))).unwrap();
+        }
+        
+        assert_eq!(scheduler.lagged_events(), 3);
+        
+        // Dropped subscribers are pruned on the next event
+        drop(slow);
+        scheduler.submit(event_test_job("after-drop")).unwrap();
+        assert_eq!(scheduler.lagged_events(), 3);
+        assert!(scheduler.subscribers.lock().is_empty());
+    }
+    
+    #[test]
+    fn test_project_quota_rejects_then_admits() {
+        let registry = Arc::new(NodeRegistry::new(60));
+        registry.register(create_test_node("node-1", 8)).unwrap();
+        let scheduler = Scheduler::new(registry, SchedulerConfig::default());
+        
+        scheduler.set_project_quota("project1", ProjectQuota {
+            max_gpus: 4,
+            max_concurrent_jobs: 2,
+        });
+        
+        let gpu_job = |gpus: u32| {
+            let mut job = event_test_job("quota-job");
+            job.descriptor.resources.gpu_count = gpus;
+            job
+        };
+        
+        // 3 GPUs queued, 1 more still fits
+        let first = scheduler.submit(gpu_job(3)).unwrap();
+        assert!(matches!(scheduler.submit(gpu_job(2)), Err(Error::QuotaExceeded(_))));
+        
+        // Usage counts running jobs too
+        scheduler.schedule_cycle();
+        scheduler.mark_job_started(&first).unwrap();
+        let second = scheduler.submit(gpu_job(1)).unwrap();
+        
+        // At the concurrent job limit
+        let err = scheduler.submit(gpu_job(0)).unwrap_err();
+        assert!(err.to_string().contains("active jobs"));
+        
+        // Other projects are unaffected
+        let mut other = gpu_job(8);
+        other.descriptor.project_id = "project2".to_string();
+        assert!(scheduler.submit(other).is_ok());
+        
+        // Finishing a job frees its share
+        scheduler.mark_job_completed(&first, true, "done").unwrap();
+        assert!(scheduler.submit(gpu_job(3)).is_ok());
+        
+        scheduler.cancel(&second, "test").unwrap();
+        scheduler.remove_project_quota("project1");
+        assert!(scheduler.project_quota("project1").is_none());
+        assert!(scheduler.submit(gpu_job(16)).is_ok());
+    }
+    
+    #[test]
+    fn test_project_quota_counts_blocked_jobs() {
+        let registry = Arc::new(NodeRegistry::new(60));
+        registry.register(create_test_node("node-1", 8)).unwrap();
+        let scheduler = Scheduler::new(registry, SchedulerConfig::default());
+        scheduler.set_project_quota("project1", ProjectQuota {
+            max_gpus: 8,
+            max_concurrent_jobs: 3,
+        });
+        
+        let parent = scheduler.submit(event_test_job("parent")).unwrap();
+        scheduler.schedule_cycle();
+        scheduler.mark_job_started(&parent).unwrap();
+        
+        // Dependents wait blocked but still take their share of the quota
+        let dependent = || {
+            let mut job = event_test_job("dependent");
+            job.descriptor.depends_on = vec![parent.clone()];
+            job
+        };
+        for _ in 0..2 {
+            scheduler.submit(dependent()).unwrap();
+        }
+        assert!(matches!(scheduler.submit(dependent()), Err(Error::QuotaExceeded(_))));
+        
+        // Releasing them stays within the limit
+        scheduler.mark_job_completed(&parent, true, "done").unwrap();
+        assert_eq!(scheduler.queue_size(), 2);
+        let (_, active_jobs) = Scheduler::project_usage(&scheduler.jobs.read(), "project1");
+        assert!(active_jobs <= 3);
+    }
+    
+    fn retry_test_job(max_attempts: u32) -> Job {
+        let mut job = event_test_job("flaky");
+        job.descriptor.resources.gpu_count = 1;
+        job.descriptor.policy.retry = RetryPolicy {
+            max_attempts,
+            backoff_base: Duration::from_secs(60),
+            backoff_max: Duration::from_secs(90),
+        };
+        job
+    }
+    
+    /// Pretend the job's backoff has already elapsed
+    fn skip_backoff(scheduler: &Scheduler, job_id: &str) {
+        scheduler.jobs.write().get_mut(job_id).unwrap().retry_after = Some(Utc::now());
+    }
+    
+    #[test]
+    fn test_retry_with_backoff_until_attempts_exhausted() {
+        let registry = Arc::new(NodeRegistry::new(60));
+        registry.register(create_test_node("node-1", 4)).unwrap();
+        let scheduler = Scheduler::new(registry, SchedulerConfig::default());
+        
+        let job_id = scheduler.submit(retry_test_job(3)).unwrap();
+        assert_eq!(scheduler.schedule_cycle().len(), 1);
+        scheduler.mark_job_started(&job_id).unwrap();
+        
+        // 60s, then 120s capped at 90s
+        for (retry, backoff_secs) in [(1, 60), (2, 90)] {
+            let failed_at = Utc::now();
+            let state = scheduler.fail_job(&job_id, JobFailure::NodeLost, "node-1 lost").unwrap();
+            assert_eq!(state, JobState::Queued);
+            
+            let job = scheduler.get_job(&job_id).unwrap();
+            assert_eq!(job.retry_count, retry);
+            assert_eq!(job.attempt(), retry + 1);
+            assert!(job.allocated_nodes.is_empty(), "Allocation should be released");
+            assert!(job.message.contains(&format!("attempt {}/3", retry + 1)), "{}", job.message);
+            let delay = (job.retry_after.unwrap() - failed_at).num_seconds();
+            assert!((backoff_secs - 1..=backoff_secs).contains(&delay), "retry {} waited {}s", retry, delay);
+            
+            // Not scheduled again until the backoff elapses
+            assert!(scheduler.schedule_cycle().is_empty());
+            assert_eq!(scheduler.queue_size(), 1);
+            skip_backoff(&scheduler, &job_id);
+            assert_eq!(scheduler.schedule_cycle().len(), 1);
+        }
+        
+        // Third attempt was the last
+        let state = scheduler.fail_job(&job_id, JobFailure::NodeLost, "node-1 lost").unwrap();
+        assert_eq!(state, JobState::Failed);
+        assert_eq!(scheduler.get_job(&job_id).unwrap().attempt(), 3);
+        assert_eq!(scheduler.queue_size(), 0);
+        assert!(scheduler.fail_job(&job_id, JobFailure::NodeLost, "again").is_err());
+    }
+    
+    #[test]
+    fn test_non_retryable_failure_is_terminal() {
+        let registry = Arc::new(NodeRegistry::new(60));
+        registry.register(create_test_node("node-1", 4)).unwrap();
+        let scheduler = Scheduler::new(registry, SchedulerConfig::default());
+        
+        for failure in [JobFailure::Validation, JobFailure::OutOfMemory, JobFailure::Application] {
+            let job_id = scheduler.submit(retry_test_job(5)).unwrap();
+            scheduler.schedule_cycle();
+            assert_eq!(scheduler.fail_job(&job_id, failure, "bad").unwrap(), JobState::Failed);
+            assert_eq!(scheduler.get_job(&job_id).unwrap().retry_count, 0);
+        }
+        assert_eq!(scheduler.queue_size(), 0);
+        
+        // Only scheduled or running jobs can fail
+        let queued = scheduler.submit(retry_test_job(5)).unwrap();
+        assert!(scheduler.fail_job(&queued, JobFailure::NodeLost, "x").is_err());
+        assert!(scheduler.fail_job("missing", JobFailure::NodeLost, "x").is_err());
+    }
+    
+    #[test]
+    fn test_cleanup_requeues_jobs_on_lost_nodes() {
+        let registry = Arc::new(NodeRegistry::new(60));
+        registry.register(create_test_node("node-1", 4)).unwrap();
+        let scheduler = Scheduler::new(registry.clone(), SchedulerConfig {
+            job_timeout_secs: 0,
+            ..Default::default()
+        });
+        
+        let job_id = scheduler.submit(retry_test_job(2)).unwrap();
+        scheduler.schedule_cycle();
+        scheduler.mark_job_started(&job_id).unwrap();
+        registry.deregister("node-1").unwrap();
+        
+        assert_eq!(scheduler.cleanup_zombie_jobs(), 1);
+        let job = scheduler.get_job(&job_id).unwrap();
+        assert_eq!(job.state, JobState::Queued);
+        assert_eq!(job.attempt(), 2);
+        assert_eq!(scheduler.queue_size(), 1);
+    }
+    
+    #[test]
+    fn test_node_selector_picks_matching_node() {
+        use crate::job::{LabelOperator, LabelRequirement};
+        
+        let registry = Arc::new(NodeRegistry::new(60));
+        let mut a100 = create_test_node("node-a100", 4);
+        a100.labels = HashMap::from([
+            ("gpu".to_string(), "a100".to_string()),
+            ("zone".to_string(), "us-east-1a".to_string()),
+        ]);
+        let mut t4 = create_test_node("node-t4", 4);
+        t4.labels = HashMap::from([("gpu".to_string(), "t4".to_string())]);
+        registry.register(t4).unwrap();
+        registry.register(a100).unwrap();
+        let scheduler = Scheduler::new(registry, SchedulerConfig::default());
+        
+        let selected = |selector: &[(&str, &str)], expressions: Vec<LabelRequirement>| {
+            let mut job = event_test_job("selector-job");
+            job.descriptor.resources.gpu_count = 1;
+            job.descriptor.locality.node_selector = selector.iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            job.descriptor.locality.node_selector_expressions = expressions;
+            job
+        };
+        let placed_on = |job: &Job| -> Vec<String> {
+            scheduler.dry_run(job).unwrap().allocations.into_keys().collect()
+        };
+        
+        assert_eq!(placed_on(&selected(&[("gpu", "a100")], vec![])), ["node-a100"]);
+        assert_eq!(placed_on(&selected(&[("gpu", "t4")], vec![])), ["node-t4"]);
+        
+        let not_a100 = LabelRequirement {
+            key: "gpu".to_string(),
+            operator: LabelOperator::NotIn,
+            values: vec!["a100".to_string()],
+        };
+        assert_eq!(placed_on(&selected(&[], vec![not_a100])), ["node-t4"]);
+        let has_zone = LabelRequirement {
+            key: "zone".to_string(),
+            operator: LabelOperator::Exists,
+            values: vec![],
+        };
+        assert_eq!(placed_on(&selected(&[], vec![has_zone])), ["node-a100"]);
+        
+        // Labels must be a superset of the selector
+        let err = scheduler.dry_run(&selected(&[("gpu", "t4"), ("zone", "us-east-1a")], vec![])).unwrap_err();
+        assert!(err.to_string().contains("no healthy node matches the node selector"), "{}", err);
+        
+        let job_id = scheduler.submit(selected(&[("gpu", "a100")], vec![])).unwrap();
+        scheduler.schedule_cycle();
+        assert_eq!(scheduler.get_job(&job_id).unwrap().allocated_nodes, ["node-a100"]);
+    }
+    
+    fn dependent_job(name: &str, depends_on: &[&str]) -> Job {
+        let mut job = event_test_job(name);
+        job.descriptor.depends_on = depends_on.iter().map(|id| id.to_string()).collect();
+        job
+    }
+    
+    /// Schedule, start and finish the only queued job
+    fn run_next(scheduler: &Scheduler, job_id: &str, success: bool) {
+        let decisions = scheduler.schedule_cycle();
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(decisions[0].job_id, job_id);
+        scheduler.mark_job_started(job_id).unwrap();
+        scheduler.mark_job_completed(job_id, success, "done").unwrap();
+    }
+    
+    #[test]
+    fn test_dependency_chain_runs_in_order() {
+        let registry = Arc::new(NodeRegistry::new(60));
+        registry.register(create_test_node("node-1", 4)).unwrap();
+        let scheduler = Scheduler::new(registry, SchedulerConfig::default());
+        
+        let preprocess = scheduler.submit(dependent_job("preprocess", &[])).unwrap();
+        let train = scheduler.submit(dependent_job("train", &[&preprocess])).unwrap();
+        let eval = scheduler.submit(dependent_job("eval", &[&train])).unwrap();
+        assert_eq!(scheduler.get_job(&train).unwrap().state, JobState::Blocked);
+        assert_eq!(scheduler.get_job(&eval).unwrap().state, JobState::Blocked);
+        assert_eq!(scheduler.queue_size(), 1, "Blocked jobs aren't queued");
+        
+        run_next(&scheduler, &preprocess, true);
+        assert_eq!(scheduler.get_job(&train).unwrap().state, JobState::Queued);
+        assert_eq!(scheduler.get_job(&eval).unwrap().state, JobState::Blocked);
+        
+        run_next(&scheduler, &train, true);
+        run_next(&scheduler, &eval, true);
+        assert_eq!(scheduler.get_job(&eval).unwrap().state, JobState::Completed);
+        
+        // Dependencies that already completed don't block
+        let report = scheduler.submit(dependent_job("report", &[&preprocess, &eval])).unwrap();
+        assert_eq!(scheduler.get_job(&report).unwrap().state, JobState::Queued);
+    }
+    
+    #[test]
+    fn test_failed_dependency_fails_dependents() {
+        let registry = Arc::new(NodeRegistry::new(60));
+        registry.register(create_test_node("node-1", 4)).unwrap();
+        let scheduler = Scheduler::new(registry, SchedulerConfig::default());
+        
+        let preprocess = scheduler.submit(dependent_job("preprocess", &[])).unwrap();
+        let other = scheduler.submit(dependent_job("other", &[])).unwrap();
+        let train = scheduler.submit(dependent_job("train", &[&preprocess, &other])).unwrap();
+        let eval = scheduler.submit(dependent_job("eval", &[&train])).unwrap();
+        
+        scheduler.cancel(&other, "not needed").unwrap();
+        run_next(&scheduler, &preprocess, true);
+        
+        // The failure cascades down the chain
+        for job_id in [&train, &eval] {
+            let job = scheduler.get_job(job_id).unwrap();
+            assert_eq!(job.state, JobState::DependencyFailed);
+            assert!(job.end_time.is_some());
+        }
+        assert!(scheduler.get_job(&train).unwrap().message.contains(&other));
+        assert_eq!(scheduler.queue_size(), 0);
+        
+        // Submitting against a failed job fails straight away
+        let late = scheduler.submit(dependent_job("late", &[&eval])).unwrap();
+        assert_eq!(scheduler.get_job(&late).unwrap().state, JobState::DependencyFailed);
+    }
+    
+    #[test]
+    fn test_dependency_cycles_rejected() {
+        let registry = Arc::new(NodeRegistry::new(60));
+        let scheduler = Scheduler::new(registry, SchedulerConfig::default());
+        
+        let mut own = dependent_job("self", &[]);
+        own.descriptor.depends_on = vec![own.id.to_string()];
+        assert!(matches!(scheduler.submit(own), Err(Error::Dependency(msg)) if msg.contains("cycle")));
+        
+        let err = scheduler.submit(dependent_job("orphan", &["no-such-job"])).unwrap_err();
+        assert!(matches!(err, Error::Dependency(ref msg) if msg.contains("unknown job no-such-job")), "{}", err);
+        
+        // Re-submitting a job so it depends on its own dependent
+        let first = dependent_job("first", &[]);
+        let first_id = first.id.to_string();
+        scheduler.submit(first.clone()).unwrap();
+        let second = scheduler.submit(dependent_job("second", &[&first_id])).unwrap();
+        let mut again = first;
+        again.descriptor.depends_on = vec![second];
+        assert!(matches!(scheduler.submit(again), Err(Error::Dependency(msg)) if msg.contains("cycle")));
+        assert_eq!(scheduler.queue_size(), 1);
+    }
 }
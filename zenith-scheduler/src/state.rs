@@ -264,6 +264,7 @@ mod tests {
             policy: SchedulingPolicy::default(),
             labels: HashMap::new(),
             annotations: HashMap::new(),
+            depends_on: vec![],
         };
         
         Job::new(descriptor)